@@ -1,22 +1,20 @@
 import argparse
-import time
+import json
 import logging
 import logging.handlers
 import platform
 import requests
 from pathlib import Path
-from .infogather import InfoGather
+from .provider import create_provider
+from .catalog import Catalog, load_catalog
+from .clock import MBTA_TIMEZONE, Clock
 from .config_parser import ConfigParser
-from .display_modes import create_display_mode
-
-PI_PLATFORMS = ("armv7l", "armv6l", "aarch64")
-
-# Conditional import for Raspberry Pi Architectures
-if platform.machine() in PI_PLATFORMS:
-    from .inkytrain import InkyTrain
-    inky_train_cls = InkyTrain
-else:
-    inky_train_cls = None
+from .credentials import redact_logs
+from .display_modes import ProfileModes, create_display_mode
+from .streaming import PredictionStore, PredictionStream
+from .gtfs_static import GTFSStaticStore
+from .renderers import RENDERERS, create_renderer
+from .recording import CATALOG_FILE, RecordingTransport, ReplayFinished, ReplayTransport
 
 # Configuration Constants
 LOG_FILENAME = 'instant.log'
@@ -39,13 +37,33 @@ def setup_logging(log_to_console=True, log_level=logging.INFO):
     main_logger.addHandler(handler)
     return main_logger
 
-def run_display_loop(config, display_mode, ig, it, logger):
-    """Main loop to update the display with transit information."""
+def write_status(path, status):
+    """Replace the status file in one step, so readers never see half of it."""
+    tmp = Path(f"{path}.tmp")
+    tmp.write_text(json.dumps(status, indent=1) + "\n")
+    tmp.replace(path)
+
+def run_display_loop(config, display_mode, ig, it, logger, clock=None, profiles=None,
+                     status_file=None):
+    """
+    Main loop to update the display with transit information. With
+    profiles (a ProfileModes), the display mode follows the time of day.
+    With status_file, the time of the last update and the API budget are
+    written there after each one, for health checks.
+    """
+    clock = clock or Clock()
     consecutive_failures = 0
     max_consecutive_failures = 3
     last_display_data = None
+    requests_made = 0
     
     while True:
+        refresh = config.display.refresh
+        if profiles is not None:
+            profile, mode = profiles.active()
+            if mode is not display_mode:
+                logger.info("Switching to profile %s", profile.name if profile else "default")
+                display_mode = mode
         try:
             # Gather data using the display mode
             logger.debug("Gathering transit data...")
@@ -71,6 +89,30 @@ def run_display_loop(config, display_mode, ig, it, logger):
                 if line.text.strip():  # Skip empty lines
                     logger.debug(f"  {line.text}")
             
+            # Stretch the refresh interval if the API budget would run out
+            budget = ig.rate_budget()
+            if budget is not None:
+                logger.info("API budget: %s", budget)
+                refresh = budget.poll_interval(config.display.refresh, budget.requests - requests_made)
+                requests_made = budget.requests
+                if refresh > config.display.refresh:
+                    logger.info("Slowing updates to every %.0f seconds to stay within the API budget (%s)",
+                                refresh, budget)
+            
+            if status_file is not None:
+                status = {
+                    'updated': clock.now().isoformat(),
+                    'title': display_data.title,
+                    'poll_interval_seconds': refresh,
+                }
+                if budget is not None:
+                    status.update(budget.metrics())
+                try:
+                    write_status(status_file, status)
+                except OSError as e:
+                    # Not a network error; don't back off for it
+                    logger.warning("Could not write status file %s: %s", status_file, e)
+            
         except (requests.exceptions.RequestException, IOError) as err:
             consecutive_failures += 1
             logger.error("Network error occurred (attempt %d/%d): %s", 
@@ -81,16 +123,21 @@ def run_display_loop(config, display_mode, ig, it, logger):
             if consecutive_failures >= max_consecutive_failures:
                 logger.error("Maximum consecutive failures reached. Waiting %d seconds before retry.", wait_time)
             
-            time.sleep(wait_time)
+            clock.sleep(wait_time)
             continue
             
         except Exception as e:
             logger.exception("Unexpected error in display loop:")
-            time.sleep(config.display.refresh)
+            clock.sleep(config.display.refresh)
             continue
         
-        # Wait before next update
-        time.sleep(config.display.refresh)
+        # Wait before next update, waking for the next profile to start or end
+        if profiles is not None:
+            now = clock.now()
+            change = config.next_profile_change(now)
+            if change is not None:
+                refresh = min(refresh, max((change - now).total_seconds(), 1))
+        clock.sleep(refresh)
 
 def run_once(config, display_mode, ig, it, logger):
     """Run the display update once (for testing)."""
@@ -114,6 +161,8 @@ def run_once(config, display_mode, ig, it, logger):
         for line in display_data.lines:
             if line.text.strip():
                 logger.info(f"  {line.text}")
+        for alert in display_data.alerts:
+            logger.info(f"  Alert: {alert}")
                 
     except Exception as e:
         logger.exception("Error during single run:")
@@ -128,6 +177,15 @@ def main():
     # Config-based arguments
     parser.add_argument("--config", type=Path, help="Path to YAML configuration file")
     parser.add_argument("--once", action="store_true", help="Run once instead of continuously")
+    parser.add_argument("--renderer", choices=RENDERERS,
+                       help="Where to draw the display (default: inky on a Raspberry Pi, otherwise none)")
+    recording = parser.add_mutually_exclusive_group()
+    recording.add_argument("--record", type=Path, metavar="DIR",
+                           help="Save every API response to DIR for later replay")
+    recording.add_argument("--replay", type=Path, metavar="DIR",
+                           help="Replay API responses recorded with --record, on the recorded clock")
+    parser.add_argument("--status-file", type=Path, metavar="FILE",
+                       help="Write the last update time and API budget to FILE as JSON after each update")
     parser.add_argument("--log-level", default="INFO", 
                        choices=["DEBUG", "INFO", "WARNING", "ERROR", "CRITICAL"], 
                        help="Set the logging level")
@@ -139,43 +197,121 @@ def main():
     logger = setup_logging(log_to_console=args.once, log_level=log_level)
     
     # Load configuration
-    config_parser = ConfigParser()
+    if args.replay:
+        # Resolve names with the recorded run's catalog rather than the network
+        catalog_path = args.replay / CATALOG_FILE
+        config_parser = ConfigParser(catalog=Catalog.load(catalog_path) if catalog_path.exists() else None)
+    else:
+        config_parser = ConfigParser(catalog_loader=load_catalog)
     try:
         config = config_parser.load_config(config_path=args.config)
     except ValueError as e:
         logger.error(f"Configuration error: {e}")
         parser.print_help()
         return 1
+    redact_logs(logger, config.provider.api_key)
     
     # Create components
-    ig = InfoGather()
-    it = inky_train_cls() if inky_train_cls is not None else None
-    display_mode = create_display_mode(config)
+    gtfs_store = None
+    if config.gtfs is not None:
+        try:
+            gtfs_store = GTFSStaticStore.open(config.gtfs.database, config.gtfs.zip_path)
+        except Exception as e:
+            logger.error(f"Could not load GTFS schedule fallback: {e}")
+    
+    # Everything shown is in the agency's local time, whatever the Pi's timezone
+    tz = gtfs_store.timezone if gtfs_store is not None else MBTA_TIMEZONE
+    clock = Clock(tz)
+    
+    transport = None
+    if args.record:
+        transport = RecordingTransport(args.record, clock)
+        if config_parser.catalog is not None:
+            config_parser.catalog.save(args.record / CATALOG_FILE)
+    elif args.replay:
+        try:
+            transport = ReplayTransport(args.replay, tz)
+        except (OSError, ValueError) as e:
+            logger.error(f"Replay error: {e}")
+            return 1
+        clock = transport.clock
+    
+    try:
+        ig = create_provider(config, gtfs_store, clock, transport)
+    except (ValueError, ImportError) as e:
+        logger.error(f"Provider error: {e}")
+        return 1
+    try:
+        it = create_renderer(args.renderer or config.display.renderer, config)
+    except Exception as e:
+        logger.error(f"Renderer error: {e}")
+        return 1
+    
+    prediction_store = None
+    stream = None
+    if config.streaming and transport is not None:
+        # The stream isn't recorded, so poll instead
+        logger.info('Streaming disabled while recording or replaying')
+    elif config.streaming and config.mode == 'single-station' and config.provider.type == 'mbta-v3':
+        prediction_store = PredictionStore(clock)
+        stream = PredictionStream.for_station(
+            config.station_id,
+            [route.route_id for route in config.routes],
+            prediction_store,
+            api_url=config.provider.api_url,
+            api_key=config.provider.api_key,
+        )
+        stream.start()
+    
+    display_mode = create_display_mode(config, prediction_store, clock)
+    profiles = ProfileModes(config, display_mode, clock) if config.profiles else None
     
     # Log startup info
     logger.info('System: %s', platform.machine())
     logger.info('Starting InstantMBTA')
     logger.info('Mode: %s', config.mode)
-    logger.info('Display enabled: %s', it is not None)
+    logger.info('Provider: %s', config.provider.type)
+    if config.provider.type == 'mbta-v3' and config.provider.api_key is None:
+        logger.warning('No MBTA API key found; using the anonymous rate limit. '
+                       'Set MBTA_API_KEY or provider.api_key to raise it.')
+    logger.info('Renderer: %s', type(it).__name__ if it is not None else 'none')
+    logger.info('Schedule fallback: %s', gtfs_store is not None)
+    if args.record:
+        logger.info('Recording API responses to %s', args.record)
+    elif args.replay:
+        logger.info('Replaying %d API responses from %s, starting %s',
+                    len(transport.records), args.replay, clock.now().isoformat())
     
     if config.mode == 'single-station':
         logger.info('Station: %s (%s)', config.station, config.station_id)
         logger.info('Tracking %d route(s)', len(config.routes))
+        logger.info('Streaming predictions: %s', stream is not None)
     else:
         logger.info('Route: %s (%s)', config.route_name, config.route_id)
         logger.info('From: %s (%s)', config.from_station, config.from_station_id)
         logger.info('To: %s (%s)', config.to_station, config.to_station_id)
+    if profiles is not None:
+        logger.info('Profiles: %s', ', '.join(profile.name for profile in config.profiles))
     
     try:
         if args.once:
+            if profiles is not None:
+                _, display_mode = profiles.active()
             run_once(config, display_mode, ig, it, logger)
         else:
-            run_display_loop(config, display_mode, ig, it, logger)
+            run_display_loop(config, display_mode, ig, it, logger, clock, profiles, args.status_file)
     except KeyboardInterrupt:
         logger.info('Shutting down InstantMBTA')
+    except ReplayFinished:
+        logger.info('Replay finished at %s', clock.now().isoformat())
     except Exception as e:
         logger.exception('Unexpected error occurred:')
         raise
+    finally:
+        if stream is not None:
+            stream.stop()
+        if it is not None:
+            it.close()
 
 if __name__ == '__main__':
     main()
\ No newline at end of file
@@ -0,0 +1,85 @@
+"""Service alerts shared by every transit provider."""
+
+from dataclasses import dataclass, field
+from datetime import datetime
+from typing import Dict, List, Optional, Tuple
+
+# MBTA alert severity runs 0-10. GTFS-Realtime only has four levels, which
+# are mapped onto the same scale so one min_severity setting works for both.
+GTFS_RT_SEVERITY = {
+    'UNKNOWN_SEVERITY': 5,
+    'INFO': 3,
+    'WARNING': 6,
+    'SEVERE': 9,
+}
+
+
+@dataclass
+class Alert:
+    """A service alert affecting one or more routes or stops."""
+    id: str
+    header: str
+    effect: str                   # e.g. 'SHUTTLE', 'DELAY', 'SUSPENSION'
+    severity: int                 # 0 (least) to 10 (most severe)
+    active_period: List[Tuple[Optional[datetime], Optional[datetime]]] = field(default_factory=list)
+    short_header: Optional[str] = None
+    # (route_id, stop_id) pairs; None in either position means "any"
+    informed_entity: List[Tuple[Optional[str], Optional[str]]] = field(default_factory=list)
+
+    def is_active(self, now: Optional[datetime] = None) -> bool:
+        """True if now falls inside any active period (or none are given)."""
+        if not self.active_period:
+            return True
+        now = now or datetime.now().astimezone()
+        for start, end in self.active_period:
+            if (start is None or start <= now) and (end is None or now < end):
+                return True
+        return False
+
+    def affects(self, route_ids: List[str], stop_ids: List[str]) -> bool:
+        """
+        True if any informed entity matches one of the routes at one of the
+        stops. A route-wide entity matches every stop and a stop-wide entity
+        matches every route.
+        """
+        for route, stop in self.informed_entity:
+            if route is None and stop is None:
+                continue
+            if (route is None or route in route_ids) and (stop is None or stop in stop_ids):
+                return True
+        return False
+
+
+def alert_from_resource(item: Dict) -> Alert:
+    """Build an Alert from an MBTA V3 JSON:API alert resource."""
+    attrs = item.get('attributes', {})
+    periods = []
+    for period in attrs.get('active_period') or []:
+        start = period.get('start')
+        end = period.get('end')
+        periods.append((
+            datetime.fromisoformat(start) if start else None,
+            datetime.fromisoformat(end) if end else None,
+        ))
+
+    entities = {
+        (entity.get('route'), entity.get('stop'))
+        for entity in attrs.get('informed_entity') or []
+    }
+    return Alert(
+        id=item.get('id'),
+        header=attrs.get('header') or '',
+        short_header=attrs.get('short_header'),
+        effect=attrs.get('effect') or 'UNKNOWN_EFFECT',
+        severity=attrs.get('severity') or 0,
+        active_period=periods,
+        informed_entity=sorted(entities, key=lambda e: (e[0] or '', e[1] or '')),
+    )
+
+
+def select_alerts(alerts: List[Alert], min_severity: int,
+                  now: Optional[datetime] = None) -> List[Alert]:
+    """Active alerts at or above min_severity, most severe first."""
+    selected = [a for a in alerts if a.severity >= min_severity and a.is_active(now)]
+    selected.sort(key=lambda a: a.severity, reverse=True)
+    return selected
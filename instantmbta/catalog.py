@@ -0,0 +1,323 @@
+"""Station and route catalog used to resolve names in the config file.
+
+The catalog is built from the MBTA V3 `/stops` and `/routes` endpoints, or
+from the GTFS static feed when one is configured, so any station, Commuter
+Rail stop, bus stop or route can be referred to by name. The API version is
+cached on disk because it only changes with the quarterly schedule.
+"""
+
+import difflib
+import json
+import logging
+import re
+import time
+from dataclasses import dataclass
+from pathlib import Path
+from typing import Dict, List, Optional, Set
+
+logger = logging.getLogger('instantmbta.catalog')
+
+CATALOG_CACHE = "instantmbta_catalog.json"
+CATALOG_MAX_AGE = 7 * 24 * 60 * 60  # seconds
+CACHE_VERSION = 1
+
+# Words commonly abbreviated in config files; both sides are normalized to
+# the long form before matching
+ABBREVIATIONS = {
+    'sq': 'square',
+    'ctr': 'center',
+    'ave': 'avenue',
+    'av': 'avenue',
+    'hts': 'heights',
+    'stn': 'station',
+}
+
+# Suffixes people add to names that the MBTA doesn't use, e.g. "Harvard
+# Square" for "Harvard" or "Orange Line" for "Orange"
+OPTIONAL_SUFFIXES = (' square', ' station', ' line')
+
+FUZZY_CUTOFF = 0.6       # Minimum similarity to suggest a name
+FUZZY_ACCEPT = 0.9       # Similarity at which a single match is used as-is
+MAX_SUGGESTIONS = 5
+
+
+class CatalogLookupError(ValueError):
+    """A name that matched nothing, or more than one stop or route."""
+
+    def __init__(self, kind: str, name: str, suggestions: List[str], ambiguous: bool = False):
+        self.name = name
+        self.suggestions = suggestions
+        if ambiguous:
+            message = f"{kind.capitalize()} '{name}' is ambiguous"
+        else:
+            message = f"Unknown {kind} '{name}'"
+        if suggestions:
+            message += f". Did you mean: {', '.join(suggestions)}?"
+        super().__init__(message)
+
+
+def normalize(name: str) -> str:
+    """Lower-case, drop punctuation and expand abbreviations."""
+    name = name.lower().replace('&', ' and ')
+    name = re.sub(r"[^\w/ ]+", ' ', name)
+    words = [ABBREVIATIONS.get(word, word) for word in name.split()]
+    return ' '.join(words)
+
+
+def _variants(name: str) -> List[str]:
+    """The normalized name plus any version without an optional suffix."""
+    key = normalize(name)
+    variants = [key]
+    for suffix in OPTIONAL_SUFFIXES:
+        if key.endswith(suffix) and len(key) > len(suffix):
+            variants.append(key[:-len(suffix)])
+    return variants
+
+
+@dataclass
+class CatalogStop:
+    id: str
+    name: str
+    location_type: int = 0
+    parent_station: Optional[str] = None
+
+
+@dataclass
+class CatalogRoute:
+    id: str
+    short_name: str = ''
+    long_name: str = ''
+    type: Optional[int] = None
+
+    @property
+    def name(self) -> str:
+        return self.long_name or self.short_name or self.id
+
+
+class Catalog:
+    """Name lookups for every stop and route of an agency."""
+
+    def __init__(self, stops: List[CatalogStop], routes: List[CatalogRoute],
+                 created: Optional[float] = None):
+        self.stops = {stop.id: stop for stop in stops}
+        self.routes = {route.id: route for route in routes}
+        self.created = created if created is not None else time.time()
+        self._station_index = self._index_stations()
+        self._route_index = self._index_routes()
+
+    def _index_stations(self) -> Dict[str, Set[str]]:
+        # Only stations and standalone stops are looked up by name; platform
+        # IDs are still accepted as-is
+        index: Dict[str, Set[str]] = {}
+        for stop in self.stops.values():
+            if stop.parent_station:
+                continue
+            for key in _variants(stop.name):
+                index.setdefault(key, set()).add(stop.id)
+
+        # A parent station wins over bus stops that share its name
+        for key, ids in index.items():
+            stations = {i for i in ids if self.stops[i].location_type == 1}
+            if stations and len(stations) < len(ids):
+                index[key] = stations
+        return index
+
+    def _index_routes(self) -> Dict[str, Set[str]]:
+        index: Dict[str, Set[str]] = {}
+        for route in self.routes.values():
+            names = {route.id, route.short_name, route.long_name}
+            for name in filter(None, names):
+                for key in _variants(name):
+                    index.setdefault(key, set()).add(route.id)
+        return index
+
+    @classmethod
+    def from_api(cls, ig) -> 'Catalog':
+        """Build the catalog from the MBTA V3 API using an InfoGather."""
+        stops = [
+            CatalogStop(
+                id=stop.id,
+                name=stop.name or stop.id,
+                location_type=stop.location_type or 0,
+                parent_station=stop.parent_station.id if stop.parent_station else None,
+            )
+            for stop in ig.get_all_stops()
+        ]
+        routes = [
+            CatalogRoute(
+                id=route.id,
+                short_name=route.short_name or '',
+                long_name=route.long_name or '',
+                type=route.type,
+            )
+            for route in ig.get_all_routes()
+        ]
+        return cls(stops, routes)
+
+    @classmethod
+    def from_gtfs(cls, store) -> 'Catalog':
+        """Build the catalog from a GTFSStaticStore."""
+        stops = [
+            CatalogStop(
+                id=row['stop_id'],
+                name=row['stop_name'] or row['stop_id'],
+                location_type=row['location_type'] or 0,
+                parent_station=row['parent_station'],
+            )
+            for row in store.all_stops()
+            if (row['location_type'] or 0) in (0, 1)
+        ]
+        routes = [
+            CatalogRoute(
+                id=row['route_id'],
+                short_name=row['route_short_name'] or '',
+                long_name=row['route_long_name'] or '',
+                type=row['route_type'],
+            )
+            for row in store.all_routes()
+        ]
+        return cls(stops, routes)
+
+    @classmethod
+    def load(cls, path) -> 'Catalog':
+        with open(path, 'r') as f:
+            data = json.load(f)
+        if data.get('version') != CACHE_VERSION:
+            raise ValueError(f"Unsupported catalog cache version in {path}")
+        return cls(
+            [CatalogStop(*row) for row in data['stops']],
+            [CatalogRoute(*row) for row in data['routes']],
+            created=data['created'],
+        )
+
+    def save(self, path):
+        data = {
+            'version': CACHE_VERSION,
+            'created': self.created,
+            'stops': [[s.id, s.name, s.location_type, s.parent_station] for s in self.stops.values()],
+            'routes': [[r.id, r.short_name, r.long_name, r.type] for r in self.routes.values()],
+        }
+        with open(path, 'w') as f:
+            json.dump(data, f)
+
+    def has_stop(self, stop_id: str) -> bool:
+        return stop_id in self.stops
+
+    def has_route(self, route_id: str) -> bool:
+        return all(part in self.routes for part in route_id.split(','))
+
+    def route_type(self, route_id: str) -> Optional[int]:
+        """The type shared by one or more comma separated routes, or None."""
+        types = {self.routes[part].type for part in route_id.split(',') if part in self.routes}
+        return types.pop() if len(types) == 1 else None
+
+    def stop_name(self, stop_id: str) -> Optional[str]:
+        stop = self.stops.get(stop_id)
+        return stop.name if stop else None
+
+    def resolve_station(self, name: str) -> str:
+        """
+        Stop ID for a station name.
+
+        Raises:
+            CatalogLookupError: if the name is unknown or ambiguous
+        """
+        if self.has_stop(name):
+            return name
+        return self._resolve('station', name, self._station_index,
+                             lambda i: self.stops[i].name)
+
+    def resolve_route(self, name: str) -> str:
+        """
+        Route ID for a route name.
+
+        Raises:
+            CatalogLookupError: if the name is unknown or ambiguous
+        """
+        if self.has_route(name):
+            return name
+        return self._resolve('route', name, self._route_index,
+                             lambda i: self.routes[i].name)
+
+    def _resolve(self, kind: str, name: str, index: Dict[str, Set[str]], display_name) -> str:
+        for key in _variants(name):
+            ids = index.get(key)
+            if ids:
+                if len(ids) == 1:
+                    return next(iter(ids))
+                raise CatalogLookupError(kind, name, [
+                    f"{display_name(i)} ({i})" for i in sorted(ids)
+                ][:MAX_SUGGESTIONS], ambiguous=True)
+
+        # Score every close name against each variant of what was written,
+        # best first
+        scores: Dict[str, float] = {}
+        for key in _variants(name):
+            for match in difflib.get_close_matches(key, index.keys(), n=MAX_SUGGESTIONS * 2,
+                                                   cutoff=FUZZY_CUTOFF):
+                ratio = difflib.SequenceMatcher(None, key, match).ratio()
+                scores[match] = max(ratio, scores.get(match, 0))
+        matches = sorted(scores, key=lambda m: scores[m], reverse=True)
+
+        best = [m for m in matches if scores[m] >= FUZZY_ACCEPT]
+        if len(best) == 1 and len(index[best[0]]) == 1:
+            resolved = next(iter(index[best[0]]))
+            logger.warning("Using %s '%s' for '%s'", kind, display_name(resolved), name)
+            return resolved
+
+        suggestions = []
+        for match in matches:
+            for i in sorted(index[match]):
+                suggestion = display_name(i)
+                if suggestion not in suggestions:
+                    suggestions.append(suggestion)
+        raise CatalogLookupError(kind, name, suggestions[:MAX_SUGGESTIONS])
+
+
+def load_catalog(config, cache_path=CATALOG_CACHE, max_age: int = CATALOG_MAX_AGE) -> Optional[Catalog]:
+    """
+    Catalog for the configured provider: the GTFS static feed when one is
+    configured, otherwise the MBTA API (cached in cache_path). Returns None
+    if no source is available, in which case names are resolved from the
+    built-in tables only.
+    """
+    if config.gtfs is not None:
+        from .gtfs_static import GTFSStaticStore
+        try:
+            store = GTFSStaticStore.open(config.gtfs.database, config.gtfs.zip_path)
+            try:
+                if not store.is_empty():
+                    return Catalog.from_gtfs(store)
+            finally:
+                store.close()
+        except Exception as e:
+            logger.warning("Could not build catalog from GTFS feed: %s", e)
+
+    if config.provider.type != 'mbta-v3':
+        return None
+
+    cache_path = Path(cache_path)
+    cached = None
+    if cache_path.exists():
+        try:
+            cached = Catalog.load(cache_path)
+        except Exception as e:
+            logger.warning("Ignoring unreadable catalog cache %s: %s", cache_path, e)
+        if cached is not None and time.time() - cached.created < max_age:
+            return cached
+
+    from .provider import create_provider
+    try:
+        catalog = Catalog.from_api(create_provider(config))
+    except Exception as e:
+        if cached is not None:
+            logger.warning("Could not refresh station catalog, using cached copy: %s", e)
+            return cached
+        logger.warning("Could not load station catalog: %s", e)
+        return None
+
+    try:
+        catalog.save(cache_path)
+    except OSError as e:
+        logger.warning("Could not write catalog cache %s: %s", cache_path, e)
+    return catalog
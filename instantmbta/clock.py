@@ -0,0 +1,76 @@
+"""Time source anchored to the agency timezone and the MBTA service day.
+
+The MBTA service day runs from about 3am to 3am Boston time, so a train at
+12:40am on Saturday belongs to Friday's schedule. The Pi's own clock may be
+set to UTC, so every "now" goes through a Clock, which tests replace with a
+FixedClock.
+"""
+
+from datetime import date, datetime, time, timedelta, timezone
+import time as _time
+from typing import Optional
+from zoneinfo import ZoneInfo
+
+MBTA_TIMEZONE = ZoneInfo("America/New_York")
+SERVICE_DAY_START = time(3, 0)  # Local time at which a new service day begins
+
+
+class Clock:
+    """The current time in the agency's timezone."""
+
+    def __init__(self, tz=MBTA_TIMEZONE):
+        self.tz = tz
+
+    def now(self) -> datetime:
+        return datetime.now(self.tz)
+
+    def sleep(self, seconds: float):
+        """Wait; simulated clocks advance instead."""
+        _time.sleep(seconds)
+
+    def localize(self, dt: datetime) -> datetime:
+        """Convert to the agency timezone, treating naive times as local to it."""
+        if dt.tzinfo is None:
+            return dt.replace(tzinfo=self.tz)
+        return dt.astimezone(self.tz)
+
+    def service_date(self, dt: Optional[datetime] = None) -> date:
+        """The service day a moment belongs to; before 3am it is the previous day's."""
+        local = self.localize(dt) if dt is not None else self.now()
+        if local.time() < SERVICE_DAY_START:
+            return local.date() - timedelta(days=1)
+        return local.date()
+
+    def service_time(self, dt: Optional[datetime] = None) -> str:
+        """
+        HH:MM relative to the start of the service day, as the V3 API's
+        min_time/max_time filters expect. After midnight hours run past 24,
+        e.g. 00:40 is "24:40".
+        """
+        local = self.localize(dt) if dt is not None else self.now()
+        hours = local.hour
+        if local.date() > self.service_date(local):
+            hours += 24
+        return f"{hours:02d}:{local.minute:02d}"
+
+
+class FixedClock(Clock):
+    """A clock pinned to a given moment, for tests and replays."""
+
+    def __init__(self, now: datetime, tz=MBTA_TIMEZONE):
+        super().__init__(tz)
+        self._now = self.localize(now)
+
+    def now(self) -> datetime:
+        return self._now
+
+    def set(self, now: datetime):
+        self._now = self.localize(now)
+
+    def advance(self, seconds: float):
+        # Elapsed time, so step in UTC rather than wall-clock time
+        utc = self._now.astimezone(timezone.utc) + timedelta(seconds=seconds)
+        self._now = utc.astimezone(self.tz)
+
+    def sleep(self, seconds: float):
+        self.advance(seconds)
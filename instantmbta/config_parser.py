@@ -1,13 +1,58 @@
 """Configuration parser for InstantMBTA - handles YAML configs."""
 
+import re
 import yaml
-from typing import List, Optional
+from datetime import datetime, time, timedelta
+from typing import Callable, Dict, List, Optional
 from pathlib import Path
 import logging
-from dataclasses import dataclass, field
+from dataclasses import dataclass, field, replace
+
+from .catalog import Catalog
+from .credentials import resolve_api_key
 
 logger = logging.getLogger('instantmbta.config')
 
+# GTFS route_type values
+LIGHT_RAIL = 0
+SUBWAY = 1
+COMMUTER_RAIL = 2
+BUS = 3
+FERRY = 4
+
+GREEN_LINE_BRANCHES = ('B', 'C', 'D', 'E')
+GREEN_LINE = ','.join(f'Green-{branch}' for branch in GREEN_LINE_BRANCHES)
+GREEN_LINE_BRANCH_NAME = re.compile(r'^(?:green line|green|gl)[\s-]*([bcde])(?: branch)?$')
+
+# Bus route IDs: 1, 34E, 116117
+BUS_ROUTE_ID = re.compile(r'^\d+[A-Z]?$')
+
+# Silver Line short name suffix → route ID
+SILVER_LINE_IDS = {'1': '741', '2': '742', '3': '743', '4': '751', '5': '749', 'w': '746'}
+SILVER_LINE_NAME = re.compile(r'^(?:sl|silver line)\s*([1-5]|w|waterfront)$')
+
+
+def infer_route_type(route_id: str) -> Optional[int]:
+    """
+    Route type from the shape of an MBTA route ID, or None if unknown or if
+    comma separated routes are of different types.
+    """
+    types = set()
+    for part in route_id.split(','):
+        if part in ('Orange', 'Red', 'Blue'):
+            types.add(SUBWAY)
+        elif part.startswith('Green-') or part == 'Mattapan':
+            types.add(LIGHT_RAIL)
+        elif part.startswith('CR-'):
+            types.add(COMMUTER_RAIL)
+        elif part.startswith('Boat-'):
+            types.add(FERRY)
+        elif BUS_ROUTE_ID.match(part):
+            types.add(BUS)
+        else:
+            return None
+    return types.pop() if len(types) == 1 else None
+
 
 @dataclass
 class RouteConfig:
@@ -16,6 +61,20 @@ class RouteConfig:
     route_name: str
     inbound: int = 0   # Number of inbound trains to show
     outbound: int = 0  # Number of outbound trains to show
+    route_type: Optional[int] = None  # GTFS route_type; inferred from route_id when not given
+    branches: List[str] = field(default_factory=list)  # Green Line branch letters to show
+    # Only trains whose headsign contains one of headsigns, and none of
+    # exclude_headsigns; case insensitive
+    headsigns: List[str] = field(default_factory=list)
+    exclude_headsigns: List[str] = field(default_factory=list)
+    walk_minutes: Optional[int] = None  # Overrides Config.walk_minutes for this route
+
+    def __post_init__(self):
+        if self.branches and self.green_line:
+            self.branches = [str(b).upper().replace('GREEN-', '') for b in self.branches]
+            self.route_id = ','.join(f'Green-{branch}' for branch in self.branches)
+        if self.route_type is None:
+            self.route_type = infer_route_type(self.route_id)
 
     @property
     def has_inbound(self) -> bool:
@@ -25,16 +84,96 @@ class RouteConfig:
     def has_outbound(self) -> bool:
         return self.outbound > 0
 
+    @property
+    def commuter_rail(self) -> bool:
+        """Shown by schedule with delays, rather than by predicted time."""
+        return self.route_type == COMMUTER_RAIL
+
+    @property
+    def filters_headsigns(self) -> bool:
+        return bool(self.headsigns or self.exclude_headsigns)
+
+    def wants_headsign(self, headsign: Optional[str]) -> bool:
+        """Whether a train to this headsign passes the headsign filters."""
+        headsign = (headsign or '').lower()
+        if self.headsigns and not any(h.lower() in headsign for h in self.headsigns):
+            return False
+        return not any(h.lower() in headsign for h in self.exclude_headsigns)
+
+    @property
+    def green_line(self) -> bool:
+        return all(route_id.startswith('Green-') for route_id in self.route_id.split(','))
+
 
 @dataclass
 class DisplayConfig:
     """Display preferences."""
-    time_format: str = "12h"
+    time_format: str = "12h"      # 12h, 24h or countdown
     abbreviate: bool = True
     refresh: int = 60
     show_route: bool = True
     show_directions: bool = False
     minimal: bool = False
+    alerts: bool = True            # Show active service alerts
+    alert_min_severity: int = 7    # MBTA severity scale, 0 (least) to 10 (most)
+    vehicles: bool = False         # Show where the next train is; costs extra requests
+    occupancy: bool = False        # Crowding indicator for the next train
+    hide_full: bool = False        # Skip trains reported full
+    show_cancelled: bool = True    # Show cancelled and skipped trains, marked as such
+    destinations: bool = True      # Each train's headsign after its time
+    renderer: Optional[str] = None # inky, png, terminal or none; default inky on a Pi
+    image_path: str = "instantmbta.png"  # Output of the png renderer
+    image_width: int = 250         # Inky pHAT panel size
+    image_height: int = 122
+
+
+@dataclass
+class GTFSConfig:
+    """Static GTFS feed used for offline schedule fallback."""
+    zip_path: Optional[str] = None  # Downloaded GTFS zip, imported on startup
+    database: str = "instantmbta_gtfs.sqlite"
+
+
+@dataclass
+class ProviderConfig:
+    """Where real-time data comes from."""
+    type: str = "mbta-v3"  # 'mbta-v3' or 'gtfs-rt'
+    trip_updates_url: Optional[str] = None  # GTFS-Realtime TripUpdates feed
+    alerts_url: Optional[str] = None        # GTFS-Realtime Alerts feed
+    headers: Dict[str, str] = field(default_factory=dict)  # e.g. agency API key header
+    api_url: Optional[str] = None  # MBTA V3 API base URL; defaults to api-v3.mbta.com
+    timeout: float = 30            # Seconds to wait for each request
+    http_cache: Optional[str] = "instantmbta_http_cache.json"  # Routes and stops; None disables
+    # MBTA API key, resolved from MBTA_API_KEY, api_key or api_key_file (see credentials.py)
+    api_key: Optional[str] = field(default=None, repr=False)
+
+
+WEEKDAYS = ('mon', 'tue', 'wed', 'thu', 'fri', 'sat', 'sun')
+DAY_GROUPS = {
+    'daily': list(range(7)),
+    'weekdays': list(range(5)),
+    'weekends': [5, 6],
+}
+
+
+@dataclass
+class Profile:
+    """
+    A configuration shown during a weekly time window. A window that ends
+    before it starts runs past midnight, into the day after each of its days.
+    """
+    name: str
+    config: 'Config'
+    start: time
+    end: time
+    days: List[int] = field(default_factory=lambda: list(range(7)))  # 0 is Monday
+
+    def active(self, now: datetime) -> bool:
+        t = now.time()
+        if self.start <= self.end:
+            return now.weekday() in self.days and self.start <= t < self.end
+        return ((now.weekday() in self.days and t >= self.start) or
+                ((now.weekday() - 1) % 7 in self.days and t < self.end))
 
 
 @dataclass
@@ -46,6 +185,8 @@ class Config:
     station: Optional[str] = None
     station_id: Optional[str] = None
     routes: List[RouteConfig] = field(default_factory=list)
+    streaming: bool = False  # Stream predictions instead of polling
+    walk_minutes: int = 0  # Walk to the station; trains sooner than this are hidden
 
     # Multi-station mode
     route_id: Optional[str] = None
@@ -54,16 +195,54 @@ class Config:
     from_station_id: Optional[str] = None
     to_station: Optional[str] = None
     to_station_id: Optional[str] = None
+    trips: int = 3  # Number of upcoming trips to show
 
     # Display settings
     display: DisplayConfig = field(default_factory=DisplayConfig)
 
+    # Offline schedule fallback (and stop names for GTFS-Realtime)
+    gtfs: Optional[GTFSConfig] = None
+
+    # Real-time data source
+    provider: ProviderConfig = field(default_factory=ProviderConfig)
+
+    # Shown instead of the settings above during their time windows
+    profiles: List[Profile] = field(default_factory=list)
+
+    def profile_at(self, now: datetime) -> Optional[Profile]:
+        """The first profile whose window includes now, or None for the default."""
+        return next((profile for profile in self.profiles if profile.active(now)), None)
+
+    def next_profile_change(self, now: datetime) -> Optional[datetime]:
+        """
+        The next start or end of a profile window after now. Days a profile
+        doesn't run on are included, which at worst refreshes once for nothing.
+        """
+        boundaries = [datetime.combine(now.date() + timedelta(days=days), t, tzinfo=now.tzinfo)
+                      for profile in self.profiles
+                      for t in (profile.start, profile.end)
+                      for days in (0, 1)]
+        return min((b for b in boundaries if b > now), default=None)
+
+    def walk_minutes_for(self, route: RouteConfig) -> int:
+        return route.walk_minutes if route.walk_minutes is not None else self.walk_minutes
+
     def validate(self):
         if self.mode == 'single-station':
             if not (self.station or self.station_id):
                 raise ValueError("Single-station mode requires 'station' or 'station_id'")
             if not self.routes:
                 raise ValueError("Single-station mode requires at least one entry under 'routes'")
+            for route in self.routes:
+                if self.walk_minutes_for(route) < 0:
+                    raise ValueError("'walk_minutes' can't be negative")
+                if not route.branches:
+                    continue
+                if not route.green_line:
+                    raise ValueError(f"'branches' is only for the Green Line, not {route.route_name}")
+                unknown = [b for b in route.branches if b not in GREEN_LINE_BRANCHES]
+                if unknown:
+                    raise ValueError(f"Unknown Green Line branch: {', '.join(unknown)}")
         elif self.mode == 'multi-station':
             if not self.route_id:
                 raise ValueError("Multi-station mode requires 'route'")
@@ -71,14 +250,43 @@ class Config:
                 raise ValueError("Multi-station mode requires 'from' station")
             if not (self.to_station or self.to_station_id):
                 raise ValueError("Multi-station mode requires 'to' station")
+            if self.trips < 1:
+                raise ValueError("Multi-station mode requires 'trips' to be at least 1")
         else:
             raise ValueError(f"Unknown mode: {self.mode}")
 
+        if self.display.time_format not in ('12h', '24h', 'countdown'):
+            raise ValueError(f"Unknown time_format: {self.display.time_format}")
+
+        if self.display.renderer is not None and \
+                self.display.renderer not in ('inky', 'png', 'terminal', 'none'):
+            raise ValueError(f"Unknown renderer: {self.display.renderer}")
+
+        if not 0 <= self.display.alert_min_severity <= 10:
+            raise ValueError("'alert_min_severity' must be between 0 and 10")
+
+        if self.provider.type == 'gtfs-rt':
+            if not self.provider.trip_updates_url:
+                raise ValueError("The gtfs-rt provider requires 'trip_updates_url'")
+            if self.gtfs is None:
+                raise ValueError("The gtfs-rt provider requires a 'gtfs' static feed")
+        elif self.provider.type != 'mbta-v3':
+            raise ValueError(f"Unknown provider: {self.provider.type}")
+
+        if self.provider.timeout <= 0:
+            raise ValueError("'timeout' must be greater than 0")
+
+        for profile in self.profiles:
+            if profile.start == profile.end:
+                raise ValueError(f"Profile {profile.name} starts and ends at the same time")
+            profile.config.validate()
+
 
 class ConfigParser:
     """Parse configuration from YAML."""
 
-    # Common station name → station ID
+    # Common station name → station ID. With a catalog these act as aliases;
+    # without one (offline) they are the only names that resolve.
     STATION_IDS = {
         'oak grove': 'place-ogmnl',
         'malden center': 'place-mlmnl',
@@ -126,9 +334,9 @@ class ConfigParser:
         'blue line': 'Blue',
         'blue': 'Blue',
         'bl': 'Blue',
-        'green line': 'Green-B,Green-C,Green-D,Green-E',
-        'green': 'Green-B,Green-C,Green-D,Green-E',
-        'gl': 'Green-B,Green-C,Green-D,Green-E',
+        'green line': GREEN_LINE,
+        'green': GREEN_LINE,
+        'gl': GREEN_LINE,
         'haverhill line': 'CR-Haverhill',
         'haverhill': 'CR-Haverhill',
         'newburyport/rockport line': 'CR-Newburyport',
@@ -137,21 +345,62 @@ class ConfigParser:
         'franklin/foxboro line': 'CR-Franklin',
     }
 
-    def __init__(self):
+    def __init__(self, catalog: Optional[Catalog] = None,
+                 catalog_loader: Optional[Callable[[Config], Optional[Catalog]]] = None):
+        """
+        Args:
+            catalog: Station and route catalog used to resolve any name
+            catalog_loader: Called with the partially parsed config to build
+                the catalog when none was given (see catalog.load_catalog)
+        """
         self.logger = logger
+        self.catalog = catalog
+        self.catalog_loader = catalog_loader
 
     def resolve_station_id(self, station_name: Optional[str]) -> Optional[str]:
+        """
+        Station ID for a name or ID. Unknown names pass through unchanged
+        without a catalog and raise CatalogLookupError with one.
+        """
         if not station_name:
             return None
-        if 'place-' in station_name:
+        station_name = str(station_name)
+        # Parent stations, and bus stops, which are numbered
+        if 'place-' in station_name or station_name.isdigit():
             return station_name
-        return self.STATION_IDS.get(station_name.lower().strip(), station_name)
+        alias = self.STATION_IDS.get(station_name.lower().strip())
+        if alias:
+            return alias
+        if self.catalog is not None:
+            return self.catalog.resolve_station(station_name.strip())
+        return station_name
 
     def resolve_route_id(self, route_name: str) -> str:
+        """
+        Route ID for a name or ID. Unknown names pass through unchanged
+        without a catalog and raise CatalogLookupError with one.
+        """
+        route_name = str(route_name)
         # If it already looks like an API ID, pass through
-        if route_name in ['Orange', 'Red', 'Blue'] or route_name.startswith(('Green-', 'CR-')):
+        if infer_route_type(route_name) is not None:
             return route_name
-        return self.ROUTE_IDS.get(route_name.lower().strip(), route_name)
+        key = route_name.lower().strip()
+        alias = self.ROUTE_IDS.get(key)
+        if alias:
+            return alias
+        branch = GREEN_LINE_BRANCH_NAME.match(key)
+        if branch:
+            return f'Green-{branch.group(1).upper()}'
+        silver_line = SILVER_LINE_NAME.match(key)
+        if silver_line:
+            return SILVER_LINE_IDS[silver_line.group(1)[0]]
+        # "Route 1", "Bus 34E"
+        bus = re.match(r'^(?:route|bus)\s+(\d+[a-z]?)$', key)
+        if bus:
+            return bus.group(1).upper()
+        if self.catalog is not None:
+            return self.catalog.resolve_route(route_name.strip())
+        return route_name
 
     def parse_yaml(self, config_path: Path) -> Config:
         try:
@@ -165,32 +414,96 @@ class ConfigParser:
         # Display settings
         disp = data.get('display', {})
         display = DisplayConfig(
-            time_format=disp.get('time_format', '12h'),
+            time_format=str(disp.get('time_format', '12h')).lower(),
             abbreviate=disp.get('abbreviate', True),
             refresh=disp.get('refresh', 60),
             show_route=disp.get('show_route', True),
             show_directions=disp.get('show_directions', False),
             minimal=disp.get('minimal', False),
+            alerts=disp.get('alerts', True),
+            alert_min_severity=disp.get('alert_min_severity', 7),
+            vehicles=disp.get('vehicles', False),
+            occupancy=disp.get('occupancy', False),
+            hide_full=disp.get('hide_full', False),
+            show_cancelled=disp.get('show_cancelled', True),
+            destinations=disp.get('destinations', True),
+            renderer=disp.get('renderer'),
+            image_path=disp.get('image_path', DisplayConfig.image_path),
+            image_width=disp.get('image_width', DisplayConfig.image_width),
+            image_height=disp.get('image_height', DisplayConfig.image_height),
         )
 
         mode = data.get('mode', 'single-station').lower()
         config = Config(mode=mode, display=display)
 
-        if mode == 'single-station':
+        prov = data.get('provider', {})
+        config.provider = ProviderConfig(
+            type=prov.get('type', 'mbta-v3').lower(),
+            trip_updates_url=prov.get('trip_updates_url'),
+            alerts_url=prov.get('alerts_url'),
+            headers=prov.get('headers', {}),
+            api_url=prov.get('api_url'),
+            timeout=prov.get('timeout', ProviderConfig.timeout),
+            http_cache=prov.get('http_cache', ProviderConfig.http_cache),
+        )
+        if config.provider.type == 'mbta-v3':
+            config.provider.api_key = resolve_api_key(prov.get('api_key'), prov.get('api_key_file'))
+
+        gtfs = data.get('gtfs')
+        if gtfs:
+            config.gtfs = GTFSConfig(
+                zip_path=gtfs.get('zip'),
+                database=gtfs.get('database', GTFSConfig.database),
+            )
+
+        if self.catalog is None and self.catalog_loader is not None:
+            self.catalog = self.catalog_loader(config)
+
+        for name, profile in (data.get('profiles') or {}).items():
+            config.profiles.append(self._parse_profile(str(name), profile or {}, config))
+
+        default = data.get('default_profile')
+        if default:
+            profile = next((p for p in config.profiles if p.name == str(default)), None)
+            if profile is None:
+                raise ValueError(f"Unknown default_profile: {default}")
+            config = replace(profile.config, profiles=config.profiles)
+        else:
+            self._parse_mode(config, data)
+
+        config.validate()
+        return config
+
+    def _parse_mode(self, config: Config, data: Dict):
+        """The stations and routes for config.mode, from the top level or a profile."""
+        if config.mode == 'single-station':
             config.station = data.get('station')
-            config.station_id = self.resolve_station_id(config.station)
+            config.station_id = (str(data['station_id']) if data.get('station_id')
+                                 else self.resolve_station_id(config.station))
+            if config.station is None and config.station_id:
+                # A bus stop given by ID; its name goes in the title
+                config.station = ((self.catalog.stop_name(config.station_id) if self.catalog else None)
+                                  or config.station_id)
+            config.streaming = data.get('streaming', False)
+            config.walk_minutes = self._parse_minutes(data.get('walk_minutes', 0))
 
             for entry in data.get('routes', []):
                 if isinstance(entry, dict):
                     for name, rc in entry.items():
+                        route_id = self.resolve_route_id(name)
                         config.routes.append(RouteConfig(
-                            route_id   = self.resolve_route_id(name),
-                            route_name = name,
+                            route_id   = route_id,
+                            route_name = str(name),
                             inbound    = rc.get('inbound', 0),
                             outbound   = rc.get('outbound', 0),
+                            route_type = self.catalog.route_type(route_id) if self.catalog else None,
+                            branches   = rc.get('branches') or [],
+                            headsigns  = [str(h) for h in rc.get('headsigns') or []],
+                            exclude_headsigns = [str(h) for h in rc.get('exclude_headsigns') or []],
+                            walk_minutes = self._parse_minutes(rc.get('walk_minutes')),
                         ))
 
-        elif mode == 'multi-station':
+        elif config.mode == 'multi-station':
             route = data.get('route', '')
             config.route_id   = self.resolve_route_id(route)
             config.route_name = route
@@ -199,9 +512,57 @@ class ConfigParser:
             config.from_station_id = self.resolve_station_id(config.from_station)
             config.to_station      = data.get('to')
             config.to_station_id   = self.resolve_station_id(config.to_station)
+            config.trips           = data.get('trips', 3)
+
+    def _parse_profile(self, name: str, data: Dict, base: Config) -> Profile:
+        """A profile, sharing the display, provider and GTFS settings of base."""
+        config = Config(mode=str(data.get('mode', 'single-station')).lower(), display=base.display,
+                        gtfs=base.gtfs, provider=base.provider)
+        self._parse_mode(config, data)
+        return Profile(
+            name   = name,
+            config = config,
+            start  = self._parse_time(data.get('start'), name, 'start'),
+            end    = self._parse_time(data.get('end'), name, 'end'),
+            days   = self._parse_days(data.get('days', 'daily'), name),
+        )
 
-        config.validate()
-        return config
+    @staticmethod
+    def _parse_time(value, profile: str, key: str) -> time:
+        """HH:MM. Unquoted, YAML reads 06:30 as the number of minutes, 390."""
+        if isinstance(value, int) and 0 <= value < 24 * 60:
+            return time(value // 60, value % 60)
+        try:
+            hours, minutes = str(value).split(':')
+            return time(int(hours), int(minutes))
+        except ValueError:
+            raise ValueError(f"Profile {profile} needs '{key}' as HH:MM, not {value!r}")
+
+    @staticmethod
+    def _parse_minutes(value) -> Optional[int]:
+        """walk_minutes as an int; quoted in YAML it arrives as a string like '5'."""
+        if value is None:
+            return None
+        if isinstance(value, bool) or (isinstance(value, float) and not value.is_integer()):
+            raise ValueError("'walk_minutes' must be a whole number of minutes")
+        try:
+            return int(value)
+        except (TypeError, ValueError):
+            raise ValueError("'walk_minutes' must be a whole number of minutes")
+
+    @staticmethod
+    def _parse_days(value, profile: str) -> List[int]:
+        """'weekdays', 'weekends', 'daily', or a list of day names like [mon, tue]."""
+        if isinstance(value, str) and value.lower() in DAY_GROUPS:
+            return DAY_GROUPS[value.lower()]
+        names = [value] if isinstance(value, str) else value
+        days = []
+        for day in names:
+            key = str(day).lower()[:3]
+            if key not in WEEKDAYS:
+                raise ValueError(f"Profile {profile} has an unknown day: {day}")
+            days.append(WEEKDAYS.index(key))
+        return days
 
     def load_config(self, config_path: Optional[Path] = None) -> Config:
         if config_path and config_path.exists():
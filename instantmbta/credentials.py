@@ -0,0 +1,73 @@
+"""MBTA API key lookup and keeping the key out of logs.
+
+The key is taken from the first of:
+
+1. the MBTA_API_KEY environment variable
+2. `api_key` in the config's provider section
+3. the key file (`api_key_file`, default ~/.config/instantmbta/api_key)
+
+Without a key the API still works, at the anonymous rate limit.
+"""
+
+import logging
+import os
+from pathlib import Path
+from typing import Optional
+
+API_KEY_ENV = 'MBTA_API_KEY'
+API_KEY_FILE = '~/.config/instantmbta/api_key'
+API_KEY_HEADER = 'x-api-key'
+REDACTED = '***'
+
+
+def resolve_api_key(config_key: Optional[str] = None,
+                    key_file: Optional[str] = None) -> Optional[str]:
+    """
+    Find the API key.
+
+    Args:
+        config_key: `api_key` from the configuration
+        key_file: `api_key_file` from the configuration; API_KEY_FILE if omitted
+
+    Returns:
+        The key, or None to use the API anonymously
+    """
+    env_key = os.environ.get(API_KEY_ENV, '').strip()
+    if env_key:
+        return env_key
+    if config_key and str(config_key).strip():
+        return str(config_key).strip()
+
+    path = Path(key_file or API_KEY_FILE).expanduser()
+    if path.is_file():
+        key = path.read_text().strip()
+        if key:
+            return key
+    elif key_file:
+        # A key file that was asked for by name should exist
+        raise ValueError(f"API key file not found: {path}")
+    return None
+
+
+class RedactingFilter(logging.Filter):
+    """Replace a secret with *** in every record passing through a handler."""
+
+    def __init__(self, secret: str):
+        super().__init__()
+        self.secret = secret
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        message = record.getMessage()
+        if self.secret in message:
+            record.msg = message.replace(self.secret, REDACTED)
+            record.args = ()
+        return True
+
+
+def redact_logs(logger: logging.Logger, secret: Optional[str]):
+    """Redact secret from everything logger's handlers write."""
+    if not secret:
+        return
+    for handler in logger.handlers:
+        if not any(isinstance(f, RedactingFilter) and f.secret == secret for f in handler.filters):
+            handler.addFilter(RedactingFilter(secret))
@@ -2,12 +2,20 @@
 
 from abc import ABC, abstractmethod
 from dataclasses import dataclass, field
-from datetime import datetime
+from datetime import datetime, timedelta
 from typing import Dict, List, Optional, Tuple
 import logging
+import re
 
-from .config_parser import Config, RouteConfig
-from .infogather import InfoGather
+from .alerts import select_alerts
+from .clock import Clock
+from .config_parser import BUS, COMMUTER_RAIL, FERRY, Config, Profile, RouteConfig
+from .models import CANCELLED_RELATIONSHIPS, Prediction
+from .provider import TransitProvider
+
+# Trains fetched per train shown when some are filtered out by headsign,
+# walk time, fullness or cancellation
+FILTER_FETCH_FACTOR = 4
 
 
 @dataclass
@@ -18,6 +26,29 @@ class TrainPrediction:
     direction: str  # 'inbound' or 'outbound'
     destination: Optional[str] = None
     uncertainty_minutes: Optional[int] = None
+    scheduled: bool = False  # From the static schedule, not a live prediction
+    arrival_time: Optional[datetime] = None
+    departure_time: Optional[datetime] = None
+    status: Optional[str] = None  # e.g. 'Boarding' or 'Stopped 2 stops away'
+    vehicle_status: Optional[str] = None  # INCOMING_AT, STOPPED_AT or IN_TRANSIT_TO
+    vehicle_stop: Optional[str] = None    # Name of the stop the vehicle is at or heading to
+    stops_away: Optional[int] = None
+    occupancy: Optional[int] = None  # 1 (seats available) to 3 (full)
+    schedule_relationship: Optional[str] = None  # CANCELLED, SKIPPED or ADDED
+    # Commuter Rail, shown by schedule: the scheduled time and minutes late
+    scheduled_time: Optional[datetime] = None
+    delay: Optional[int] = None
+    train: Optional[str] = None  # Train number
+    track: Optional[str] = None
+    route_type: Optional[int] = None  # GTFS route_type of the configured route
+    route_id: Optional[str] = None    # The trip's route, e.g. Green-D or Boat-F1
+    branch: Optional[str] = None  # Green Line branch letter
+    show_destination: bool = False  # Headsign after the time; see DisplayConfig.destinations
+    leave_at: Optional[datetime] = None  # When to leave to catch it, given a walk time
+
+    @property
+    def cancelled(self) -> bool:
+        return self.schedule_relationship in CANCELLED_RELATIONSHIPS
 
 
 @dataclass
@@ -27,6 +58,8 @@ class DisplayLine:
     is_header: bool = False
     is_route: bool = False
     indent: bool = False
+    occupancy: Optional[int] = None  # Crowding of the line's next train, 1 to 3
+    urgent: bool = False  # Drawn in red, e.g. LEAVE NOW
 
 
 @dataclass
@@ -36,6 +69,7 @@ class DisplayData:
     date: str
     lines: List[DisplayLine] = field(default_factory=list)
     refresh_seconds: int = 60
+    alerts: List[str] = field(default_factory=list)  # Footer banner text
 
     def __eq__(self, other):
         if not isinstance(other, DisplayData):
@@ -44,7 +78,8 @@ class DisplayData:
             self.title == other.title and
             self.date == other.date and
             self.lines == other.lines and
-            self.refresh_seconds == other.refresh_seconds
+            self.refresh_seconds == other.refresh_seconds and
+            self.alerts == other.alerts
         )
 
     def __ne__(self, other):
@@ -53,13 +88,14 @@ class DisplayData:
 class DisplayMode(ABC):
     """Abstract base class for display modes."""
     
-    def __init__(self, config: Config):
+    def __init__(self, config: Config, clock: Optional[Clock] = None):
         self.config = config
+        self.clock = clock or Clock()
         self.logger = logging.getLogger(f'instantmbta.{self.__class__.__name__}')
     
     @abstractmethod
-    def gather_data(self, ig: InfoGather) -> Dict:
-        """Gather raw data from MBTA API."""
+    def gather_data(self, ig: TransitProvider) -> Dict:
+        """Gather raw data from the transit provider."""
         pass
     
     @abstractmethod
@@ -67,24 +103,80 @@ class DisplayMode(ABC):
         """Format raw data for display."""
         pass
     
-    def format_time(self, time_str: Optional[str]) -> str:
-        """Convert ISO time string to display format."""
-        if not time_str:
+    def format_time(self, time: Optional[datetime]) -> str:
+        """
+        Convert a time to display format. In countdown mode this gives the
+        12h clock time, for times that aren't counted down.
+        """
+        if time is None:
             return "---"
         
-        try:
-            dt = datetime.fromisoformat(time_str)
-            if self.config.display.time_format == "24h":
-                return dt.strftime("%H:%M")
-            else:
-                return dt.strftime("%-I:%M %p")
-        except Exception:
+        # Show agency local time whatever the system timezone is
+        dt = self.clock.localize(time)
+        if self.config.display.time_format == "24h":
+            return dt.strftime("%H:%M")
+        else:
+            return dt.strftime("%-I:%M %p")
+
+    def format_countdown(self, arrival: Optional[datetime], departure: Optional[datetime],
+                         status: Optional[str] = None) -> str:
+        """
+        Minutes until a train arrives, following the MBTA's countdown sign
+        rules: BRD while boarding, ARR within 30 seconds, then "N min"
+        rounded to the nearest minute and "20+ min" beyond that.
+        """
+        if status and 'boarding' in status.lower():
+            return "BRD"
+
+        target = arrival or departure
+        if target is None:
             return "---"
+
+        now = self.clock.now()
+        seconds = (target - now).total_seconds()
+        if seconds <= 0 and departure is not None and departure > now:
+            # Arrived but not yet departed
+            return "BRD"
+        if seconds <= 30:
+            return "ARR"
+        if seconds <= 60:
+            return "1 min"
+
+        minutes = int(seconds / 60 + 0.5)
+        if minutes > 20:
+            return "20+ min"
+        return f"{minutes} min"
+
+    def format_departure(self, departure: Optional[datetime], arrival: Optional[datetime] = None,
+                         status: Optional[str] = None) -> str:
+        """A departure as a countdown or a clock time, per display.time_format."""
+        if self.config.display.time_format != "countdown":
+            return self.format_time(departure)
+        return self.format_countdown(arrival, departure, status)
     
-    def abbreviate_route(self, route_name: str) -> str:
+    def abbreviate_route(self, route_name: str, route_type: Optional[int] = None,
+                         route_id: Optional[str] = None) -> str:
         """Abbreviate route name if configured."""
         if not self.config.display.abbreviate:
             return route_name
+
+        if route_type == BUS:
+            # 'Silver Line 4' → 'SL4', 'Route 1' → '1'
+            silver_line = re.match(r'^(?:Silver Line|SL)\s*(\w+)$', route_name, re.IGNORECASE)
+            if silver_line:
+                branch = silver_line.group(1).upper()
+                # 'Silver Line Waterfront' → 'SLW'
+                return "SL" + ("W" if branch.startswith("W") else branch)
+            return re.sub(r'^(?:Route|Bus)\s+', '', route_name, flags=re.IGNORECASE)
+        if route_type == FERRY:
+            # 'Boat-F1' → 'F1', as on the ferry maps
+            numbered = re.match(r'^Boat-(F\w+)$', route_id or '')
+            return numbered.group(1) if numbered else 'Ferry'
+        if route_type == COMMUTER_RAIL:
+            return 'CR'
+        if route_name.startswith('Green Line'):
+            # Branches are shown on each train
+            return 'GL'
         
         abbreviations = {
             'Orange Line': 'OL',
@@ -104,223 +196,384 @@ class DisplayMode(ABC):
         
         return route_name
 
+    def gather_alerts(self, ig: TransitProvider, route_ids: List[str],
+                      stop_ids: List[str]) -> List:
+        """Active alerts at or above the configured severity, most severe first."""
+        if not self.config.display.alerts:
+            return []
+        try:
+            alerts = ig.get_alerts(route_ids, stop_ids)
+            return select_alerts(alerts, self.config.display.alert_min_severity, self.clock.now())
+        except Exception as e:
+            self.logger.error(f"Error getting alerts: {e}")
+            return []
+
+    def format_alerts(self, data: Dict) -> List[str]:
+        """Banner text for each alert in the gathered data."""
+        return [alert.short_header or alert.header for alert in data.get('alerts', [])]
+
 
 class SingleStationMode(DisplayMode):
     """Display mode for tracking multiple routes at a single station."""
     
-    def gather_data(self, ig: InfoGather) -> Dict:
+    def __init__(self, config: Config, prediction_store=None, clock: Optional[Clock] = None):
+        super().__init__(config, clock)
+        # Optional streaming PredictionStore; used instead of polling once
+        # it has received its first reset event
+        self.prediction_store = prediction_store
+    
+    def gather_data(self, ig: TransitProvider) -> Dict:
         """
         For each (route, direction) call `get_predictions_filtered`
         and convert the returned list into `TrainPrediction` objects.
         Any exception → record an error, move on.
         """
-        data = {"station": self.config.station, "predictions": [], "errors": []}
+        data = {"station": self.config.station, "predictions": [], "alerts": [], "errors": []}
+        display = self.config.display
+        # Each route and direction's candidates, trimmed to its limit once
+        # full trains are known
+        groups: List[Tuple[List[Prediction], RouteConfig, str, Optional[int]]] = []
 
+        source = ig
+        if self.prediction_store is not None and self.prediction_store.ready:
+            source = self.prediction_store
+
+        now = self.clock.now()
         for route in self.config.routes:
+            walk = timedelta(minutes=self.config.walk_minutes_for(route))
             for dir_id, dir_label, limit in (
                 ("0", "inbound", route.inbound),
                 ("1", "outbound", route.outbound),
             ):
                 if limit == 0:
                     continue
+                # Commuter Rail schedules aren't streamed, so always ask the provider
+                fetch = ig.get_departures if route.commuter_rail else source.get_predictions_filtered
+                filtered = (route.filters_headsigns or walk or display.hide_full
+                            or not display.show_cancelled)
+                count = limit * FILTER_FETCH_FACTOR if filtered else limit
                 try:
-                    predictions = ig.get_predictions_filtered(
-                        self.config.station_id, dir_id, route.route_id, limit
-                    )
+                    predictions = fetch(self.config.station_id, dir_id, route.route_id, count)
+                    if not predictions and route.route_type == BUS:
+                        # Quiet bus routes often have no predictions yet
+                        predictions = ig.get_departures(self.config.station_id, dir_id,
+                                                        route.route_id, count)
                 except Exception as e:
                     data["errors"].append(f"{route.route_name}: {e}")
                     continue
 
-                for raw in predictions:
-                    try:
-                        tp = self._build_tp(raw, route.route_name, dir_label)
-                        data["predictions"].append(tp)
-                    except Exception:
-                        # Skip malformed entry
-                        continue
+                if route.filters_headsigns:
+                    predictions = [p for p in predictions if route.wants_headsign(p.destination)]
+                if walk:
+                    # Trains leaving before we could get there
+                    predictions = [p for p in predictions if p.time is not None and p.time >= now + walk]
+                if not display.show_cancelled:
+                    predictions = [p for p in predictions if not p.cancelled]
+                groups.append((predictions, route, dir_label, limit if filtered else None))
+
+        if display.vehicles or display.occupancy or display.hide_full:
+            try:
+                ig.locate_vehicles([prediction for predictions, _, _, _ in groups
+                                    for prediction in predictions],
+                                   count_stops=display.vehicles)
+            except Exception as e:
+                self.logger.error(f"Error locating vehicles: {e}")
+
+        gathered: List[Tuple[Prediction, RouteConfig, str]] = []
+        for predictions, route, dir_label, limit in groups:
+            if display.hide_full:
+                predictions = [p for p in predictions if not (p.vehicle and p.vehicle.full)]
+            gathered.extend((prediction, route, dir_label) for prediction in predictions[:limit])
+
+        for prediction, route, dir_label in gathered:
+            try:
+                tp = self._build_tp(prediction, route.route_name, dir_label,
+                                    by_schedule=route.commuter_rail)
+                tp.route_type = route.route_type
+                tp.show_destination = display.destinations
+                walk_minutes = self.config.walk_minutes_for(route)
+                if walk_minutes:
+                    tp.leave_at = tp.time - timedelta(minutes=walk_minutes)
+                data["predictions"].append(tp)
+            except Exception:
+                # Skip malformed entry
+                continue
 
         data["predictions"].sort(key=lambda tp: tp.time)
+
+        route_ids = [r for route in self.config.routes for r in route.route_id.split(',')]
+        data["alerts"] = self.gather_alerts(ig, route_ids, [self.config.station_id])
         return data
 
-    def _build_tp(self, raw: dict, route_name: str, direction: str) -> TrainPrediction:
-        attrs = raw.get("attributes", raw)
-        ts = attrs.get("departure_time") or attrs.get("arrival_time")
-        if not ts:
+    def _build_tp(self, prediction: Prediction, route_name: str, direction: str,
+                  by_schedule: bool = False) -> TrainPrediction:
+        if prediction.time is None:
             raise ValueError("missing departure_time")
 
-        dt = datetime.fromisoformat(ts) if isinstance(ts, str) else ts
-        unc = attrs.get("departure_uncertainty")
-        dest = raw.get("destination")
-
+        unc = prediction.departure_uncertainty
+        vehicle = prediction.vehicle
+        schedule = prediction.schedule if by_schedule else None
         return TrainPrediction(
-            time=dt,
+            time=prediction.time,
             route_name=route_name,
             direction=direction,
-            destination=dest,
+            destination=prediction.destination,
             uncertainty_minutes=(unc // 60) if unc else None,
+            # NO_DATA predictions, and departures not yet predicted, only
+            # have the scheduled time
+            scheduled=(prediction.scheduled or prediction.schedule_relationship == "NO_DATA"
+                       or (prediction.departure_time is None and prediction.arrival_time is None
+                           and not prediction.cancelled)),
+            arrival_time=prediction.arrival_time,
+            departure_time=prediction.departure_time,
+            status=prediction.status,
+            vehicle_status=vehicle.current_status if vehicle else None,
+            vehicle_stop=vehicle.stop.name if vehicle and vehicle.stop else None,
+            stops_away=prediction.stops_away,
+            occupancy=vehicle.crowding if vehicle else None,
+            schedule_relationship=(prediction.schedule_relationship
+                                   if prediction.schedule_relationship != "NO_DATA" else None),
+            scheduled_time=schedule.time if schedule else None,
+            delay=prediction.delay if schedule else None,
+            train=prediction.train,
+            track=prediction.track,
+            route_id=prediction.route_id,
+            branch=(prediction.route_id.split('-', 1)[1]
+                    if prediction.route_id and prediction.route_id.startswith('Green-') else None),
         )
-
-    def _parse_predictions(self, response_data: Dict, route_id: str, route_name: str, 
-                          direction: str, count: int) -> List[TrainPrediction]:
-        """Parse prediction response into TrainPrediction objects."""
-        predictions = []
-        
-        for item in response_data.get('data', []):
-            # Check if this prediction is for our route
-            relationships = item.get('relationships', {})
-            pred_route = relationships.get('route', {}).get('data', {})
-            if pred_route.get('id') != route_id:
-                continue
-            
-            attrs = item.get('attributes', {})
-            departure_time = attrs.get('departure_time') or attrs.get('arrival_time')
-            
-            if departure_time and len(predictions) < count:
-                predictions.append(TrainPrediction(
-                    time=datetime.fromisoformat(departure_time),
-                    route_name=route_name,
-                    direction=direction,
-                    uncertainty_minutes=attrs.get('departure_uncertainty', 120) // 60 if attrs.get('departure_uncertainty') else None
-                ))
-        
-        return predictions
     
+    def format_prediction(self, pred: TrainPrediction) -> str:
+        """Clock time or countdown for one prediction."""
+        if self.config.display.time_format != "countdown":
+            return self.format_time(pred.time)
+        return self.format_countdown(pred.arrival_time, pred.departure_time or pred.time, pred.status)
+
+    def format_entry(self, pred: TrainPrediction) -> str:
+        """One time on a route line, marked sched, added, CANCELLED or SKIPPED."""
+        if pred.cancelled:
+            # A clock time even in countdown mode: there's nothing to count down to
+            return f"{self.format_time(pred.time)} {pred.schedule_relationship}"
+        if pred.scheduled_time is not None:
+            return self.format_scheduled(pred)
+        text = self.format_prediction(pred)
+        if pred.scheduled:
+            return text + " sched"
+        if pred.schedule_relationship == "ADDED":
+            return text + " added"
+        return text
+
+    def format_scheduled(self, pred: TrainPrediction) -> str:
+        """
+        A departure shown by schedule: the scheduled time (or countdown to
+        the predicted one) and how late it's running, e.g. '10:28 AM (+5)',
+        or the API's status such as 'All aboard'.
+        """
+        if self.config.display.time_format == "countdown":
+            text = self.format_prediction(pred)
+        else:
+            text = self.format_time(pred.scheduled_time)
+        if pred.delay is not None and pred.delay > 0:
+            return f"{text} (+{pred.delay})"
+        if pred.status and pred.status.lower() != "on time":
+            return f"{text} {pred.status}"
+        return text
+
+    def format_leave(self, preds: List[TrainPrediction]) -> Optional[DisplayLine]:
+        """
+        When to leave for the next train that can still be caught, e.g.
+        'Leave in 4 min: OL In 10:15 AM', or 'LEAVE NOW' in red.
+        """
+        catchable = [p for p in preds if p.leave_at is not None and not p.cancelled]
+        if not catchable:
+            return None
+        pred = min(catchable, key=lambda p: p.leave_at)
+        minutes = int((pred.leave_at - self.clock.now()).total_seconds() // 60)
+        guidance = "LEAVE NOW" if minutes < 1 else f"Leave in {minutes} min"
+        direction = "In" if pred.direction == "inbound" else "Out"
+        train = f"{self.abbreviate_route(pred.route_name, pred.route_type, pred.route_id)} {direction} {self.format_prediction(pred)}"
+        return DisplayLine(text=f"{guidance}: {train}", urgent=minutes < 1)
+
+    def format_branch(self, pred: TrainPrediction) -> str:
+        """Green Line branch and headsign before the time, e.g. 'D Riverside '."""
+        if not pred.branch:
+            return ""
+        if pred.show_destination and pred.destination:
+            return f"{pred.branch} {pred.destination} "
+        return f"{pred.branch} "
+
+    def format_train(self, pred: TrainPrediction) -> Optional[str]:
+        """Train number and track, e.g. '#211 Trk 5', when known."""
+        parts = []
+        if pred.train:
+            parts.append(f"#{pred.train}")
+        if pred.track:
+            parts.append(f"Trk {pred.track}")
+        return " ".join(parts) or None
+
+    def format_vehicle(self, pred: TrainPrediction) -> Optional[str]:
+        """Where the train is, e.g. 'Stopped at Wellington' or '2 stops away'."""
+        stopped = pred.vehicle_status == "STOPPED_AT"
+        if pred.stops_away == 0:
+            # Countdown already shows ARR and BRD
+            if self.config.display.time_format == "countdown":
+                return None
+            return "Boarding" if stopped else "Approaching"
+        if stopped and pred.vehicle_stop:
+            return f"Stopped at {pred.vehicle_stop}"
+        if pred.stops_away is not None:
+            return f"{pred.stops_away} stop{'s' if pred.stops_away != 1 else ''} away"
+        return None
+
     def format_for_display(self, data: Dict) -> DisplayData:
         """Format single station data for display."""
         display = DisplayData(
             title=data['station'],
-            date=datetime.now().strftime("%m/%d/%y"),
+            date=self.clock.now().strftime("%m/%d/%y"),
             refresh_seconds=self.config.display.refresh
         )
         
-        # Group predictions by route and direction
-        grouped: Dict[Tuple[str,str], List[TrainPrediction]] = {}
+        leave = self.format_leave(data['predictions'])
+        if leave:
+            display.lines.append(leave)
+
+        # Group predictions by route and direction, and by headsign for buses
+        # and ferries, whose variants in one direction go different places
+        grouped: Dict[Tuple[str, str, Optional[str]], List[TrainPrediction]] = {}
         for pred in data['predictions']:
-            key = (pred.route_name, pred.direction)
+            headsign = pred.destination if pred.route_type in (BUS, FERRY) else None
+            key = (pred.route_name, pred.direction, headsign)
             grouped.setdefault(key, []).append(pred)
         
         # For each route/direction, dump all times on one line
-        for (route_name, direction), preds in grouped.items():
-            abbrev_route = self.abbreviate_route(route_name)
-            direction_abbrev = "In" if direction == "inbound" else "Out"
-            times = [ self.format_time(p.time.isoformat()) for p in preds ]
+        for (route_name, direction, headsign), preds in grouped.items():
+            abbrev_route = self.abbreviate_route(route_name, preds[0].route_type, preds[0].route_id)
+            direction_abbrev = headsign or ("In" if direction == "inbound" else "Out")
+            times = [self.format_branch(p) + self.format_entry(p) for p in preds]
+            # Bus and ferry headsigns are in the label, Green Line ones with the branch
+            if not headsign:
+                times = [f"{text} {p.destination}" if p.show_destination and p.destination and not p.branch
+                         else text for text, p in zip(times, preds)]
+            # Only the next train's position is worth the space
+            next_train = next((i for i, p in enumerate(preds) if not p.cancelled), None)
+            occupancy = None
+            if next_train is not None:
+                train = self.format_train(preds[next_train])
+                if train:
+                    times[next_train] += f" {train}"
+                location = self.format_vehicle(preds[next_train])
+                if location:
+                    times[next_train] += f" ({location})"
+                if self.config.display.occupancy:
+                    occupancy = preds[next_train].occupancy
             times_str = ", ".join(times)
             
             line_text = f"{abbrev_route} {direction_abbrev}: {times_str}"
             display.lines.append(DisplayLine(
                 text=line_text,
                 is_route=True,
+                occupancy=occupancy,
             ))
         
         # Add any errors at the bottom
         for error in data.get('errors', []):
             display.lines.append(DisplayLine(text=f"Error: {error}"))
         
+        display.alerts = self.format_alerts(data)
         return display
 
 
 class MultiStationMode(DisplayMode):
     """Display mode for tracking a journey between two stations."""
     
-    def gather_data(self, ig: InfoGather) -> Dict:
-        """Gather schedule data for both stations."""
+    def gather_data(self, ig: TransitProvider) -> Dict:
+        """Gather the next trips that serve both stations, in order."""
         data = {
             'route': self.config.route_name,
             'from_station': self.config.from_station,
             'to_station': self.config.to_station,
-            'from_schedule': None,
-            'to_schedule': None,
+            'trips': [],
+            'alerts': [],
             'errors': []
         }
         
         try:
-            # Get schedule for from station
-            from_data = ig.get_current_schedule(
-                self.config.route_id,
-                self.config.from_station_id
-            )
-            data['from_schedule'] = {
-                'inbound_arrival': from_data[0],
-                'outbound_arrival': from_data[1],
-                'inbound_departure': from_data[2],
-                'outbound_departure': from_data[3]
-            }
-            
-            # Get schedule for to station
-            to_data = ig.get_current_schedule(
+            data['trips'] = ig.get_journey_trips(
                 self.config.route_id,
-                self.config.to_station_id
+                self.config.from_station_id,
+                self.config.to_station_id,
+                self.config.trips
             )
-            data['to_schedule'] = {
-                'inbound_arrival': to_data[0],
-                'outbound_arrival': to_data[1],
-                'inbound_departure': to_data[2],
-                'outbound_departure': to_data[3]
-            }
-            
         except Exception as e:
-            self.logger.error(f"Error getting schedule data: {e}")
+            self.logger.error(f"Error getting journey data: {e}")
             data['errors'].append(str(e))
         
+        data['alerts'] = self.gather_alerts(
+            ig,
+            self.config.route_id.split(','),
+            [self.config.from_station_id, self.config.to_station_id]
+        )
         return data
     
     def format_for_display(self, data: Dict) -> DisplayData:
         """Format multi-station data for display."""
         display = DisplayData(
             title=data['route'] if self.config.display.show_route else "",
-            date=datetime.now().strftime("%m/%d/%y"),
+            date=self.clock.now().strftime("%m/%d/%y"),
             refresh_seconds=self.config.display.refresh
         )
         
-        # From station
         display.lines.append(DisplayLine(
-            text=data['from_station'],
+            text=f"{data['from_station']} → {data['to_station']}",
             is_header=True
         ))
         
-        if data['from_schedule']:
-            # Show inbound time at from station
-            inbound_time = self.format_time(data['from_schedule']['inbound_departure'])
+        # One line per trip: departure from the first station and arrival
+        # of the same train at the second
+        for trip in data['trips']:
+            depart = self.format_departure(trip.departure_time, trip.from_arrival_time, trip.status)
+            arrive = self.format_time(trip.arrival_time)
             display.lines.append(DisplayLine(
-                text=f"Next Inbound:    {inbound_time}",
-                indent=False
+                text=f"depart {depart} → arrive {arrive}",
+                is_route=True
             ))
         
-        # To station
-        display.lines.append(DisplayLine(
-            text="",  # Blank line
-            is_header=False
-        ))
-        
-        display.lines.append(DisplayLine(
-            text=data['to_station'],
-            is_header=True
-        ))
-        
-        if data['to_schedule']:
-            # Show both directions at to station
-            inbound_time = self.format_time(data['to_schedule']['inbound_departure'])
-            display.lines.append(DisplayLine(
-                text=f"Next Inbound:    {inbound_time}",
-                indent=False
-            ))
-            
-            outbound_time = self.format_time(data['to_schedule']['outbound_departure'])
-            display.lines.append(DisplayLine(
-                text=f"Next Outbound:   {outbound_time}",
-                indent=False
-            ))
+        if not data['trips'] and not data.get('errors'):
+            display.lines.append(DisplayLine(text="No upcoming trips"))
         
         # Add any errors
         for error in data.get('errors', []):
             display.lines.append(DisplayLine(text=f"Error: {error}"))
         
+        display.alerts = self.format_alerts(data)
         return display
 
-def create_display_mode(config: Config) -> DisplayMode:
+def create_display_mode(config: Config, prediction_store=None,
+                        clock: Optional[Clock] = None) -> DisplayMode:
     if config.mode == 'single-station':
-        return SingleStationMode(config)
+        return SingleStationMode(config, prediction_store, clock)
     elif config.mode == 'multi-station':
-        return MultiStationMode(config)
+        return MultiStationMode(config, clock)
     else:
-        raise ValueError(f"Unknown display mode: {config.mode}")
\ No newline at end of file
+        raise ValueError(f"Unknown display mode: {config.mode}")
+
+
+class ProfileModes:
+    """
+    The display mode for the time of day: the active profile's, or the
+    default one outside every profile's window. Profile modes are created
+    the first time each profile becomes active, and don't stream.
+    """
+
+    def __init__(self, config: Config, default_mode: DisplayMode, clock: Optional[Clock] = None):
+        self.config = config
+        self.default_mode = default_mode
+        self.clock = clock or Clock()
+        self._modes: Dict[str, DisplayMode] = {}
+
+    def active(self) -> Tuple[Optional[Profile], DisplayMode]:
+        profile = self.config.profile_at(self.clock.now())
+        if profile is None:
+            return None, self.default_mode
+        if profile.name not in self._modes:
+            self._modes[profile.name] = create_display_mode(profile.config, clock=self.clock)
+        return profile, self._modes[profile.name]
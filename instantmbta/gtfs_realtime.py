@@ -0,0 +1,336 @@
+"""GTFS-Realtime provider so the display works with transit agencies other
+than the MBTA.
+
+TripUpdates feeds are protobuf FeedMessages (https://gtfs.org/realtime/).
+They identify stops and trips by ID only, so stop names, headsigns,
+directions and scheduled times come from the agency's GTFS static feed.
+"""
+
+from datetime import date, datetime
+import logging
+import time
+from typing import Dict, Iterator, List, Optional
+
+import requests
+
+from .alerts import GTFS_RT_SEVERITY, Alert
+from .clock import Clock
+from .gtfs_static import GTFSStaticStore, service_time
+from .models import JourneyTrip, Prediction, Route, Stop, Trip
+from .provider import TransitProvider
+
+STANDARD_TIMEOUT = 30
+FEED_CACHE_SECONDS = 15  # One fetch serves every route/direction in a refresh
+
+
+def decode_feed(content: bytes) -> Dict:
+    """
+    Decode a protobuf FeedMessage into plain dictionaries using the GTFS
+    field names (trip_update, stop_time_update, ...). 64-bit integers such
+    as POSIX times come back as strings.
+    """
+    try:
+        from google.transit import gtfs_realtime_pb2
+        from google.protobuf.json_format import MessageToDict
+    except ImportError as e:
+        raise ImportError(
+            "GTFS-Realtime support requires the gtfs-realtime-bindings package"
+        ) from e
+
+    feed = gtfs_realtime_pb2.FeedMessage()
+    feed.ParseFromString(content)
+    return MessageToDict(feed, preserving_proto_field_name=True)
+
+
+class GTFSRealtimeProvider(TransitProvider):
+    """Departures from a GTFS-Realtime TripUpdates feed."""
+
+    def __init__(self, trip_updates_url: str, static_store: GTFSStaticStore,
+                 alerts_url: Optional[str] = None, headers: Optional[Dict[str, str]] = None,
+                 timeout: int = STANDARD_TIMEOUT, clock: Optional[Clock] = None):
+        self.logger = logging.getLogger('instantmbta.gtfs_realtime')
+        self.trip_updates_url = trip_updates_url
+        self.alerts_url = alerts_url
+        self.static = static_store
+        # Times in the feed are interpreted in the static feed's timezone
+        self.clock = clock or Clock(static_store.timezone)
+        self.headers = headers or {}
+        self.timeout = timeout
+        self._feeds: Dict[str, tuple] = {}
+
+    def _fetch_feed(self, url: str) -> Dict:
+        """Download and decode a feed, reusing it for FEED_CACHE_SECONDS."""
+        cached = self._feeds.get(url)
+        if cached is not None and time.time() - cached[0] < FEED_CACHE_SECONDS:
+            return cached[1]
+
+        self.logger.debug("Fetching GTFS-Realtime feed %s", url)
+        response = requests.get(url, headers=self.headers, timeout=self.timeout)
+        response.raise_for_status()
+        feed = decode_feed(response.content)
+        self._feeds[url] = (time.time(), feed)
+        return feed
+
+    def _trip_updates(self) -> Iterator[Dict]:
+        feed = self._fetch_feed(self.trip_updates_url)
+        for entity in feed.get('entity', []):
+            if entity.get('is_deleted'):
+                continue
+            trip_update = entity.get('trip_update')
+            if trip_update:
+                yield trip_update
+
+    def _trip_info(self, trip_update: Dict) -> Dict:
+        """Trip descriptor fields, filled in from the static feed where absent."""
+        trip = trip_update.get('trip', {})
+        trip_id = trip.get('trip_id')
+        static_trip = self.static.trip(trip_id) if trip_id else None
+        static_trip = static_trip or {}
+
+        start_date = trip.get('start_date')
+        if start_date:
+            service_date = datetime.strptime(start_date, '%Y%m%d').date()
+        else:
+            service_date = self.clock.service_date()
+
+        direction_id = trip.get('direction_id', static_trip.get('direction_id'))
+        return {
+            'trip_id': trip_id,
+            'route_id': trip.get('route_id') or static_trip.get('route_id'),
+            'direction_id': int(direction_id) if direction_id is not None else None,
+            'headsign': static_trip.get('trip_headsign'),
+            'service_date': service_date,
+            'cancelled': trip.get('schedule_relationship') in ('CANCELED', 'CANCELLED'),
+        }
+
+    def _stop_calls(self, trip_update: Dict, info: Dict) -> List[Dict]:
+        """Resolve every stop_time_update to a stop, sequence and times."""
+        static_times = None
+        calls = []
+        for stu in trip_update.get('stop_time_update', []):
+            stop_id = stu.get('stop_id')
+            stop_sequence = stu.get('stop_sequence')
+
+            static_row = None
+            if stop_id is None or stop_sequence is None or \
+                    'time' not in (stu.get('departure') or stu.get('arrival') or {}):
+                if static_times is None:
+                    static_times = self.static.trip_stop_times(info['trip_id']) if info['trip_id'] else []
+                static_row = next(
+                    (row for row in static_times
+                     if row['stop_sequence'] == stop_sequence or
+                     (stop_sequence is None and row['stop_id'] == stop_id)),
+                    None
+                )
+                if static_row is not None:
+                    stop_id = stop_id or static_row['stop_id']
+                    stop_sequence = stop_sequence if stop_sequence is not None else static_row['stop_sequence']
+
+            calls.append({
+                'stop_id': stop_id,
+                'stop_sequence': stop_sequence,
+                'arrival': self._event_time(stu.get('arrival'), static_row, 'arrival_seconds',
+                                            info['service_date']),
+                'departure': self._event_time(stu.get('departure'), static_row, 'departure_seconds',
+                                              info['service_date']),
+                'uncertainty': (stu.get('departure') or {}).get('uncertainty'),
+                'skipped': stu.get('schedule_relationship') in ('SKIPPED', 'NO_DATA'),
+            })
+        return calls
+
+    def _event_time(self, event: Optional[Dict], static_row: Optional[Dict], field: str,
+                    service_date: date) -> Optional[datetime]:
+        """Absolute time of a StopTimeEvent, or scheduled time plus delay."""
+        if not event:
+            return None
+        if 'time' in event:
+            return datetime.fromtimestamp(int(event['time']), self.static.timezone)
+        if 'delay' in event and static_row is not None and static_row[field] is not None:
+            return service_time(service_date, static_row[field] + int(event['delay']),
+                                self.static.timezone)
+        return None
+
+    def get_predictions_filtered(
+        self,
+        stop_id: str,
+        direction_id: str,
+        route_id: Optional[str] = None,
+        count: int = 3
+    ) -> List[Prediction]:
+        """
+        Get filtered predictions for a stop from the TripUpdates feed.
+
+        Args:
+            stop_id: GTFS stop ID or parent station ID
+            direction_id: "0" or "1"
+            route_id: Optional route ID, or comma separated route IDs
+            count: Maximum number of predictions to return
+
+        Returns:
+            Predictions sorted by departure time
+        """
+        try:
+            stop_ids = set(self.static.stop_ids_for(stop_id))
+            route_ids = set(route_id.split(',')) if route_id else None
+            now = self.clock.now()
+
+            predictions = []
+            for trip_update in self._trip_updates():
+                info = self._trip_info(trip_update)
+                if info['cancelled']:
+                    continue
+                if route_ids is not None and info['route_id'] not in route_ids:
+                    continue
+                if info['direction_id'] is not None and info['direction_id'] != int(direction_id):
+                    continue
+
+                for call in self._stop_calls(trip_update, info):
+                    if call['stop_id'] not in stop_ids or call['skipped']:
+                        continue
+                    departure = call['departure'] or call['arrival']
+                    if departure is None or departure < now:
+                        continue
+                    route = Route(id=info['route_id']) if info['route_id'] else None
+                    predictions.append(Prediction(
+                        id=f"{info['trip_id']}-{call['stop_sequence']}",
+                        departure_time=departure,
+                        arrival_time=call['arrival'],
+                        direction_id=info['direction_id'],
+                        departure_uncertainty=call['uncertainty'],
+                        stop_sequence=call['stop_sequence'],
+                        route=route,
+                        trip=Trip(id=info['trip_id'], headsign=info['headsign'],
+                                  direction_id=info['direction_id'], route=route),
+                        stop=Stop(id=call['stop_id']),
+                    ))
+
+            predictions.sort(key=lambda p: p.time)
+            return predictions[:count]
+
+        except Exception as e:
+            self.logger.error(f"Error getting GTFS-Realtime predictions: {str(e)}")
+            return []
+
+    def get_journey_trips(
+        self,
+        route_id: str,
+        from_stop_id: str,
+        to_stop_id: str,
+        count: int = 3
+    ) -> List[JourneyTrip]:
+        """
+        Find the next trips in the TripUpdates feed that stop at from_stop_id
+        and later at to_stop_id.
+        """
+        try:
+            from_stops = set(self.static.stop_ids_for(from_stop_id))
+            to_stops = set(self.static.stop_ids_for(to_stop_id))
+            route_ids = set(route_id.split(',')) if route_id else None
+            now = self.clock.now()
+
+            trips = []
+            for trip_update in self._trip_updates():
+                info = self._trip_info(trip_update)
+                if info['cancelled']:
+                    continue
+                if route_ids is not None and info['route_id'] not in route_ids:
+                    continue
+
+                calls = [call for call in self._stop_calls(trip_update, info) if not call['skipped']]
+                origin = next((c for c in calls if c['stop_id'] in from_stops), None)
+                destination = next((c for c in calls if c['stop_id'] in to_stops), None)
+                if origin is None or destination is None:
+                    continue
+                if origin['stop_sequence'] is None or destination['stop_sequence'] is None:
+                    continue
+                if origin['stop_sequence'] >= destination['stop_sequence']:
+                    continue
+
+                departure = origin['departure'] or origin['arrival']
+                arrival = destination['arrival'] or destination['departure']
+                if departure is None or arrival is None or departure <= now:
+                    continue
+
+                trips.append(JourneyTrip(
+                    trip_id=info['trip_id'],
+                    direction_id=info['direction_id'],
+                    departure_time=departure,
+                    arrival_time=arrival,
+                    from_arrival_time=origin['arrival'],
+                    destination=info['headsign'],
+                    predicted=True,
+                ))
+
+            trips.sort(key=lambda trip: trip.departure_time)
+            return trips[:count]
+
+        except Exception as e:
+            self.logger.error(f"Error getting GTFS-Realtime journey trips: {str(e)}")
+            return []
+
+    def get_alerts(self, route_ids: List[str], stop_ids: List[str]) -> List[Alert]:
+        """Alerts from the GTFS-Realtime Alerts feed affecting the routes or stops."""
+        if not self.alerts_url:
+            return []
+        try:
+            feed = self._fetch_feed(self.alerts_url)
+            stops = set(stop_ids)
+            for stop_id in stop_ids:
+                stops.update(self.static.stop_ids_for(stop_id))
+
+            alerts = []
+            for entity in feed.get('entity', []):
+                if entity.get('is_deleted') or 'alert' not in entity:
+                    continue
+                alert = self._alert_from_entity(entity['id'], entity['alert'])
+                if alert.affects(route_ids, list(stops)):
+                    alerts.append(alert)
+            return alerts
+
+        except Exception as e:
+            self.logger.error(f"Error getting GTFS-Realtime alerts: {str(e)}")
+            return []
+
+    def _alert_from_entity(self, alert_id: str, alert: Dict) -> Alert:
+        periods = []
+        for period in alert.get('active_period', []):
+            start = period.get('start')
+            end = period.get('end')
+            periods.append((
+                datetime.fromtimestamp(int(start), self.static.timezone) if start else None,
+                datetime.fromtimestamp(int(end), self.static.timezone) if end else None,
+            ))
+
+        return Alert(
+            id=alert_id,
+            header=self._translated(alert.get('header_text')),
+            effect=alert.get('effect', 'UNKNOWN_EFFECT'),
+            severity=GTFS_RT_SEVERITY.get(alert.get('severity_level'), GTFS_RT_SEVERITY['UNKNOWN_SEVERITY']),
+            active_period=periods,
+            informed_entity=[
+                (entity.get('route_id'), entity.get('stop_id'))
+                for entity in alert.get('informed_entity', [])
+            ],
+        )
+
+    @staticmethod
+    def _translated(text: Optional[Dict]) -> str:
+        """Pick the English (or first) translation of a TranslatedString."""
+        translations = (text or {}).get('translation', [])
+        for translation in translations:
+            if translation.get('language', 'en').startswith('en'):
+                return translation.get('text', '')
+        return translations[0].get('text', '') if translations else ''
+
+    def get_routes_at_stop(self, stop_id: str) -> List[Route]:
+        """Routes serving a stop according to the static feed."""
+        try:
+            return [Route(
+                id=route['route_id'],
+                long_name=route['route_long_name'] or None,
+                short_name=route['route_short_name'] or None,
+                type=route['route_type'],
+            ) for route in self.static.routes_at_stop(stop_id)]
+        except Exception as e:
+            self.logger.error(f"Error getting routes at stop: {str(e)}")
+            return []
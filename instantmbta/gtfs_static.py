@@ -0,0 +1,339 @@
+"""Local SQLite copy of a GTFS static feed, used for offline schedule fallback.
+
+Download the MBTA feed from https://cdn.mbta.com/MBTA_GTFS.zip and point
+the `gtfs.zip` config option at it. The feed is imported once into SQLite
+and re-imported whenever the zip file is newer than the database.
+"""
+
+import csv
+import io
+import logging
+import sqlite3
+import zipfile
+from datetime import date, datetime, time, timedelta, timezone
+from pathlib import Path
+from typing import Dict, Iterator, List, Optional, Set
+from zoneinfo import ZoneInfo
+
+from .clock import MBTA_TIMEZONE
+from .models import Prediction, Route, Trip
+
+logger = logging.getLogger('instantmbta.gtfs_static')
+
+# All MBTA service (and so the feed's times) is in Boston local time. Other
+# agencies' feeds declare their own timezone in agency.txt.
+AGENCY_TIMEZONE = MBTA_TIMEZONE
+
+SCHEMA = """
+CREATE TABLE IF NOT EXISTS agency (
+    agency_id TEXT,
+    agency_name TEXT,
+    agency_timezone TEXT
+);
+CREATE TABLE IF NOT EXISTS stops (
+    stop_id TEXT PRIMARY KEY,
+    stop_name TEXT,
+    parent_station TEXT,
+    location_type INTEGER,
+    platform_code TEXT
+);
+CREATE TABLE IF NOT EXISTS routes (
+    route_id TEXT PRIMARY KEY,
+    route_short_name TEXT,
+    route_long_name TEXT,
+    route_type INTEGER
+);
+CREATE TABLE IF NOT EXISTS trips (
+    trip_id TEXT PRIMARY KEY,
+    route_id TEXT,
+    service_id TEXT,
+    trip_headsign TEXT,
+    trip_short_name TEXT,
+    direction_id INTEGER
+);
+CREATE TABLE IF NOT EXISTS stop_times (
+    trip_id TEXT,
+    arrival_seconds INTEGER,
+    departure_seconds INTEGER,
+    stop_id TEXT,
+    stop_sequence INTEGER
+);
+CREATE TABLE IF NOT EXISTS calendar (
+    service_id TEXT PRIMARY KEY,
+    monday INTEGER, tuesday INTEGER, wednesday INTEGER, thursday INTEGER,
+    friday INTEGER, saturday INTEGER, sunday INTEGER,
+    start_date TEXT,
+    end_date TEXT
+);
+CREATE TABLE IF NOT EXISTS calendar_dates (
+    service_id TEXT,
+    date TEXT,
+    exception_type INTEGER
+);
+CREATE INDEX IF NOT EXISTS idx_stops_parent ON stops(parent_station);
+CREATE INDEX IF NOT EXISTS idx_stop_times_stop ON stop_times(stop_id, departure_seconds);
+CREATE INDEX IF NOT EXISTS idx_calendar_dates_date ON calendar_dates(date);
+"""
+
+WEEKDAYS = ('monday', 'tuesday', 'wednesday', 'thursday', 'friday', 'saturday', 'sunday')
+
+
+def parse_gtfs_time(value: str) -> Optional[int]:
+    """
+    Convert a GTFS HH:MM:SS time to seconds since the start of the service
+    day. Hours may exceed 23 for trips running past midnight (25:10:00).
+    """
+    if not value:
+        return None
+    hours, minutes, seconds = (int(part) for part in value.strip().split(':'))
+    return hours * 3600 + minutes * 60 + seconds
+
+
+def service_day_origin(service_date: date, tz=AGENCY_TIMEZONE) -> datetime:
+    """
+    The instant GTFS times on a service date are measured from: noon minus
+    12 hours, which is midnight except on daylight saving changeover days.
+    """
+    # Arithmetic on aware datetimes sharing a tzinfo is wall-clock
+    # arithmetic, so step back through UTC to get the real elapsed time
+    noon = datetime.combine(service_date, time(12), tzinfo=tz).astimezone(timezone.utc)
+    return (noon - timedelta(hours=12)).astimezone(tz)
+
+
+def service_time(service_date: date, seconds: int, tz=AGENCY_TIMEZONE) -> datetime:
+    """The local time `seconds` after the start of a service date."""
+    origin = service_day_origin(service_date, tz).astimezone(timezone.utc)
+    return (origin + timedelta(seconds=seconds)).astimezone(tz)
+
+
+def _read_csv(zf: zipfile.ZipFile, name: str) -> Iterator[Dict[str, str]]:
+    if name not in zf.namelist():
+        return iter(())
+    handle = io.TextIOWrapper(zf.open(name), encoding='utf-8-sig', newline='')
+    return csv.DictReader(handle)
+
+
+def _int_or_none(value: Optional[str]) -> Optional[int]:
+    return int(value) if value not in (None, '') else None
+
+
+class GTFSStaticStore:
+    """Query scheduled service from a GTFS feed imported into SQLite."""
+
+    def __init__(self, db_path):
+        self.db_path = Path(db_path)
+        self.logger = logger
+        self.conn = sqlite3.connect(str(self.db_path), check_same_thread=False)
+        self.conn.row_factory = sqlite3.Row
+        self.conn.executescript(SCHEMA)
+        self.timezone = self._load_timezone()
+
+    def _load_timezone(self):
+        row = self.conn.execute("SELECT agency_timezone FROM agency LIMIT 1").fetchone()
+        if row and row['agency_timezone']:
+            return ZoneInfo(row['agency_timezone'])
+        return AGENCY_TIMEZONE
+
+    @classmethod
+    def open(cls, db_path, zip_path=None) -> 'GTFSStaticStore':
+        """
+        Open the database, importing zip_path first if the database is
+        missing, empty, or older than the zip file.
+        """
+        db_path = Path(db_path)
+        needs_import = False
+        if zip_path is not None:
+            zip_path = Path(zip_path)
+            needs_import = (not db_path.exists() or
+                            db_path.stat().st_mtime < zip_path.stat().st_mtime)
+
+        store = cls(db_path)
+        if zip_path is not None and (needs_import or store.is_empty()):
+            store.import_zip(zip_path)
+        return store
+
+    def close(self):
+        self.conn.close()
+
+    def is_empty(self) -> bool:
+        return self.conn.execute("SELECT 1 FROM stop_times LIMIT 1").fetchone() is None
+
+    def import_zip(self, zip_path):
+        """Replace the database contents with the feed in zip_path."""
+        self.logger.info("Importing GTFS static feed from %s", zip_path)
+        with zipfile.ZipFile(zip_path) as zf, self.conn:
+            for table in ('agency', 'stops', 'routes', 'trips', 'stop_times', 'calendar', 'calendar_dates'):
+                self.conn.execute(f"DELETE FROM {table}")
+
+            self.conn.executemany(
+                "INSERT INTO agency VALUES (?, ?, ?)",
+                ((row.get('agency_id'), row.get('agency_name'), row.get('agency_timezone'))
+                 for row in _read_csv(zf, 'agency.txt'))
+            )
+
+            self.conn.executemany(
+                "INSERT OR REPLACE INTO stops VALUES (?, ?, ?, ?, ?)",
+                ((row['stop_id'], row.get('stop_name'), row.get('parent_station') or None,
+                  _int_or_none(row.get('location_type')), row.get('platform_code') or None)
+                 for row in _read_csv(zf, 'stops.txt'))
+            )
+            self.conn.executemany(
+                "INSERT OR REPLACE INTO routes VALUES (?, ?, ?, ?)",
+                ((row['route_id'], row.get('route_short_name'), row.get('route_long_name'),
+                  _int_or_none(row.get('route_type')))
+                 for row in _read_csv(zf, 'routes.txt'))
+            )
+            self.conn.executemany(
+                "INSERT OR REPLACE INTO trips VALUES (?, ?, ?, ?, ?, ?)",
+                ((row['trip_id'], row['route_id'], row['service_id'], row.get('trip_headsign'),
+                  row.get('trip_short_name'), _int_or_none(row.get('direction_id')))
+                 for row in _read_csv(zf, 'trips.txt'))
+            )
+            self.conn.executemany(
+                "INSERT INTO stop_times VALUES (?, ?, ?, ?, ?)",
+                ((row['trip_id'], parse_gtfs_time(row.get('arrival_time')),
+                  parse_gtfs_time(row.get('departure_time')), row['stop_id'],
+                  int(row['stop_sequence']))
+                 for row in _read_csv(zf, 'stop_times.txt'))
+            )
+            self.conn.executemany(
+                "INSERT OR REPLACE INTO calendar VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                ((row['service_id'], *(int(row[day]) for day in WEEKDAYS),
+                  row['start_date'], row['end_date'])
+                 for row in _read_csv(zf, 'calendar.txt'))
+            )
+            self.conn.executemany(
+                "INSERT INTO calendar_dates VALUES (?, ?, ?)",
+                ((row['service_id'], row['date'], int(row['exception_type']))
+                 for row in _read_csv(zf, 'calendar_dates.txt'))
+            )
+        self.timezone = self._load_timezone()
+        self.logger.info("GTFS import complete")
+
+    def active_services(self, service_date: date) -> Set[str]:
+        """Service IDs running on a date, after calendar_dates exceptions."""
+        day = service_date.strftime('%Y%m%d')
+        weekday = WEEKDAYS[service_date.weekday()]
+        services = {
+            row['service_id'] for row in self.conn.execute(
+                f"SELECT service_id FROM calendar WHERE {weekday} = 1 "
+                "AND start_date <= ? AND end_date >= ?", (day, day))
+        }
+        for row in self.conn.execute(
+                "SELECT service_id, exception_type FROM calendar_dates WHERE date = ?", (day,)):
+            if row['exception_type'] == 1:
+                services.add(row['service_id'])
+            elif row['exception_type'] == 2:
+                services.discard(row['service_id'])
+        return services
+
+    def stop_ids_for(self, stop_id: str) -> List[str]:
+        """The stop itself plus any platforms whose parent station it is."""
+        children = [row['stop_id'] for row in self.conn.execute(
+            "SELECT stop_id FROM stops WHERE parent_station = ?", (stop_id,))]
+        return [stop_id] + children
+
+    def stop_name(self, stop_id: str) -> Optional[str]:
+        row = self.conn.execute("SELECT stop_name FROM stops WHERE stop_id = ?", (stop_id,)).fetchone()
+        return row['stop_name'] if row else None
+
+    def all_stops(self) -> List[Dict]:
+        """Every stop, station and platform in the feed."""
+        return [dict(row) for row in self.conn.execute(
+            "SELECT stop_id, stop_name, parent_station, location_type FROM stops ORDER BY stop_id")]
+
+    def all_routes(self) -> List[Dict]:
+        return [dict(row) for row in self.conn.execute("SELECT * FROM routes ORDER BY route_id")]
+
+    def trip(self, trip_id: str) -> Optional[Dict]:
+        """Route, headsign and direction for a trip, or None if unknown."""
+        row = self.conn.execute("SELECT * FROM trips WHERE trip_id = ?", (trip_id,)).fetchone()
+        return dict(row) if row else None
+
+    def trip_stop_times(self, trip_id: str) -> List[Dict]:
+        """All scheduled stops of a trip in stop_sequence order."""
+        return [dict(row) for row in self.conn.execute(
+            "SELECT * FROM stop_times WHERE trip_id = ? ORDER BY stop_sequence", (trip_id,))]
+
+    def routes_at_stop(self, stop_id: str) -> List[Dict]:
+        """Routes with scheduled service at a stop or any of its platforms."""
+        stop_ids = self.stop_ids_for(stop_id)
+        return [dict(row) for row in self.conn.execute(
+            "SELECT DISTINCT r.* FROM routes r "
+            "JOIN trips t ON t.route_id = r.route_id "
+            "JOIN stop_times st ON st.trip_id = t.trip_id "
+            f"WHERE st.stop_id IN ({','.join('?' * len(stop_ids))}) "
+            "ORDER BY r.route_id", stop_ids)]
+
+    def scheduled_departures(
+        self,
+        stop_id: str,
+        direction_id: str,
+        route_id: Optional[str] = None,
+        count: int = 3,
+        now: Optional[datetime] = None
+    ) -> List[Prediction]:
+        """
+        Get the next scheduled departures as predictions, in the same form as
+        InfoGather.get_predictions_filtered, flagged with scheduled=True.
+
+        Args:
+            stop_id: GTFS stop ID or parent station ID
+            direction_id: "0" or "1"
+            route_id: Optional route ID, or comma separated route IDs
+            count: Maximum number of departures to return
+            now: Current time; defaults to the system clock
+
+        Returns:
+            Departures sorted by departure time
+        """
+        now = (now or datetime.now(self.timezone)).astimezone(self.timezone)
+        stop_ids = self.stop_ids_for(stop_id)
+        route_ids = route_id.split(',') if route_id else []
+
+        departures = []
+        # Yesterday's service day still has trips running after midnight
+        for service_date in (now.date() - timedelta(days=1), now.date()):
+            services = self.active_services(service_date)
+            if not services:
+                continue
+            origin = service_day_origin(service_date, self.timezone).astimezone(timezone.utc)
+            min_seconds = int((now.astimezone(timezone.utc) - origin).total_seconds())
+
+            query = (
+                "SELECT st.trip_id, st.arrival_seconds, st.departure_seconds, st.stop_sequence, "
+                "t.route_id, t.trip_headsign, t.direction_id "
+                "FROM stop_times st JOIN trips t ON t.trip_id = st.trip_id "
+                f"WHERE st.stop_id IN ({','.join('?' * len(stop_ids))}) "
+                f"AND t.service_id IN ({','.join('?' * len(services))}) "
+                "AND t.direction_id = ? "
+                "AND COALESCE(st.departure_seconds, st.arrival_seconds) >= ? "
+            )
+            params = [*stop_ids, *services, int(direction_id), min_seconds]
+            if route_ids:
+                query += f"AND t.route_id IN ({','.join('?' * len(route_ids))}) "
+                params.extend(route_ids)
+            query += "ORDER BY COALESCE(st.departure_seconds, st.arrival_seconds) LIMIT ?"
+            params.append(count)
+
+            for row in self.conn.execute(query, params):
+                departure = row['departure_seconds'] if row['departure_seconds'] is not None \
+                    else row['arrival_seconds']
+                departure_time = service_time(service_date, departure, self.timezone)
+                arrival_time = (service_time(service_date, row['arrival_seconds'], self.timezone)
+                                if row['arrival_seconds'] is not None else None)
+                route = Route(id=row['route_id'])
+                departures.append(Prediction(
+                    id=f"schedule-{row['trip_id']}-{row['stop_sequence']}",
+                    departure_time=departure_time,
+                    arrival_time=arrival_time,
+                    direction_id=row['direction_id'],
+                    stop_sequence=row['stop_sequence'],
+                    route=route,
+                    trip=Trip(id=row['trip_id'], headsign=row['trip_headsign'],
+                              direction_id=row['direction_id'], route=route),
+                    scheduled=True,
+                ))
+
+        departures.sort(key=lambda d: d.time)
+        return departures[:count]
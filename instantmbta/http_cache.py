@@ -0,0 +1,173 @@
+"""Response caching for the MBTA V3 API.
+
+Two layers, both keyed by request URL:
+
+- Every response with a Last-Modified header is kept in memory, and the next
+  request for the same URL sends If-Modified-Since. When nothing changed the
+  API answers 304 Not Modified with an empty body, and the cached response is
+  used instead.
+- Slowly changing resources (routes and stops) are also written to a disk
+  cache and reused without any request until their TTL runs out, so a
+  restart doesn't download them again.
+"""
+
+from collections import OrderedDict
+import json
+import logging
+import os
+from pathlib import Path
+from typing import Dict, Optional
+from urllib.parse import urlsplit
+
+import requests
+from requests.structures import CaseInsensitiveDict
+
+from .clock import Clock
+
+HTTP_CACHE = "instantmbta_http_cache.json"
+CACHE_VERSION = 1
+
+# Path prefix → seconds a response may be reused without asking the API
+DISK_TTLS = {
+    '/routes': 24 * 60 * 60,
+    '/stops': 24 * 60 * 60,
+}
+
+MAX_MEMORY_ENTRIES = 256
+
+
+def build_response(url: str, status: int, headers: Dict[str, str], body: str) -> requests.Response:
+    """A requests.Response with the given content, as if it came off the wire."""
+    response = requests.Response()
+    response.status_code = status
+    response.headers = CaseInsensitiveDict(headers)
+    response._content = body.encode('utf-8')
+    response.encoding = 'utf-8'
+    response.url = url
+    return response
+
+
+class ResponseCache:
+    """Last-Modified revalidation in memory, plus a TTL disk cache."""
+
+    def __init__(self, path: Optional[str] = None, ttls: Optional[Dict[str, float]] = None,
+                 clock: Optional[Clock] = None):
+        """
+        Args:
+            path: Disk cache file; None keeps everything in memory
+            ttls: Path prefix → TTL in seconds for the disk cache
+            clock: Time source for TTLs
+        """
+        self.path = Path(path) if path else None
+        self.ttls = DISK_TTLS if ttls is None else ttls
+        self.clock = clock or Clock()
+        self.logger = logging.getLogger('instantmbta.http_cache')
+        self._memory: 'OrderedDict[str, Dict]' = OrderedDict()
+        self._disk: Optional[Dict[str, Dict]] = None  # Loaded on first use
+
+    def _ttl(self, url: str) -> Optional[float]:
+        path = urlsplit(url).path
+        for prefix, ttl in self.ttls.items():
+            if path == prefix or path.startswith(prefix + '/'):
+                return ttl
+        return None
+
+    def _load_disk(self) -> Dict[str, Dict]:
+        if self._disk is None:
+            self._disk = {}
+            if self.path is not None and self.path.exists():
+                try:
+                    with open(self.path) as f:
+                        data = json.load(f)
+                    if data.get('version') == CACHE_VERSION:
+                        self._disk = data.get('entries', {})
+                except (OSError, ValueError) as e:
+                    self.logger.warning("Ignoring unreadable HTTP cache %s: %s", self.path, e)
+        return self._disk
+
+    def _save_disk(self):
+        # Write then rename so a crash never leaves a truncated cache
+        tmp_path = self.path.with_name(self.path.name + '.tmp')
+        try:
+            with open(tmp_path, 'w') as f:
+                json.dump({'version': CACHE_VERSION, 'entries': self._disk}, f)
+            os.replace(tmp_path, self.path)
+        except OSError as e:
+            self.logger.warning("Could not write HTTP cache %s: %s", self.path, e)
+
+    def _entry(self, url: str) -> Optional[Dict]:
+        entry = self._memory.get(url)
+        if entry is None and self.path is not None and self._ttl(url) is not None:
+            entry = self._load_disk().get(url)
+        return entry
+
+    def fresh(self, url: str) -> Optional[requests.Response]:
+        """A cached response still within its TTL, to use without a request."""
+        ttl = self._ttl(url)
+        if ttl is None or self.path is None:
+            return None
+        entry = self._entry(url)
+        if entry is None or self.clock.now().timestamp() - entry['stored'] > ttl:
+            return None
+        self.logger.debug("Using cached %s", url)
+        return build_response(url, entry['status'], entry['headers'], entry['body'])
+
+    def conditional_headers(self, url: str) -> Dict[str, str]:
+        """Headers to revalidate a cached response, if there is one."""
+        entry = self._entry(url)
+        if entry is None or not entry['headers'].get('Last-Modified'):
+            return {}
+        return {'If-Modified-Since': entry['headers']['Last-Modified']}
+
+    def update(self, url: str, response: requests.Response) -> requests.Response:
+        """
+        Store a response, or turn a 304 into the cached response it refers to.
+
+        Returns:
+            The response to use
+        """
+        if response.status_code == 304:
+            entry = self._entry(url)
+            if entry is None:
+                return response
+            entry['stored'] = self.clock.now().timestamp()
+            self._remember(url, entry)
+            self.logger.debug("Not modified: %s", url)
+            # The 304 carries the current rate budget
+            headers = dict(entry['headers'])
+            headers.update({k: v for k, v in response.headers.items()
+                            if k.lower().startswith('x-ratelimit-')})
+            return build_response(url, entry['status'], headers, entry['body'])
+
+        if response.status_code != 200:
+            return response
+        last_modified = response.headers.get('Last-Modified')
+        on_disk = self.path is not None and self._ttl(url) is not None
+        if not last_modified and not on_disk:
+            return response
+
+        headers = {name: response.headers.get(name) for name in ('Last-Modified', 'Content-Type')}
+        entry = {
+            'stored': self.clock.now().timestamp(),
+            'status': response.status_code,
+            'headers': {name: value for name, value in headers.items() if value},
+            'body': response.text,
+        }
+        self._remember(url, entry)
+        return response
+
+    def _remember(self, url: str, entry: Dict):
+        self._memory[url] = entry
+        self._memory.move_to_end(url)
+        while len(self._memory) > MAX_MEMORY_ENTRIES:
+            self._memory.popitem(last=False)
+        if self.path is not None and self._ttl(url) is not None:
+            disk = self._load_disk()
+            previous = disk.get(url)
+            disk[url] = entry
+            # A revalidation only moves 'stored', not worth rewriting a file
+            # holding the whole stops catalog for; it's saved with the next
+            # real change
+            if previous is None or any(previous[key] != entry[key]
+                                       for key in ('status', 'headers', 'body')):
+                self._save_disk()
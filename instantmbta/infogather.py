@@ -1,12 +1,19 @@
 """Module for gathering and processing MBTA transit information using their V3 API."""
 
-from datetime import datetime
 import time
 import logging
+from datetime import timedelta
 import logging.handlers
 import requests
-from . import secret_constants
 from typing import List, Dict, Optional
+from .provider import TransitProvider
+from .alerts import Alert
+from .jsonapi import ResourceIndex, next_link
+from .models import JourneyTrip, Line, Prediction, Route, Schedule, Stop, Vehicle
+from .clock import Clock
+from .credentials import API_KEY_HEADER
+from .ratelimit import RateBudget, RateLimiter
+from .http_cache import ResponseCache
 
 class CircuitBreaker:
     def __init__(self, failure_threshold=5, reset_timeout=60):
@@ -36,7 +43,6 @@ class CircuitBreaker:
                 self.state = "OPEN"
             raise e
 
-API_REQUEST = "api_key="+secret_constants.API_KEY
 API_URL = "https://api-v3.mbta.com"
 """
 "https://api-v3.mbta.com/routes/Orange" 
@@ -52,27 +58,58 @@ OUTBOUND = "1"
 LOG_FILENAME = 'instant.log'
 STANDARD_TIMEOUT = 30
 UPDATE_INTERVAL_SECONDS = 60
+MAX_PAGES = 50  # Stop following links.next after this many pages
+MAX_TRIP_STOPS = 500  # Trips whose stop order is remembered for counting stops away
+MAX_LATE_MINUTES = 60  # How far back get_departures looks for trains running late
 
-class InfoGather():
+class InfoGather(TransitProvider):
     """
     # A collection of functions leveraging the MBTA API (v3)
     # See: https://www.mbta.com/developers/v3-api
     """
 
-    def __init__(self):
+    def __init__(self, gtfs_store=None, clock: Optional[Clock] = None,
+                 api_url: Optional[str] = None, timeout: float = STANDARD_TIMEOUT,
+                 transport=None, api_key: Optional[str] = None,
+                 cache_path: Optional[str] = None):
         self.logger = logging.getLogger('instantmbta.infogather')
+        # Base URL of the V3 API, without a trailing slash
+        self.api_url = (api_url or API_URL).rstrip('/')
+        self.timeout = timeout
+        # Sent as a header so it never appears in logged URLs; None is anonymous access
+        self.api_key = api_key
+        # Optional GTFSStaticStore used when live predictions are unavailable
+        self.gtfs_store = gtfs_store
+        self.clock = clock or Clock()
+        # Anything with requests' get(url, **kwargs), e.g. a recording.RecordingTransport;
+        # a pooled session when omitted
+        self.transport = transport
+        self.session = requests.Session()
+        self.session.headers['Accept-Encoding'] = 'gzip'
+        # Revalidates with If-Modified-Since; routes and stops also go to disk at cache_path
+        self.cache = ResponseCache(cache_path, clock=self.clock)
         self.circuit_breaker = CircuitBreaker()
+        self.rate_limiter = RateLimiter(self.clock)
+        # Trip ID → its stop sequences in order; a trip's stops don't change
+        self._trip_stops: Dict[str, List[int]] = {}
         self.last_successful_request = None
         self.consecutive_failures = 0
         self.max_retries = 5
         self.base_retry_delay = 5  # seconds
 
+    def _get(self, url, **kwargs):
+        headers = self.cache.conditional_headers(url)
+        if self.api_key:
+            headers[API_KEY_HEADER] = self.api_key
+        response = (self.transport or self.session).get(url, headers=headers, timeout=self.timeout, **kwargs)
+        return self.cache.update(url, response)
+
     def verify_connection(self):
         """Verify connection to the MBTA API"""
         try:
             # Simple request to check connectivity
-            test_request = API_URL + '/routes?' + API_REQUEST
-            response = requests.get(test_request, timeout=STANDARD_TIMEOUT)
+            test_request = self.api_url + '/routes'
+            response = self._get(test_request)
             response.raise_for_status()
             self.last_successful_request = time.time()
             self.consecutive_failures = 0
@@ -83,179 +120,146 @@ class InfoGather():
             return False
 
     def _make_api_request(self, request_string):
-        """Make an API request with circuit breaker protection and retry logic"""
+        """
+        Make an API request with circuit breaker protection, retry logic and
+        rate limiting. A 429 is retried once its Retry-After has passed.
+        """
         def _request():
-            return requests.get(request_string, timeout=STANDARD_TIMEOUT)
+            return self._get(request_string)
+        
+        cached = self.cache.fresh(request_string)
+        if cached is not None:
+            return cached
         
         retry_delay = self.base_retry_delay
         
         for attempt in range(self.max_retries):
+            self.rate_limiter.wait()
             try:
-                if not self.verify_connection():
-                    if attempt < self.max_retries - 1:
-                        self.logger.info("Waiting %d seconds before retry %d/%d", 
-                                       retry_delay, attempt + 1, self.max_retries)
-                        time.sleep(retry_delay)
-                        retry_delay *= 2  # Exponential backoff
-                        continue
-                    else:
-                        raise Exception("Max retries exceeded")
-                
-                return self.circuit_breaker.execute(_request)
+                response = self.circuit_breaker.execute(_request)
             except Exception as e:
+                self.consecutive_failures += 1
                 if attempt == self.max_retries - 1:
                     self.logger.error("Circuit breaker prevented request after %d retries: %s", 
                                     self.max_retries, e)
                     raise
+                self.logger.info("Waiting %d seconds before retry %d/%d: %s",
+                                 retry_delay, attempt + 1, self.max_retries, e)
+                self.clock.sleep(retry_delay)
                 retry_delay *= 2  # Exponential backoff
+                continue
+
+            self.rate_limiter.update(response)
+            if response.status_code == 429 and attempt < self.max_retries - 1:
+                continue
+            self.last_successful_request = time.time()
+            self.consecutive_failures = 0
+            return response
 
-    def get_line(self, line_name):
+    def rate_budget(self) -> RateBudget:
+        """The API rate budget as of the last response."""
+        return self.rate_limiter.budget()
+
+    def _get_index(self, request_string: str, paginate: bool = True) -> ResourceIndex:
+        """
+        Request a JSON:API document and index its resources. A paginated
+        collection is followed through links.next and returned as one.
+
+        Raises:
+            requests.HTTPError: if the API answers anything but 200
+        """
+        document = None
+        url = request_string
+        for _ in range(MAX_PAGES):
+            response = self._make_api_request(url)
+            if response.status_code != 200:
+                raise requests.HTTPError(f"{response.status_code} from {url}", response=response)
+            page = response.json()
+            if document is None:
+                document = page
+            else:
+                document['data'] = document.get('data', []) + page.get('data', [])
+                document['included'] = document.get('included', []) + page.get('included', [])
+            url = next_link(page) if paginate else None
+            if not url:
+                break
+        else:
+            self.logger.warning("Stopped after %d pages of %s", MAX_PAGES, request_string)
+        return ResourceIndex(document)
+
+    def get_line(self, line_name) -> Optional[Line]:
         """
         Get information for a specific line
         """
-        request_string = API_URL+'/lines/'+line_name+'?'+API_REQUEST
+        request_string = self.api_url+'/lines/'+line_name
         self.logger.debug("Getting Line Information %s", request_string)
-        return self._make_api_request(request_string)
+        return next(iter(self._get_index(request_string).models()), None)
 
-    def get_routes(self, get_route_id):
+    def get_routes(self, get_route_id) -> Optional[Route]:
         """
         Get information for a specific route
         """
-        request_string = API_URL+'/routes/'+get_route_id+'?'+API_REQUEST
+        request_string = self.api_url+'/routes/'+get_route_id
         self.logger.debug("Getting Route Information %s", request_string)
-        return self._make_api_request(request_string)
+        return next(iter(self._get_index(request_string).models()), None)
 
-    def get_schedule(self, get_route_id, stop_id, direction_id):
+    def get_schedule(self, get_route_id, stop_id, direction_id) -> List[Schedule]:
         """
         Get the schedule given a route, stop and direction
         """
         hh_mm = self.get_current_time()
-        request_string = API_URL+'/schedules?include=stop,prediction&filter[route]='+\
-            get_route_id+'&filter[stop]='+stop_id+'&filter[direction_id]='+direction_id+'&sort=departure_time&filter[min_time]='+hh_mm+'&'+API_REQUEST
+        service_date = self.clock.service_date().isoformat()
+        request_string = self.api_url+'/schedules?include=stop,prediction&filter[route]='+\
+            get_route_id+'&filter[stop]='+stop_id+'&filter[direction_id]='+direction_id+'&sort=departure_time&filter[date]='+service_date+'&filter[min_time]='+hh_mm
         self.logger.debug("Getting schedule %s", request_string)
-        return self._make_api_request(request_string)
+        return self._get_index(request_string).models()
 
-    def find_prediction_by_id(self, prediction_id, predictions):
+    def find_prediction_by_id(self, prediction_id: str,
+                              predictions: List[Prediction]) -> Optional[Prediction]:
         """
-        Given a prediction ID, find the prediction in a list of prediction data using dictionary lookup.
+        Given a prediction ID, find the prediction in a list of predictions.
         
         Args:
             prediction_id (str): The ID of the prediction to find
-            predictions (dict): The predictions data from the API
+            predictions (list): Predictions, e.g. from get_predictions_filtered
             
         Returns:
-            dict: The matching prediction or None if not found
+            Prediction: The matching prediction or None if not found
         """
-        # Create a dictionary mapping prediction IDs to their data
-        prediction_map = {pred['id']: pred for pred in predictions['data']}
-        
-        # Look up the prediction directly
-        prediction = prediction_map.get(prediction_id)
+        prediction = next((p for p in predictions if p.id == prediction_id), None)
         
         if prediction is None:
             self.logger.error("No prediction found for ID: %s", prediction_id)
         
         return prediction
 
-    def get_stops(self, for_route_id):
+    def get_stops(self, for_route_id) -> List[Stop]:
         """
         Given a route id, get the stops associated with the route.
         """
-        r = requests.get(API_URL+'/stops?filter[route]='+for_route_id+'&'+API_REQUEST, timeout=STANDARD_TIMEOUT)
-        return r
+        return self._get_index(self.api_url+'/stops?filter[route]='+for_route_id).models()
 
-    def get_current_time(self):
+    def get_all_stops(self) -> List[Stop]:
         """
-        Get the current time of the system
+        Get every station and stop (but not entrances or nodes).
         """
-        current_time = datetime.now().strftime('%H:%M')
-        return current_time
+        request_string = (f"{self.api_url}/stops?filter[location_type]=0,1"
+                          f"&fields[stop]=name,location_type,parent_station")
+        self.logger.debug("Getting all stops %s", request_string)
+        return self._get_index(request_string).models()
 
-    def get_current_schedule(self, route_id, stop_id):
-        """Get current schedule for a route and stop."""
-        try:
-            current_time = datetime.now().astimezone()
-            current_date = current_time.date()
-            
-            # Get predicted times
-            response = self._make_api_request(
-                f"{API_URL}/predictions?filter[route]={route_id}&filter[stop]={stop_id}&sort=departure_time&{API_REQUEST}"
-            )
-            
-            if response is None:
-                self.logger.error(f"Failed to get predicted times for route {route_id} at stop {stop_id}")
-                return None, None, None, None
-                
-            outbound_predicted_time_json = response.json()
-            self.logger.debug(f"Predicted times response: {outbound_predicted_time_json}")
-            
-            # Get scheduled times
-            response = self._make_api_request(
-                f"{API_URL}/schedules?filter[route]={route_id}&filter[stop]={stop_id}&sort=departure_time&{API_REQUEST}"
-            )
-            
-            if response is None:
-                self.logger.error(f"Failed to get scheduled times for route {route_id} at stop {stop_id}")
-                return None, None, None, None
-                
-            outbound_scheduled_time_json = response.json()
-            self.logger.debug(f"Scheduled times response: {outbound_scheduled_time_json}")
-
-            # Process predicted times
-            next_inbound_departure_time = None
-            next_outbound_departure_time = None
-            next_inbound_arrival_time = None
-            next_outbound_arrival_time = None
-            
-            if 'data' in outbound_predicted_time_json:
-                for prediction in outbound_predicted_time_json['data']:
-                    if 'attributes' in prediction:
-                        departure_time = prediction['attributes'].get('departure_time')
-                        arrival_time = prediction['attributes'].get('arrival_time')
-                        if departure_time or arrival_time:
-                            dt = datetime.fromisoformat(departure_time or arrival_time)
-                            if dt > current_time and dt.date() == current_date:
-                                if prediction['attributes'].get('direction_id') == 0:  # Inbound
-                                    if next_inbound_departure_time is None:
-                                        next_inbound_departure_time = departure_time or arrival_time
-                                    if next_inbound_arrival_time is None:
-                                        next_inbound_arrival_time = arrival_time
-                                else:  # Outbound
-                                    if next_outbound_departure_time is None:
-                                        next_outbound_departure_time = departure_time or arrival_time
-                                    if next_outbound_arrival_time is None:
-                                        next_outbound_arrival_time = arrival_time
-
-            # Process scheduled times
-            next_inbound_scheduled_time = None
-            next_outbound_scheduled_time = None
-            
-            if 'data' in outbound_scheduled_time_json:
-                for schedule in outbound_scheduled_time_json['data']:
-                    if 'attributes' in schedule:
-                        departure_time = schedule['attributes'].get('departure_time')
-                        if departure_time:
-                            dt = datetime.fromisoformat(departure_time)
-                            if dt > current_time and dt.date() == current_date:
-                                if schedule['attributes'].get('direction_id') == 0:  # Inbound
-                                    if next_inbound_scheduled_time is None:
-                                        next_inbound_scheduled_time = departure_time
-                                else:  # Outbound
-                                    if next_outbound_scheduled_time is None:
-                                        next_outbound_scheduled_time = departure_time
-
-            # Use arrival time as departure time if no departure time is available
-            if next_inbound_departure_time is None and next_inbound_arrival_time is not None:
-                next_inbound_departure_time = next_inbound_arrival_time
-            if next_outbound_departure_time is None and next_outbound_arrival_time is not None:
-                next_outbound_departure_time = next_outbound_arrival_time
-
-            return (next_inbound_arrival_time, next_outbound_arrival_time,
-                    next_inbound_departure_time, next_outbound_departure_time)
-                    
-        except Exception as e:
-            self.logger.error(f"Error getting schedule for route {route_id} at stop {stop_id}: {str(e)}")
-            return None, None, None, None
+    def get_all_routes(self) -> List[Route]:
+        """Get every route."""
+        request_string = f"{self.api_url}/routes?fields[route]=short_name,long_name,type"
+        self.logger.debug("Getting all routes %s", request_string)
+        return self._get_index(request_string).models()
+
+    def get_current_time(self):
+        """
+        Get the current time within the MBTA service day (HH:MM, past 24:00
+        after midnight)
+        """
+        return self.clock.service_time()
 
     def get_predictions_filtered(
         self,
@@ -263,7 +267,7 @@ class InfoGather():
         direction_id: str, 
         route_id: Optional[str] = None, 
         count: int = 3
-    ) -> List[Dict]:
+    ) -> List[Prediction]:
         """
         Get filtered predictions for a specific stop, direction, and optionally route.
         
@@ -274,63 +278,340 @@ class InfoGather():
             count: Maximum number of predictions to return
             
         Returns:
-            List of prediction dictionaries with departure times and route info
+            Predictions with departure times, with their trips included.
+            Cancelled and skipped stops are kept, at their scheduled time.
+            If the API can't be reached and a GTFS store is configured, scheduled
+            departures flagged with scheduled=True are returned instead.
         """
         try:
             # Build the request
-            request_string = f"{API_URL}/predictions?filter[stop]={stop_id}&filter[direction_id]={direction_id}"
+            request_string = (f"{self.api_url}/predictions?filter[stop]={stop_id}"
+                              f"&filter[direction_id]={direction_id}&include=trip,schedule")
             if route_id:
                 request_string += f"&filter[route]={route_id}"
-            request_string += f"&page[limit]={count * 2}&sort=departure_time&{API_REQUEST}"
+            request_string += f"&page[limit]={count * 2}&sort=departure_time"
             
             self.logger.debug(f"Getting filtered predictions: {request_string}")
-            response = self._make_api_request(request_string)
-            
-            if response is None or response.status_code != 200:
-                self.logger.error(f"Failed to get predictions for stop {stop_id}")
-                return []
-            
-            data = response.json()
-            predictions = []
-            
-            for item in data.get('data', []):
-                attrs = item.get('attributes', {})
-                relationships = item.get('relationships', {})
-                
-                # Get departure or arrival time
-                departure_time = attrs.get('departure_time')
-                arrival_time   = attrs.get('arrival_time')
-                if departure_time is None and arrival_time is None:
-                    continue
-
-                prediction = {
-                    'id': item.get('id'),
-                    'departure_time': departure_time or arrival_time,
-                    'arrival_time': arrival_time,
-                    'direction_id': attrs.get('direction_id'),
-                    'route_id': relationships.get('route', {}).get('data', {}).get('id'),
-                    'trip_id': relationships.get('trip', {}).get('data', {}).get('id'),
-                    'status': attrs.get('status'),
-                    'departure_uncertainty': attrs.get('departure_uncertainty'),
-                }
-                
-                # Get destination from trip if available
-                if 'included' in data:
-                    for inc in data['included']:
-                        if inc['type'] == 'trip' and inc['id'] == prediction['trip_id']:
-                            prediction['destination'] = inc['attributes'].get('headsign')
-                            break
-                            
-                predictions.append(prediction)
+            # page[limit] is only there to keep the response small
+            predictions = self._get_index(request_string, paginate=False).models()
+            now = self.clock.now()
+            # A cancelled prediction lingers after its scheduled time has passed
+            predictions = [p for p in predictions
+                           if p.time is not None and not (p.cancelled and p.time < now)]
+            # Without a departure_time, cancelled ones are sorted apart from the rest
+            predictions.sort(key=lambda p: p.time)
                 
             # Trim to the requested count *after* filtering
             return predictions[:count]
             
         except Exception as e:
             self.logger.error(f"Error getting filtered predictions: {str(e)}")
+            return self._scheduled_fallback(stop_id, direction_id, route_id, count)
+
+    def get_departures(
+        self,
+        stop_id: str,
+        direction_id: str,
+        route_id: Optional[str] = None,
+        count: int = 3
+    ) -> List[Prediction]:
+        """
+        Get the next scheduled departures merged with their predictions, for
+        services like the Commuter Rail where being late matters more than
+        the predicted time. The schedule's joined prediction supplies the
+        delay, status and track; a departure the API isn't predicting yet
+        has only the schedule, and its time is the scheduled time.
+
+        Returns:
+            Predictions with their schedule attached, sorted by time, as
+            get_predictions_filtered. Falls back the same way when the API
+            can't be reached.
+        """
+        try:
+            now = self.clock.now()
+            # Trains scheduled a while ago may not have left yet
+            since = now - timedelta(minutes=MAX_LATE_MINUTES)
+            if self.clock.service_date(since) != self.clock.service_date(now):
+                since = now
+            request_string = (f"{self.api_url}/schedules?filter[stop]={stop_id}"
+                              f"&filter[direction_id]={direction_id}"
+                              f"&filter[date]={self.clock.service_date(now).isoformat()}"
+                              f"&filter[min_time]={self.clock.service_time(since)}"
+                              f"&include=stop,trip,prediction,prediction.stop&sort=departure_time")
+            if route_id:
+                request_string += f"&filter[route]={route_id}"
+            self.logger.debug(f"Getting departures: {request_string}")
+
+            departures = []
+            for schedule in self._get_index(request_string).models():
+                if schedule.pickup_type == 1:
+                    # Drop-off only
+                    continue
+                prediction = schedule.prediction
+                if prediction is None:
+                    prediction = Prediction(
+                        id=schedule.id,
+                        direction_id=schedule.direction_id,
+                        stop_sequence=schedule.stop_sequence,
+                        route=schedule.route,
+                        trip=schedule.trip,
+                        stop=schedule.stop,
+                    )
+                prediction.schedule = schedule
+                if prediction.time is None or prediction.time < now:
+                    continue
+                departures.append(prediction)
+
+            departures.sort(key=lambda p: p.time)
+            return departures[:count]
+
+        except Exception as e:
+            self.logger.error(f"Error getting departures: {str(e)}")
+            return self._scheduled_fallback(stop_id, direction_id, route_id, count)
+
+    def _scheduled_fallback(self, stop_id: str, direction_id: str,
+                            route_id: Optional[str], count: int) -> List[Prediction]:
+        """Scheduled departures from the GTFS store, or [] if there isn't one."""
+        if self.gtfs_store is None:
+            return []
+        try:
+            departures = self.gtfs_store.scheduled_departures(stop_id, direction_id, route_id, count,
+                                                              now=self.clock.now())
+            self.logger.info(f"Using {len(departures)} scheduled departures for stop {stop_id}")
+            return departures
+        except Exception as e:
+            self.logger.error(f"Error reading scheduled departures: {str(e)}")
+            return []
+
+    def get_vehicles(self, trip_ids: List[str]) -> List[Vehicle]:
+        """
+        Get the vehicles running the given trips, with the stop each one is
+        at or heading to. A trip that hasn't started yet has no vehicle.
+        """
+        if not trip_ids:
+            return []
+        request_string = f"{self.api_url}/vehicles?filter[trip]={','.join(trip_ids)}&include=stop"
+        self.logger.debug(f"Getting vehicles: {request_string}")
+        return self._get_index(request_string).models()
+
+    def _trip_stop_sequences(self, trip_ids: List[str]) -> Dict[str, List[int]]:
+        """The stop sequences each trip calls at, in order, from its schedule."""
+        missing = [trip_id for trip_id in trip_ids if trip_id not in self._trip_stops]
+        if missing:
+            if len(self._trip_stops) > MAX_TRIP_STOPS:
+                self._trip_stops.clear()
+            request_string = f"{self.api_url}/schedules?filter[trip]={','.join(missing)}"
+            self.logger.debug(f"Getting trip stops: {request_string}")
+            sequences: Dict[str, set] = {trip_id: set() for trip_id in missing}
+            for schedule in self._get_index(request_string).models():
+                if schedule.trip_id in sequences and schedule.stop_sequence is not None:
+                    sequences[schedule.trip_id].add(schedule.stop_sequence)
+            for trip_id, trip_sequences in sequences.items():
+                self._trip_stops[trip_id] = sorted(trip_sequences)
+        return {trip_id: self._trip_stops[trip_id] for trip_id in trip_ids}
+
+    def locate_vehicles(self, predictions: List[Prediction],
+                        count_stops: bool = True) -> List[Prediction]:
+        """
+        Attach the vehicle running each predicted trip and count the stops
+        between where it is and the predicted stop. Stop sequences usually
+        skip numbers (subway trips count in tens), so stops are counted in
+        the trip's schedule rather than by subtracting sequences.
+
+        Args:
+            predictions: Predictions to fill in
+            count_stops: Also fetch trip schedules to set stops_away; not
+                needed for just the vehicle's occupancy
+
+        Returns:
+            The same predictions; those without a vehicle are unchanged
+        """
+        trip_ids = sorted({p.trip_id for p in predictions if p.trip_id and not p.scheduled})
+        try:
+            vehicles = {vehicle.trip_id: vehicle for vehicle in self.get_vehicles(trip_ids)}
+        except Exception as e:
+            self.logger.error(f"Error getting vehicles: {str(e)}")
+            return predictions
+
+        try:
+            trip_stops = self._trip_stop_sequences(sorted(vehicles)) if count_stops else {}
+        except Exception as e:
+            self.logger.error(f"Error getting trip stops: {str(e)}")
+            trip_stops = {}
+
+        for prediction in predictions:
+            vehicle = vehicles.get(prediction.trip_id)
+            if vehicle is None:
+                continue
+            sequences = trip_stops.get(prediction.trip_id, [])
+            if vehicle.current_stop_sequence in sequences and prediction.stop_sequence in sequences:
+                stops_away = (sequences.index(prediction.stop_sequence) -
+                              sequences.index(vehicle.current_stop_sequence))
+                if stops_away < 0:
+                    # Already left; the prediction is about to be dropped
+                    continue
+                prediction.stops_away = stops_away
+            prediction.vehicle = vehicle
+        return predictions
+
+    def get_journey_trips(
+        self,
+        route_id: str,
+        from_stop_id: str,
+        to_stop_id: str,
+        count: int = 3
+    ) -> List[JourneyTrip]:
+        """
+        Find the next trips that stop at from_stop_id and then at to_stop_id.
+
+        Predictions and schedules for both stops are grouped by trip_id so the
+        departure and arrival always belong to the same train. Predicted times
+        take precedence over scheduled times for the same trip and stop.
+
+        Args:
+            route_id: MBTA route ID
+            from_stop_id: Stop ID (or parent station ID) the journey starts at
+            to_stop_id: Stop ID (or parent station ID) the journey ends at
+            count: Maximum number of trips to return
+
+        Returns:
+            Journey trips sorted by departure time
+
+        Raises:
+            requests.RequestException: if the API can't be reached, or
+                answers neither request
+        """
+        current_time = self.clock.now()
+        stops = f"{from_stop_id},{to_stop_id}"
+
+        calls: Dict[str, Dict[str, Dict]] = {}
+        failures = []
+
+        request_string = (f"{self.api_url}/schedules?filter[route]={route_id}&filter[stop]={stops}"
+                          f"&filter[date]={self.clock.service_date(current_time).isoformat()}"
+                          f"&filter[min_time]={self.clock.service_time(current_time)}&include=stop,trip"
+                          f"&sort=departure_time")
+        self.logger.debug(f"Getting journey schedules: {request_string}")
+        try:
+            schedules = self._get_index(request_string).models()
+        except requests.HTTPError as e:
+            self.logger.warning(f"No journey schedules: {e}")
+            failures.append(e)
+            schedules = []
+        self._collect_stop_calls(schedules, from_stop_id, to_stop_id, calls, 'schedule')
+
+        request_string = (f"{self.api_url}/predictions?filter[route]={route_id}&filter[stop]={stops}"
+                          f"&include=stop,trip&sort=departure_time")
+        self.logger.debug(f"Getting journey predictions: {request_string}")
+        try:
+            predictions = self._get_index(request_string).models()
+        except requests.HTTPError as e:
+            self.logger.warning(f"No journey predictions: {e}")
+            failures.append(e)
+            predictions = []
+        if len(failures) == 2:
+            # Either one alone still finds trips; with neither the API is down
+            raise failures[-1]
+        self._collect_stop_calls(predictions, from_stop_id, to_stop_id, calls, 'prediction')
+
+        trips = []
+        for trip_id, trip_calls in calls.items():
+            origin = trip_calls.get('from')
+            destination = trip_calls.get('to')
+            if origin is None or destination is None:
+                continue
+            if origin['cancelled'] or destination['cancelled']:
+                continue
+            if origin['stop_sequence'] is None or destination['stop_sequence'] is None:
+                continue
+            # The same trip passes both stations; only keep it if it
+            # reaches the origin first
+            if origin['stop_sequence'] >= destination['stop_sequence']:
+                continue
+
+            departure_time = origin['departure_time'] or origin['arrival_time']
+            arrival_time = destination['arrival_time'] or destination['departure_time']
+            if departure_time is None or arrival_time is None:
+                continue
+            if departure_time <= current_time:
+                continue
+
+            trips.append(JourneyTrip(
+                trip_id=trip_id,
+                direction_id=origin['direction_id'],
+                departure_time=departure_time,
+                arrival_time=arrival_time,
+                from_arrival_time=origin['arrival_time'],
+                status=origin['status'],
+                destination=origin['headsign'] or destination['headsign'],
+                predicted=origin['source'] == 'prediction' or destination['source'] == 'prediction',
+            ))
+
+        trips.sort(key=lambda trip: trip.departure_time)
+        return trips[:count]
+
+    def _collect_stop_calls(self, stop_times: List, from_stop_id: str, to_stop_id: str,
+                            calls: Dict[str, Dict[str, Dict]], source: str):
+        """
+        Index predictions or schedules by trip_id and journey end ('from' or
+        'to'). Later sources overwrite earlier ones.
+        """
+        for stop_time in stop_times:
+            if stop_time.trip is None or stop_time.stop is None:
+                continue
+
+            if from_stop_id in (stop_time.stop.id, stop_time.stop.station_id):
+                end = 'from'
+            elif to_stop_id in (stop_time.stop.id, stop_time.stop.station_id):
+                end = 'to'
+            else:
+                continue
+
+            cancelled = getattr(stop_time, 'schedule_relationship', None) in ('CANCELLED', 'SKIPPED')
+            if not cancelled and stop_time.time is None:
+                # Nothing to add over what we already know about this stop
+                continue
+
+            calls.setdefault(stop_time.trip.id, {})[end] = {
+                'departure_time': stop_time.departure_time,
+                'arrival_time': stop_time.arrival_time,
+                'stop_sequence': stop_time.stop_sequence,
+                'direction_id': stop_time.direction_id,
+                'cancelled': cancelled,
+                'status': getattr(stop_time, 'status', None),
+                'headsign': stop_time.trip.headsign,
+                'source': source,
+            }
+
+    def get_alerts(self, route_ids: List[str], stop_ids: List[str]) -> List[Alert]:
+        """
+        Get alerts in effect now for the given routes and stops.
+        
+        Args:
+            route_ids: MBTA route IDs
+            stop_ids: MBTA stop or parent station IDs
+            
+        Returns:
+            List of Alert objects that affect any of the routes at the stops
+        """
+        try:
+            request_string = f"{self.api_url}/alerts?filter[datetime]=NOW"
+            if route_ids:
+                request_string += f"&filter[route]={','.join(route_ids)}"
+            else:
+                request_string += f"&filter[stop]={','.join(stop_ids)}"
+            self.logger.debug(f"Getting alerts: {request_string}")
+            alerts = self._get_index(request_string).models()
+            
+            # The route filter also returns alerts for other stations on the
+            # line, so narrow down by informed entity
+            return [alert for alert in alerts if alert.affects(route_ids, stop_ids)]
+            
+        except Exception as e:
+            self.logger.error(f"Error getting alerts: {str(e)}")
             return []
 
-    def get_routes_at_stop(self, stop_id: str) -> List[Dict]:
+    def get_routes_at_stop(self, stop_id: str) -> List[Route]:
         """
         Get all routes that serve a specific stop.
         
@@ -338,31 +619,13 @@ class InfoGather():
             stop_id: MBTA stop ID
             
         Returns:
-            List of route dictionaries
+            List of routes
         """
         try:
-            request_string = f"{API_URL}/routes?filter[stop]={stop_id}&{API_REQUEST}"
+            request_string = f"{self.api_url}/routes?filter[stop]={stop_id}"
             self.logger.debug(f"Getting routes at stop: {request_string}")
-            response = self._make_api_request(request_string)
-            
-            if response is None or response.status_code != 200:
-                return []
-                
-            data = response.json()
-            routes = []
-            
-            for item in data.get('data', []):
-                routes.append({
-                    'id': item['id'],
-                    'name': item['attributes'].get('long_name', item['id']),
-                    'short_name': item['attributes'].get('short_name'),
-                    'type': item['attributes'].get('type'),
-                    'direction_names': item['attributes'].get('direction_names', []),
-                    'direction_destinations': item['attributes'].get('direction_destinations', [])
-                })
-                
-            return routes
+            return self._get_index(request_string).models()
             
         except Exception as e:
             self.logger.error(f"Error getting routes at stop: {str(e)}")
-            return []
\ No newline at end of file
+            return []
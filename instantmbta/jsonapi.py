@@ -0,0 +1,203 @@
+"""Parse MBTA V3 JSON:API documents into models.
+
+A document holds the requested resources in ``data`` and any related
+resources asked for with ``include=`` in ``included``; relationships refer to
+them by type and id:
+
+    {"data": [{"type": "prediction", "id": "...",
+               "relationships": {"trip": {"data": {"type": "trip", "id": "T1"}}}}],
+     "included": [{"type": "trip", "id": "T1", "attributes": {"headsign": "Oak Grove"}}],
+     "links": {"next": "https://api-v3.mbta.com/stops?page[offset]=100&page[limit]=100"}}
+
+ResourceIndex indexes every resource in a document once, so resolving a
+relationship is a dictionary lookup.
+"""
+
+from datetime import datetime
+from typing import Callable, Dict, List, Optional, Tuple
+
+from .alerts import alert_from_resource
+from .models import Carriage, Line, Prediction, Route, Schedule, Stop, Trip, Vehicle
+
+
+def parse_time(value: Optional[str]) -> Optional[datetime]:
+    """An ISO 8601 time from the API, or None."""
+    return datetime.fromisoformat(value) if value else None
+
+
+def next_link(document: Dict) -> Optional[str]:
+    """URL of the next page of a paginated collection, if there is one."""
+    return (document.get('links') or {}).get('next')
+
+
+class ResourceIndex:
+    """Resources in a document by (type, id), parsed into models on demand."""
+
+    def __init__(self, document: Dict):
+        data = document.get('data')
+        self.data: List[Dict] = data if isinstance(data, list) else [data] if data else []
+        self._resources: Dict[Tuple[str, str], Dict] = {}
+        for resource in document.get('included', []) + self.data:
+            self._resources[(resource.get('type'), resource.get('id'))] = resource
+        self._models: Dict[Tuple[str, str], object] = {}
+        self._parsing: set = set()
+
+    def get(self, resource_type: str, resource_id: str) -> Optional[Dict]:
+        """The raw resource, if the document has it."""
+        return self._resources.get((resource_type, resource_id))
+
+    def models(self) -> List:
+        """Every resource in data as a model; unknown types are skipped."""
+        return [model for model in (self.model(r.get('type'), r.get('id')) for r in self.data)
+                if model is not None]
+
+    def model(self, resource_type: str, resource_id: str):
+        """
+        The model for a resource. One that isn't in the document becomes a
+        model with only its id set. A relationship leading back to a model
+        still being built (a prediction's schedule pointing at the
+        prediction) resolves to None.
+        """
+        key = (resource_type, resource_id)
+        if key in self._models:
+            return self._models[key]
+        parser = PARSERS.get(resource_type)
+        if parser is None or key in self._parsing:
+            return None
+        resource = self._resources.get(key) or {'type': resource_type, 'id': resource_id}
+        self._parsing.add(key)
+        try:
+            model = parser(resource, self)
+        finally:
+            self._parsing.discard(key)
+        self._models[key] = model
+        return model
+
+    def related(self, resource: Dict, name: str):
+        """The model a to-one relationship points to, or None."""
+        ref = (resource.get('relationships', {}).get(name) or {}).get('data')
+        if not ref:
+            return None
+        return self.model(ref.get('type'), ref.get('id'))
+
+
+def _line(resource: Dict, index: ResourceIndex) -> Line:
+    attrs = resource.get('attributes', {})
+    return Line(
+        id=resource['id'],
+        long_name=attrs.get('long_name'),
+        short_name=attrs.get('short_name'),
+        color=attrs.get('color'),
+    )
+
+
+def _route(resource: Dict, index: ResourceIndex) -> Route:
+    attrs = resource.get('attributes', {})
+    return Route(
+        id=resource['id'],
+        long_name=attrs.get('long_name'),
+        short_name=attrs.get('short_name'),
+        type=attrs.get('type'),
+        direction_names=attrs.get('direction_names') or [],
+        direction_destinations=attrs.get('direction_destinations') or [],
+        color=attrs.get('color'),
+        line=index.related(resource, 'line'),
+    )
+
+
+def _stop(resource: Dict, index: ResourceIndex) -> Stop:
+    attrs = resource.get('attributes', {})
+    return Stop(
+        id=resource['id'],
+        name=attrs.get('name'),
+        location_type=attrs.get('location_type'),
+        platform_code=attrs.get('platform_code'),
+        platform_name=attrs.get('platform_name'),
+        parent_station=index.related(resource, 'parent_station'),
+    )
+
+
+def _trip(resource: Dict, index: ResourceIndex) -> Trip:
+    attrs = resource.get('attributes', {})
+    return Trip(
+        id=resource['id'],
+        headsign=attrs.get('headsign'),
+        name=attrs.get('name'),
+        direction_id=attrs.get('direction_id'),
+        route=index.related(resource, 'route'),
+    )
+
+
+def _vehicle(resource: Dict, index: ResourceIndex) -> Vehicle:
+    attrs = resource.get('attributes', {})
+    return Vehicle(
+        id=resource['id'],
+        label=attrs.get('label'),
+        current_status=attrs.get('current_status'),
+        current_stop_sequence=attrs.get('current_stop_sequence'),
+        direction_id=attrs.get('direction_id'),
+        latitude=attrs.get('latitude'),
+        longitude=attrs.get('longitude'),
+        updated_at=parse_time(attrs.get('updated_at')),
+        occupancy_status=attrs.get('occupancy_status'),
+        carriages=[
+            Carriage(c.get('label'), c.get('occupancy_status'), c.get('occupancy_percentage'))
+            for c in attrs.get('carriages') or []
+        ],
+        route=index.related(resource, 'route'),
+        trip=index.related(resource, 'trip'),
+        stop=index.related(resource, 'stop'),
+    )
+
+
+def _prediction(resource: Dict, index: ResourceIndex) -> Prediction:
+    attrs = resource.get('attributes', {})
+    return Prediction(
+        id=resource['id'],
+        arrival_time=parse_time(attrs.get('arrival_time')),
+        departure_time=parse_time(attrs.get('departure_time')),
+        direction_id=attrs.get('direction_id'),
+        status=attrs.get('status'),
+        departure_uncertainty=attrs.get('departure_uncertainty'),
+        schedule_relationship=attrs.get('schedule_relationship'),
+        stop_sequence=attrs.get('stop_sequence'),
+        route=index.related(resource, 'route'),
+        trip=index.related(resource, 'trip'),
+        stop=index.related(resource, 'stop'),
+        vehicle=index.related(resource, 'vehicle'),
+        schedule=index.related(resource, 'schedule'),
+    )
+
+
+def _schedule(resource: Dict, index: ResourceIndex) -> Schedule:
+    attrs = resource.get('attributes', {})
+    return Schedule(
+        id=resource['id'],
+        arrival_time=parse_time(attrs.get('arrival_time')),
+        departure_time=parse_time(attrs.get('departure_time')),
+        direction_id=attrs.get('direction_id'),
+        stop_sequence=attrs.get('stop_sequence'),
+        pickup_type=attrs.get('pickup_type'),
+        drop_off_type=attrs.get('drop_off_type'),
+        route=index.related(resource, 'route'),
+        trip=index.related(resource, 'trip'),
+        stop=index.related(resource, 'stop'),
+        prediction=index.related(resource, 'prediction'),
+    )
+
+
+PARSERS: Dict[str, Callable[[Dict, ResourceIndex], object]] = {
+    'alert': lambda resource, index: alert_from_resource(resource),
+    'line': _line,
+    'prediction': _prediction,
+    'route': _route,
+    'schedule': _schedule,
+    'stop': _stop,
+    'trip': _trip,
+    'vehicle': _vehicle,
+}
+
+
+def parse_document(document: Dict) -> List:
+    """The resources in a document's data as models."""
+    return ResourceIndex(document).models()
@@ -0,0 +1,151 @@
+"""Layout of DisplayData on the e-ink panel, shared by every image renderer.
+
+The image uses the panel's palette indices (white, black, red) so the Inky
+backend can hand it straight to the display and the PNG backend only has to
+attach matching RGB colours.
+"""
+
+# Palette indices used by the Inky driver
+WHITE = 0
+BLACK = 1
+RED = 2
+
+# RGB colours of the red/black/white Inky pHAT panel, in palette index order
+PANEL_PALETTE = [
+    255, 255, 255,
+    0, 0, 0,
+    200, 0, 0,
+]
+
+PANEL_WIDTH = 250   # Inky pHAT (SSD1608)
+PANEL_HEIGHT = 122
+
+STANDARD_X_COORD = 10
+MAX_LINES = 8  # Maximum lines that fit on the display
+
+OCCUPANCY_BAR_WIDTH = 4
+OCCUPANCY_BAR_GAP = 2
+
+
+def draw_occupancy(draw, right: int, baseline: int, level: int):
+    """
+    Three bars of rising height ending at `right`, filled up to `level`
+    (1 to 3). A full train is drawn in red.
+    """
+    color = RED if level >= 3 else BLACK
+    for i in range(3):
+        x = right - (3 - i) * (OCCUPANCY_BAR_WIDTH + OCCUPANCY_BAR_GAP) + OCCUPANCY_BAR_GAP
+        top = baseline - 6 - i * 4
+        box = (x, top, x + OCCUPANCY_BAR_WIDTH - 1, baseline)
+        if i < level:
+            draw.rectangle(box, fill=color)
+        else:
+            draw.rectangle(box, outline=BLACK)
+
+
+def render_display_data(display_data, width: int = PANEL_WIDTH, height: int = PANEL_HEIGHT):
+    """
+    Draw content from a DisplayData object onto a palette image.
+
+    Args:
+        display_data: DisplayData object with formatted lines
+        width: Panel width in pixels
+        height: Panel height in pixels
+
+    Returns:
+        A "P" mode PIL image using the WHITE, BLACK and RED palette indices
+    """
+    # Imported here so the panel constants are usable without Pillow
+    from PIL import Image, ImageFont, ImageDraw
+    from font_hanken_grotesk import HankenGroteskBold, HankenGroteskMedium
+
+    img = Image.new("P", (width, height))
+    img.putpalette(PANEL_PALETTE)
+    draw = ImageDraw.Draw(img)
+
+    # Fonts
+    font_title = ImageFont.truetype(HankenGroteskBold, 24)
+    font_date = ImageFont.truetype(HankenGroteskBold, 20)
+    font_header = ImageFont.truetype(HankenGroteskBold, 20)
+    font_text = ImageFont.truetype(HankenGroteskMedium, 18)
+    font_text_small = ImageFont.truetype(HankenGroteskMedium, 16)
+
+    y_pos = 0
+
+    # Draw title if present
+    if display_data.title:
+        draw.text((STANDARD_X_COORD, y_pos),
+                 display_data.title,
+                 BLACK,
+                 font_title)
+        _, _, _, bottom = font_title.getbbox(display_data.title)
+        y_pos += bottom + 2
+
+    # Draw date on the right
+    if display_data.date:
+        date_width = font_date.getlength(display_data.date)
+        x_date = width - date_width - 10
+        draw.text((x_date, 0),
+                 display_data.date,
+                 RED,
+                 font_date)
+
+    # Reserve a red footer at the bottom for the most severe alert
+    bottom_limit = height - 25
+    alert_text = display_data.alerts[0] if display_data.alerts else None
+    if alert_text:
+        if len(display_data.alerts) > 1:
+            alert_text += f" (+{len(display_data.alerts) - 1})"
+        _, _, _, bottom = font_text_small.getbbox(alert_text)
+        alert_y = height - bottom - 2
+        draw.rectangle((0, alert_y - 2, width, height), fill=RED)
+        draw.text((STANDARD_X_COORD, alert_y),
+                  alert_text,
+                  WHITE,
+                  font_text_small)
+        bottom_limit = alert_y - 2 - 20
+
+    # Draw lines
+    line_count = 0
+    for line in display_data.lines:
+        if line_count >= MAX_LINES:
+            break
+
+        # Skip drawing if we're near the bottom
+        if y_pos > bottom_limit:
+            break
+
+        # Select font based on line type
+        if line.is_header:
+            font = font_header
+            color = BLACK
+            x_pos = STANDARD_X_COORD
+        elif line.is_route:
+            font = font_text
+            color = BLACK
+            x_pos = STANDARD_X_COORD
+        else:
+            # Use smaller font if we have many lines
+            if len(display_data.lines) > 6:
+                font = font_text_small
+            else:
+                font = font_text
+            color = BLACK
+            x_pos = STANDARD_X_COORD + (20 if line.indent else 0)
+        if line.urgent:
+            color = RED
+
+        # Draw the line
+        if line.text.strip():  # Only draw non-empty lines
+            draw.text((x_pos, y_pos), line.text, color, font)
+            _, _, _, bottom = font.getbbox(line.text)
+            if line.occupancy:
+                draw_occupancy(draw, width - STANDARD_X_COORD, y_pos + bottom - 2, line.occupancy)
+            y_pos += bottom + 2
+        else:
+            # Empty line - add some spacing
+            y_pos += 10
+
+        line_count += 1
+
+    return img
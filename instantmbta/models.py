@@ -0,0 +1,249 @@
+"""Typed models for MBTA V3 API resources.
+
+Relationships are resolved to models as well. A related resource that wasn't
+included in the response is still present, with only its id filled in, so
+``prediction.route.id`` works whether or not routes were requested with
+``include=route``. See jsonapi.py for the parsing.
+"""
+
+from dataclasses import dataclass, field
+from datetime import datetime
+from typing import List, Optional
+
+from .alerts import Alert
+
+__all__ = ['Alert', 'Carriage', 'JourneyTrip', 'Line', 'Prediction', 'Route', 'Schedule', 'Stop',
+           'Trip', 'Vehicle']
+
+# GTFS-Realtime occupancy statuses on a 1 (seats) to 3 (full) scale
+OCCUPANCY_LEVELS = {
+    'EMPTY': 1,
+    'MANY_SEATS_AVAILABLE': 1,
+    'FEW_SEATS_AVAILABLE': 2,
+    'STANDING_ROOM_ONLY': 2,
+    'CRUSHED_STANDING_ROOM_ONLY': 3,
+    'FULL': 3,
+    'NOT_ACCEPTING_PASSENGERS': 3,
+}
+FULL_OCCUPANCY = ('CRUSHED_STANDING_ROOM_ONLY', 'FULL', 'NOT_ACCEPTING_PASSENGERS')
+
+# Prediction schedule_relationship values where the train won't call
+CANCELLED_RELATIONSHIPS = ('CANCELLED', 'SKIPPED')
+
+
+@dataclass
+class Line:
+    """A group of routes shown together, e.g. the Green Line branches."""
+    id: str
+    long_name: Optional[str] = None
+    short_name: Optional[str] = None
+    color: Optional[str] = None
+
+
+@dataclass
+class Route:
+    """A route such as Orange, Red or CR-Haverhill."""
+    id: str
+    long_name: Optional[str] = None
+    short_name: Optional[str] = None
+    type: Optional[int] = None  # 0 light rail, 1 subway, 2 commuter rail, 3 bus, 4 ferry
+    direction_names: List[str] = field(default_factory=list)
+    direction_destinations: List[str] = field(default_factory=list)
+    color: Optional[str] = None
+    line: Optional[Line] = None
+
+    @property
+    def name(self) -> str:
+        return self.long_name or self.short_name or self.id
+
+
+@dataclass
+class Stop:
+    """A station (location_type 1) or a platform or stop within one."""
+    id: str
+    name: Optional[str] = None
+    location_type: Optional[int] = None
+    platform_code: Optional[str] = None
+    platform_name: Optional[str] = None
+    parent_station: Optional['Stop'] = None
+
+    @property
+    def station_id(self) -> str:
+        """The parent station's ID, or this stop's for a standalone stop."""
+        return self.parent_station.id if self.parent_station else self.id
+
+
+@dataclass
+class Trip:
+    """One run of a vehicle along a route."""
+    id: str
+    headsign: Optional[str] = None
+    name: Optional[str] = None  # Train number on the Commuter Rail
+    direction_id: Optional[int] = None
+    route: Optional[Route] = None
+
+
+@dataclass
+class Carriage:
+    """One car of a train, for lines that report occupancy per car."""
+    label: Optional[str] = None
+    occupancy_status: Optional[str] = None
+    occupancy_percentage: Optional[int] = None
+
+
+@dataclass
+class Vehicle:
+    """A vehicle's last reported position."""
+    id: str
+    label: Optional[str] = None
+    current_status: Optional[str] = None  # INCOMING_AT, STOPPED_AT or IN_TRANSIT_TO
+    current_stop_sequence: Optional[int] = None
+    direction_id: Optional[int] = None
+    latitude: Optional[float] = None
+    longitude: Optional[float] = None
+    updated_at: Optional[datetime] = None
+    occupancy_status: Optional[str] = None  # e.g. MANY_SEATS_AVAILABLE or FULL
+    carriages: List[Carriage] = field(default_factory=list)
+    route: Optional[Route] = None
+    trip: Optional[Trip] = None
+    stop: Optional[Stop] = None  # The stop it's at or heading to
+
+    @property
+    def trip_id(self) -> Optional[str]:
+        return self.trip.id if self.trip else None
+
+    def _occupancy_statuses(self) -> List[str]:
+        if self.occupancy_status:
+            return [self.occupancy_status]
+        return [c.occupancy_status for c in self.carriages if c.occupancy_status in OCCUPANCY_LEVELS]
+
+    @property
+    def crowding(self) -> Optional[int]:
+        """
+        1 (seats available) to 3 (full), or None when not reported. For a
+        train reported by carriage it's the emptiest car, since that's the
+        one to board.
+        """
+        levels = [OCCUPANCY_LEVELS[s] for s in self._occupancy_statuses() if s in OCCUPANCY_LEVELS]
+        return min(levels) if levels else None
+
+    @property
+    def full(self) -> bool:
+        """Reported full, or every car is."""
+        statuses = self._occupancy_statuses()
+        return bool(statuses) and all(s in FULL_OCCUPANCY for s in statuses)
+
+
+class _StopTime:
+    """Shared accessors for predictions and schedules."""
+    arrival_time: Optional[datetime]
+    departure_time: Optional[datetime]
+    route: Optional[Route]
+    trip: Optional[Trip]
+    stop: Optional[Stop]
+
+    @property
+    def time(self) -> Optional[datetime]:
+        """Departure time, or arrival time at the end of the line."""
+        return self.departure_time or self.arrival_time
+
+    @property
+    def route_id(self) -> Optional[str]:
+        return self.route.id if self.route else None
+
+    @property
+    def trip_id(self) -> Optional[str]:
+        return self.trip.id if self.trip else None
+
+    @property
+    def stop_id(self) -> Optional[str]:
+        return self.stop.id if self.stop else None
+
+    @property
+    def destination(self) -> Optional[str]:
+        return self.trip.headsign if self.trip else None
+
+
+@dataclass
+class Prediction(_StopTime):
+    """A predicted arrival and departure of a trip at a stop."""
+    id: str
+    arrival_time: Optional[datetime] = None
+    departure_time: Optional[datetime] = None
+    direction_id: Optional[int] = None
+    status: Optional[str] = None  # e.g. 'Boarding' or 'Stopped 2 stops away'
+    departure_uncertainty: Optional[int] = None  # Seconds
+    schedule_relationship: Optional[str] = None  # CANCELLED, SKIPPED, ADDED or NO_DATA
+    stop_sequence: Optional[int] = None
+    route: Optional[Route] = None
+    trip: Optional[Trip] = None
+    stop: Optional[Stop] = None
+    vehicle: Optional[Vehicle] = None
+    schedule: Optional['Schedule'] = None
+    stops_away: Optional[int] = None  # Stops between the vehicle and this stop; see locate_vehicles
+    scheduled: bool = False  # From the static schedule, not a live prediction
+
+    @property
+    def time(self) -> Optional[datetime]:
+        """
+        Departure time, or arrival time at the end of the line. Cancelled,
+        skipped and NO_DATA predictions have neither, so the scheduled time
+        is used when the schedule was included.
+        """
+        live = self.departure_time or self.arrival_time
+        if live is None and self.schedule is not None:
+            return self.schedule.time
+        return live
+
+    @property
+    def cancelled(self) -> bool:
+        """The trip is cancelled or won't stop here."""
+        return self.schedule_relationship in CANCELLED_RELATIONSHIPS
+
+    @property
+    def delay(self) -> Optional[int]:
+        """Minutes behind schedule (negative if early), when both times are known."""
+        live = self.departure_time or self.arrival_time
+        scheduled = self.schedule.time if self.schedule else None
+        if live is None or scheduled is None:
+            return None
+        return round((live - scheduled).total_seconds() / 60)
+
+    @property
+    def track(self) -> Optional[str]:
+        """Commuter Rail track, once the train has been assigned a platform."""
+        return self.stop.platform_code if self.stop else None
+
+    @property
+    def train(self) -> Optional[str]:
+        """Commuter Rail train number."""
+        return self.trip.name if self.trip else None
+
+
+@dataclass
+class Schedule(_StopTime):
+    """A scheduled arrival and departure of a trip at a stop."""
+    id: str
+    arrival_time: Optional[datetime] = None
+    departure_time: Optional[datetime] = None
+    direction_id: Optional[int] = None
+    stop_sequence: Optional[int] = None
+    pickup_type: Optional[int] = None
+    drop_off_type: Optional[int] = None
+    route: Optional[Route] = None
+    trip: Optional[Trip] = None
+    stop: Optional[Stop] = None
+    prediction: Optional[Prediction] = None
+
+
+@dataclass
+class JourneyTrip:
+    """A trip that calls at a journey's origin and then its destination."""
+    trip_id: str
+    departure_time: datetime           # From the origin
+    arrival_time: datetime             # At the destination
+    direction_id: Optional[int] = None
+    from_arrival_time: Optional[datetime] = None  # At the origin
+    status: Optional[str] = None
+    destination: Optional[str] = None  # Headsign
+    predicted: bool = False
@@ -0,0 +1,130 @@
+"""Transit data provider abstraction used by the display modes."""
+
+from abc import ABC, abstractmethod
+from typing import List, Optional
+
+from .alerts import Alert
+from .models import JourneyTrip, Prediction, Route
+from .ratelimit import RateBudget
+
+
+class TransitProvider(ABC):
+    """
+    Source of real-time departures for the display modes.
+
+    InfoGather implements this against the MBTA V3 API and
+    GTFSRealtimeProvider against any agency publishing GTFS-Realtime
+    TripUpdates alongside a GTFS static feed.
+    """
+
+    @abstractmethod
+    def get_predictions_filtered(
+        self,
+        stop_id: str,
+        direction_id: str,
+        route_id: Optional[str] = None,
+        count: int = 3
+    ) -> List[Prediction]:
+        """
+        Get the next departures for a stop and direction, optionally
+        restricted to one or more (comma separated) routes.
+
+        Returns:
+            Predictions sorted by departure time
+        """
+
+    def get_departures(
+        self,
+        stop_id: str,
+        direction_id: str,
+        route_id: Optional[str] = None,
+        count: int = 3
+    ) -> List[Prediction]:
+        """
+        Get the next departures by schedule, each with its prediction and
+        the schedule attached so the delay can be shown. Providers without
+        schedules return their predictions.
+        """
+        return self.get_predictions_filtered(stop_id, direction_id, route_id, count)
+
+    @abstractmethod
+    def get_journey_trips(
+        self,
+        route_id: str,
+        from_stop_id: str,
+        to_stop_id: str,
+        count: int = 3
+    ) -> List[JourneyTrip]:
+        """
+        Get the next trips that stop at from_stop_id and then to_stop_id.
+
+        Returns:
+            Journey trips sorted by departure time
+        """
+
+    @abstractmethod
+    def get_routes_at_stop(self, stop_id: str) -> List[Route]:
+        """Get all routes that serve a specific stop."""
+
+    def get_alerts(self, route_ids: List[str], stop_ids: List[str]) -> List[Alert]:
+        """
+        Get current service alerts affecting any of the routes or stops.
+        Providers without an alerts source return an empty list.
+        """
+        return []
+
+    def locate_vehicles(self, predictions: List[Prediction],
+                        count_stops: bool = True) -> List[Prediction]:
+        """
+        Fill in the vehicle running each predicted trip, with its occupancy,
+        and how many stops away it is. Providers without vehicle positions
+        return the predictions unchanged.
+        """
+        return predictions
+
+    def rate_budget(self) -> Optional[RateBudget]:
+        """
+        The API rate budget, for pacing polls. Providers without a rate
+        limit return None.
+        """
+        return None
+
+
+def create_provider(config, gtfs_store=None, clock=None, transport=None) -> TransitProvider:
+    """
+    Create the provider selected by config.provider.
+
+    Args:
+        config: Complete configuration
+        gtfs_store: Optional GTFSStaticStore; required for GTFS-Realtime
+        clock: Optional Clock; defaults to the agency's local time
+        transport: Optional replacement for requests (see recording.py);
+            only the mbta-v3 provider supports it
+
+    Returns:
+        A TransitProvider instance
+    """
+    provider = config.provider
+    if provider.type == 'mbta-v3':
+        from .infogather import InfoGather
+        return InfoGather(gtfs_store=gtfs_store, clock=clock,
+                          api_url=provider.api_url, timeout=provider.timeout,
+                          transport=transport, api_key=provider.api_key,
+                          # Cached responses would be missing from a recording
+                          cache_path=provider.http_cache if transport is None else None)
+    elif provider.type == 'gtfs-rt':
+        from .gtfs_realtime import GTFSRealtimeProvider
+        if gtfs_store is None:
+            raise ValueError("The gtfs-rt provider requires a 'gtfs' static feed for stop names")
+        if transport is not None:
+            raise ValueError("Recording and replay are only supported with the mbta-v3 provider")
+        return GTFSRealtimeProvider(
+            trip_updates_url=provider.trip_updates_url,
+            static_store=gtfs_store,
+            alerts_url=provider.alerts_url,
+            headers=provider.headers,
+            timeout=provider.timeout,
+            clock=clock,
+        )
+    else:
+        raise ValueError(f"Unknown provider: {provider.type}")
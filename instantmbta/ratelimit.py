@@ -0,0 +1,146 @@
+"""Keep API usage inside the MBTA V3 rate limit.
+
+Every response carries the budget for the current window:
+
+    x-ratelimit-limit: 1000        requests allowed per window
+    x-ratelimit-remaining: 997     requests left in this window
+    x-ratelimit-reset: 1751896860  when the window resets (Unix time)
+
+RateLimiter records these from each response, holds requests back once the
+window is used up or a 429's Retry-After is in force, and suggests how long
+the display loop should wait between polls so the remaining budget lasts
+until the reset.
+"""
+
+from dataclasses import dataclass
+from email.utils import parsedate_to_datetime
+import logging
+from typing import Dict, Optional
+
+from .clock import Clock
+
+# Share of each window kept back for retries and startup lookups
+RESERVE_FRACTION = 0.1
+# Wait after a 429 without a usable Retry-After
+DEFAULT_RETRY_AFTER = 60
+
+
+@dataclass
+class RateBudget:
+    """Snapshot of the API rate budget."""
+    limit: Optional[int]        # Requests per window; None until a response reports it
+    remaining: Optional[int]
+    reset_in: float             # Seconds until the window resets
+    retry_after: float          # Seconds before requests may resume after a 429
+    requests: int               # Requests made so far
+
+    def __str__(self):
+        if self.limit is None:
+            return f"unknown ({self.requests} requests made)"
+        text = f"{self.remaining}/{self.limit} requests left, resets in {self.reset_in:.0f}s"
+        if self.retry_after > 0:
+            text += f", rate limited for {self.retry_after:.0f}s"
+        return text
+
+    def metrics(self) -> Dict[str, Optional[float]]:
+        """The budget as flat metrics, for the status file."""
+        return {
+            'api_requests': self.requests,
+            'api_rate_limit': self.limit,
+            'api_rate_remaining': self.remaining,
+            'api_rate_reset_seconds': round(self.reset_in),
+            'api_retry_after_seconds': round(self.retry_after),
+        }
+
+    def poll_interval(self, refresh: float, requests_per_poll: int) -> float:
+        """
+        Seconds to wait before the next poll: refresh, or longer if polling
+        every refresh seconds would run out of budget before the reset.
+
+        Args:
+            refresh: The configured refresh interval
+            requests_per_poll: Requests the last poll made
+        """
+        if self.retry_after > 0:
+            return max(refresh, self.retry_after)
+        if self.limit is None or self.remaining is None or requests_per_poll <= 0:
+            return refresh
+
+        usable = self.remaining - int(self.limit * RESERVE_FRACTION)
+        polls_left = usable // requests_per_poll
+        if polls_left <= 0:
+            return max(refresh, self.reset_in)
+        return max(refresh, self.reset_in / polls_left)
+
+
+class RateLimiter:
+    """Rate budget shared by every request an InfoGather makes."""
+
+    def __init__(self, clock: Optional[Clock] = None):
+        self.clock = clock or Clock()
+        self.logger = logging.getLogger('instantmbta.ratelimit')
+        self.limit: Optional[int] = None
+        self.remaining: Optional[int] = None
+        self.reset_at: Optional[float] = None     # Unix time
+        self.blocked_until: Optional[float] = None
+        self.requests = 0
+
+    def _now(self) -> float:
+        return self.clock.now().timestamp()
+
+    def update(self, response):
+        """Record the budget reported by a response."""
+        self.requests += 1
+        headers = response.headers
+        try:
+            if 'x-ratelimit-limit' in headers:
+                self.limit = int(headers['x-ratelimit-limit'])
+            if 'x-ratelimit-remaining' in headers:
+                self.remaining = int(headers['x-ratelimit-remaining'])
+            if 'x-ratelimit-reset' in headers:
+                self.reset_at = float(headers['x-ratelimit-reset'])
+        except ValueError:
+            self.logger.debug("Ignoring malformed rate limit headers")
+
+        if response.status_code == 429:
+            retry_after = self._retry_after(headers.get('Retry-After'))
+            self.blocked_until = self._now() + retry_after
+            self.remaining = 0
+            self.logger.warning("Rate limited by the API; waiting %.0fs", retry_after)
+
+    def _retry_after(self, value: Optional[str]) -> float:
+        if value:
+            try:
+                return max(0.0, float(value))
+            except ValueError:
+                pass
+            try:
+                return max(0.0, parsedate_to_datetime(value).timestamp() - self._now())
+            except (TypeError, ValueError):
+                pass
+        if self.reset_at is not None and self.reset_at > self._now():
+            return self.reset_at - self._now()
+        return DEFAULT_RETRY_AFTER
+
+    def delay(self) -> float:
+        """Seconds to hold the next request back."""
+        now = self._now()
+        if self.blocked_until is not None and self.blocked_until > now:
+            return self.blocked_until - now
+        if self.remaining is not None and self.remaining <= 0 \
+                and self.reset_at is not None and self.reset_at > now:
+            return self.reset_at - now
+        return 0.0
+
+    def wait(self):
+        """Sleep until a request is allowed."""
+        delay = self.delay()
+        if delay > 0:
+            self.logger.info("API budget used up; waiting %.0fs", delay)
+            self.clock.sleep(delay)
+
+    def budget(self) -> RateBudget:
+        now = self._now()
+        reset_in = max(0.0, self.reset_at - now) if self.reset_at is not None else 0.0
+        retry_after = max(0.0, self.blocked_until - now) if self.blocked_until is not None else 0.0
+        return RateBudget(self.limit, self.remaining, reset_in, retry_after, self.requests)
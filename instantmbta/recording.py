@@ -0,0 +1,139 @@
+"""Record API traffic and replay it later on a simulated clock.
+
+`--record DIR` wraps the provider's HTTP requests in a RecordingTransport,
+which saves every response (or network error) as a numbered JSON file along
+with the time it was made. `--replay DIR` serves those responses back through
+a ReplayTransport whose clock jumps to each request's recorded time, so a
+display that misbehaved at 7:42 can be reproduced step by step elsewhere.
+"""
+
+from datetime import datetime
+import json
+import logging
+from pathlib import Path
+from typing import Dict, List, Optional
+from urllib.parse import parse_qsl, urlencode, urlsplit, urlunsplit
+
+import requests
+
+from .clock import MBTA_TIMEZONE, Clock, FixedClock
+from .http_cache import build_response
+
+CATALOG_FILE = 'catalog.json'  # Station catalog the recorded run resolved names with
+
+# Query parameters never written to disk or used for matching
+REDACTED_PARAMS = ('api_key',)
+
+
+class ReplayFinished(BaseException):
+    """
+    Raised once every recorded response has been served.
+
+    A BaseException, like KeyboardInterrupt, so the providers' and display
+    loop's error handling lets it through to stop the run.
+    """
+
+
+def redact_url(url: str) -> str:
+    """The URL without credentials."""
+    parts = urlsplit(url)
+    query = [(k, v) for k, v in parse_qsl(parts.query, keep_blank_values=True)
+             if k not in REDACTED_PARAMS]
+    return urlunsplit(parts._replace(query=urlencode(query, safe='[],:')))
+
+
+class RecordingTransport:
+    """Make requests with requests.get and save each response to a directory."""
+
+    def __init__(self, directory, clock: Optional[Clock] = None, get=None):
+        self.directory = Path(directory)
+        self.directory.mkdir(parents=True, exist_ok=True)
+        self.clock = clock or Clock()
+        self._get = get or requests.get
+        self.logger = logging.getLogger('instantmbta.recording')
+        self.count = len(list(self.directory.glob('[0-9]*.json')))
+
+    def get(self, url, **kwargs):
+        record = {'time': self.clock.now().isoformat(), 'url': redact_url(url)}
+        try:
+            response = self._get(url, **kwargs)
+        except requests.exceptions.RequestException as e:
+            record['error'] = str(e)
+            record['timeout'] = isinstance(e, requests.exceptions.Timeout)
+            self._write(record)
+            raise
+        record.update({
+            'status': response.status_code,
+            'headers': dict(response.headers),
+            'body': response.text,
+        })
+        self._write(record)
+        return response
+
+    def _write(self, record: Dict):
+        self.count += 1
+        path = self.directory / f'{self.count:05d}.json'
+        with open(path, 'w') as f:
+            json.dump(record, f, indent=1)
+        self.logger.debug("Recorded %s to %s", record['url'], path)
+
+
+class ReplayTransport:
+    """
+    Serve recorded responses in order.
+
+    A request is answered by the next unserved recording of the same URL,
+    or failing that of the same endpoint (the clock-dependent filters of a
+    replayed request can differ by a minute from the recorded one). Requests
+    with no recording get a ConnectionError, as if the API were unreachable.
+    """
+
+    def __init__(self, directory, tz=MBTA_TIMEZONE):
+        self.directory = Path(directory)
+        paths = sorted(self.directory.glob('[0-9]*.json'))
+        if not paths:
+            raise ValueError(f"No recorded responses in {self.directory}")
+        self.records: List[Dict] = []
+        for path in paths:
+            with open(path) as f:
+                self.records.append(json.load(f))
+        self.position = 0
+        self.logger = logging.getLogger('instantmbta.recording')
+        # Set to each recorded request's time as it is served; sleeps advance it
+        self.clock = FixedClock(datetime.fromisoformat(self.records[0]['time']), tz)
+
+    @property
+    def exhausted(self) -> bool:
+        return self.position >= len(self.records)
+
+    def get(self, url, **kwargs):
+        if self.exhausted:
+            raise ReplayFinished()
+
+        index = self._find(redact_url(url))
+        if index is None:
+            raise requests.exceptions.ConnectionError(f"No recorded response for {redact_url(url)}")
+        if index > self.position:
+            self.logger.debug("Skipping %d recorded responses", index - self.position)
+        record = self.records[index]
+        self.position = index + 1
+
+        self.clock.set(datetime.fromisoformat(record['time']))
+        if 'error' in record:
+            if record.get('timeout'):
+                raise requests.exceptions.Timeout(record['error'])
+            raise requests.exceptions.ConnectionError(record['error'])
+
+        return build_response(record['url'], record['status'],
+                              record.get('headers', {}), record.get('body', ''))
+
+    def _find(self, url: str) -> Optional[int]:
+        remaining = range(self.position, len(self.records))
+        for i in remaining:
+            if self.records[i]['url'] == url:
+                return i
+        path = urlsplit(url).path
+        for i in remaining:
+            if urlsplit(self.records[i]['url']).path == path:
+                return i
+        return None
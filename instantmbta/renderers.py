@@ -0,0 +1,162 @@
+"""Output backends for formatted DisplayData.
+
+- inky: the Inky pHAT e-ink panel (Raspberry Pi only)
+- png: writes what the panel would show to an image file
+- terminal: prints the display in the terminal, using rich if installed
+"""
+
+from abc import ABC, abstractmethod
+import logging
+import os
+from pathlib import Path
+import platform
+import sys
+from typing import Optional, TextIO
+
+from .layout import PANEL_HEIGHT, PANEL_WIDTH
+
+PI_PLATFORMS = ("armv7l", "armv6l", "aarch64")
+
+RENDERERS = ('inky', 'png', 'terminal', 'none')
+
+
+def occupancy_text(level: int) -> str:
+    """Crowding from 1 to 3 as filled and empty bars, e.g. ▮▮▯."""
+    return '▮' * level + '▯' * (3 - level)
+
+
+class Renderer(ABC):
+    """Something that can show a DisplayData."""
+
+    @abstractmethod
+    def draw_from_display_data(self, display_data):
+        """
+        Draw content from DisplayData object.
+
+        Args:
+            display_data: DisplayData object with formatted lines
+        """
+
+    def close(self):
+        """Release any resources held by the renderer."""
+
+
+class PNGRenderer(Renderer):
+    """Write the panel image to a PNG file, replacing it on every update."""
+
+    def __init__(self, path, width: int = PANEL_WIDTH, height: int = PANEL_HEIGHT):
+        self.path = Path(path)
+        self.width = width
+        self.height = height
+        self.logger = logging.getLogger('instantmbta.renderers')
+
+    def draw_from_display_data(self, display_data):
+        from .layout import render_display_data
+        img = render_display_data(display_data, self.width, self.height)
+
+        # Write then rename so anything serving the file never sees half an image
+        tmp_path = self.path.with_name(self.path.name + '.tmp')
+        img.save(tmp_path, format='PNG')
+        os.replace(tmp_path, self.path)
+        self.logger.debug("Wrote %s", self.path)
+
+
+class TerminalRenderer(Renderer):
+    """Show the display as text, framed like the panel."""
+
+    def __init__(self, stream: Optional[TextIO] = None, width: int = 40):
+        self.stream = stream or sys.stdout
+        self.width = width
+
+    def draw_from_display_data(self, display_data):
+        try:
+            from rich.console import Console
+        except ImportError:
+            self.stream.write(self.format_plain(display_data))
+            self.stream.flush()
+            return
+        Console(file=self.stream).print(self.format_rich(display_data))
+
+    def format_plain(self, display_data) -> str:
+        """The display as a plain text box."""
+        inner = self.width - 4
+        rows = []
+        title = display_data.title or ''
+        date = display_data.date or ''
+        rows.append(title[:inner - len(date) - 1].ljust(inner - len(date)) + date)
+        rows.append('')
+        for line in display_data.lines:
+            text = ('  ' if line.indent else '') + line.text
+            if line.occupancy:
+                # Right-aligned like the bars on the panel
+                text = text[:inner - 4].ljust(inner - 3) + occupancy_text(line.occupancy)
+            rows.append(text[:inner])
+        for alert in display_data.alerts:
+            rows.append(('! ' + alert)[:inner])
+
+        border = '+' + '-' * (self.width - 2) + '+'
+        body = [f"| {row.ljust(inner)} |" for row in rows]
+        return '\n'.join([border, *body, border]) + '\n'
+
+    def format_rich(self, display_data):
+        """The display as a rich Panel, with the panel's red for date and alerts."""
+        from rich.panel import Panel
+        from rich.table import Table
+        from rich.text import Text
+
+        body = Text()
+        for i, line in enumerate(display_data.lines):
+            if i:
+                body.append('\n')
+            style = 'bold' if line.is_header else ''
+            if line.urgent:
+                style = 'bold red'
+            body.append(('  ' if line.indent else '') + line.text, style=style)
+            if line.occupancy:
+                body.append(' ' + occupancy_text(line.occupancy),
+                            style='red' if line.occupancy >= 3 else '')
+        for alert in display_data.alerts:
+            body.append('\n')
+            body.append(f" {alert} ", style='bold white on red')
+
+        header = Table.grid(expand=True)
+        header.add_column()
+        header.add_column(justify='right')
+        header.add_row(Text(display_data.title or '', style='bold'),
+                       Text(display_data.date or '', style='bold red'))
+
+        grid = Table.grid()
+        grid.add_row(header)
+        grid.add_row(body)
+        return Panel(grid, width=self.width)
+
+
+def default_renderer() -> str:
+    """The Inky panel on a Raspberry Pi, nothing elsewhere."""
+    return 'inky' if platform.machine() in PI_PLATFORMS else 'none'
+
+
+def create_renderer(name: Optional[str], config) -> Optional[Renderer]:
+    """
+    Create a renderer by name.
+
+    Args:
+        name: One of RENDERERS, or None for the platform default
+        config: Complete configuration (for the PNG path and size)
+
+    Returns:
+        The renderer, or None for 'none'
+    """
+    name = (name or default_renderer()).lower()
+    if name == 'inky':
+        from .inkytrain import InkyTrain
+        return InkyTrain()
+    elif name == 'png':
+        return PNGRenderer(config.display.image_path,
+                           config.display.image_width, config.display.image_height)
+    elif name == 'terminal':
+        return TerminalRenderer()
+    elif name == 'none':
+        return None
+    else:
+        raise ValueError(f"Unknown renderer: {name}")
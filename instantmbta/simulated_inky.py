@@ -0,0 +1,48 @@
+"""Stand-in for an Inky display, for running InkyTrain off the Pi.
+
+It exposes the parts of the inky driver that InkyTrain uses (WIDTH, HEIGHT,
+the colour constants, set_image, set_border and show) and keeps the last
+palette image instead of sending it to the panel.
+"""
+
+from typing import Optional
+
+from .layout import BLACK, PANEL_HEIGHT, PANEL_PALETTE, PANEL_WIDTH, RED, WHITE
+
+
+class SimulatedInky:
+    """An Inky pHAT that captures what would have been shown."""
+
+    WHITE = WHITE
+    BLACK = BLACK
+    RED = RED
+
+    def __init__(self, width: int = PANEL_WIDTH, height: int = PANEL_HEIGHT):
+        self.WIDTH = width
+        self.HEIGHT = height
+        self.h_flip = False
+        self.v_flip = False
+        self.image = None               # Last image passed to set_image
+        self.border: Optional[int] = None
+        self.shown = None               # Image on the "panel" after show()
+        self.show_count = 0
+
+    def set_image(self, image):
+        self.image = image
+
+    def set_border(self, colour: int):
+        self.border = colour
+
+    def show(self):
+        # Flips are applied by the panel driver, not in the image, so the
+        # captured image is what a viewer sees on the mounted display
+        self.shown = self.image.copy() if self.image is not None else None
+        self.show_count += 1
+
+    def to_rgb(self):
+        """The shown image in the panel's colours."""
+        if self.shown is None:
+            return None
+        image = self.shown.copy()
+        image.putpalette(PANEL_PALETTE)
+        return image.convert('RGB')
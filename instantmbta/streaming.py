@@ -0,0 +1,263 @@
+"""Streaming client for live MBTA predictions using server-sent events.
+
+The MBTA V3 API streams any collection endpoint when the request carries
+``Accept: text/event-stream``. The stream starts with a ``reset`` event
+holding the full result set, followed by ``add``, ``update`` and ``remove``
+events as predictions change. See:
+https://www.mbta.com/developers/v3-api/streaming
+"""
+
+import json
+import logging
+import threading
+import time
+from typing import Dict, Iterable, Iterator, List, Optional, Tuple
+
+import requests
+
+from .clock import Clock
+from .credentials import API_KEY_HEADER
+from .infogather import API_URL
+from .jsonapi import ResourceIndex
+from .models import Prediction
+
+STREAM_CONNECT_TIMEOUT = 10
+STREAM_READ_TIMEOUT = 60  # The API sends keep-alive comments well within this
+RECONNECT_DELAY_SECONDS = 5
+
+
+def iter_sse_events(lines: Iterable[str]) -> Iterator[Tuple[str, str]]:
+    """
+    Parse a server-sent events stream into (event, data) pairs.
+
+    Args:
+        lines: Decoded lines from the HTTP response body
+
+    Yields:
+        Tuples of event name and data payload
+    """
+    event = "message"
+    data_lines: List[str] = []
+
+    for line in lines:
+        if line is None:
+            continue
+        line = line.rstrip("\r")
+
+        # A blank line dispatches the buffered event
+        if not line:
+            if data_lines:
+                yield event, "\n".join(data_lines)
+            event = "message"
+            data_lines = []
+            continue
+
+        # Comment lines are keep-alives
+        if line.startswith(":"):
+            continue
+
+        name, _, value = line.partition(":")
+        if value.startswith(" "):
+            value = value[1:]
+
+        if name == "event":
+            event = value
+        elif name == "data":
+            data_lines.append(value)
+
+    if data_lines:
+        yield event, "\n".join(data_lines)
+
+
+class PredictionStore:
+    """
+    Thread-safe in-memory view of streamed predictions and their included
+    trips and stops.
+
+    Exposes get_predictions_filtered with the same signature and return type
+    as InfoGather so display modes can read from either.
+    """
+
+    def __init__(self, clock: Optional[Clock] = None):
+        self.logger = logging.getLogger('instantmbta.streaming')
+        self.clock = clock or Clock()
+        self._lock = threading.Lock()
+        self._predictions: Dict[str, Dict] = {}
+        self._included: Dict[Tuple[str, str], Dict] = {}
+        self.ready = False
+        self.last_update: Optional[float] = None
+
+    def reset(self, resources: List[Dict]):
+        """Replace the store contents with a full result set."""
+        with self._lock:
+            self._predictions.clear()
+            self._included.clear()
+            for resource in resources:
+                self._put(resource)
+            self.ready = True
+            self.last_update = time.time()
+
+    def disconnected(self):
+        """
+        The stream has dropped, so the store misses changes until the next
+        reset; readers should poll instead of trusting it.
+        """
+        with self._lock:
+            self.ready = False
+
+    def upsert(self, resource: Dict):
+        """Add a new resource or replace an existing one."""
+        with self._lock:
+            self._put(resource)
+            self.last_update = time.time()
+
+    def remove(self, resource: Dict):
+        """Remove a resource given its identifier."""
+        with self._lock:
+            if resource.get('type') == 'prediction':
+                self._predictions.pop(resource.get('id'), None)
+            else:
+                self._included.pop((resource.get('type'), resource.get('id')), None)
+            self.last_update = time.time()
+
+    def _put(self, resource: Dict):
+        if resource.get('type') == 'prediction':
+            self._predictions[resource['id']] = resource
+        else:
+            self._included[(resource.get('type'), resource.get('id'))] = resource
+
+    def __len__(self):
+        with self._lock:
+            return len(self._predictions)
+
+    def get_predictions_filtered(
+        self,
+        stop_id: str,
+        direction_id: str,
+        route_id: Optional[str] = None,
+        count: int = 3
+    ) -> List[Prediction]:
+        """
+        Get filtered predictions from the store.
+
+        Args:
+            stop_id: MBTA stop ID (or parent station ID)
+            direction_id: "0" for inbound, "1" for outbound
+            route_id: Optional route ID, or comma separated route IDs
+            count: Maximum number of predictions to return
+
+        Returns:
+            Predictions sorted by departure time
+        """
+        route_ids = set(route_id.split(',')) if route_id else None
+        now = self.clock.now()
+
+        with self._lock:
+            index = ResourceIndex({
+                'data': list(self._predictions.values()),
+                'included': list(self._included.values()),
+            })
+
+        predictions = []
+        for prediction in index.models():
+            if str(prediction.direction_id) != str(direction_id):
+                continue
+            if not self._at_stop(index, prediction, stop_id):
+                continue
+            if prediction.time is None:
+                continue
+            if route_ids is not None and prediction.route_id not in route_ids:
+                continue
+            # The stream removes departed predictions, but not always promptly
+            if prediction.time < now:
+                continue
+            predictions.append(prediction)
+
+        predictions.sort(key=lambda p: p.time)
+        return predictions[:count]
+
+    @staticmethod
+    def _at_stop(index: ResourceIndex, prediction: Prediction, stop_id: str) -> bool:
+        if prediction.stop is None or prediction.stop.id == stop_id:
+            return True
+        if index.get('stop', prediction.stop.id) is None:
+            # The stream is already filtered by station, so a platform we
+            # haven't seen the stop resource for still belongs to it
+            return True
+        return prediction.stop.station_id == stop_id
+
+
+class PredictionStream(threading.Thread):
+    """
+    Background thread that keeps a PredictionStore current from an SSE
+    endpoint, reconnecting whenever the connection drops.
+    """
+
+    def __init__(self, url: str, store: PredictionStore,
+                 reconnect_delay: float = RECONNECT_DELAY_SECONDS,
+                 api_key: Optional[str] = None):
+        super().__init__(name='instantmbta-prediction-stream', daemon=True)
+        self.url = url
+        self.api_key = api_key
+        self.store = store
+        self.reconnect_delay = reconnect_delay
+        self.logger = logging.getLogger('instantmbta.streaming')
+        self._stop_event = threading.Event()
+
+    @classmethod
+    def for_station(cls, stop_id: str, route_ids: List[str], store: PredictionStore,
+                    api_url: Optional[str] = None, **kwargs) -> 'PredictionStream':
+        """Build a stream of predictions for the given routes at a station."""
+        api_url = (api_url or API_URL).rstrip('/')
+        url = f"{api_url}/predictions?filter[stop]={stop_id}&include=stop,trip,schedule"
+        if route_ids:
+            url += f"&filter[route]={','.join(route_ids)}"
+        return cls(url, store, **kwargs)
+
+    def stop(self):
+        """Ask the stream to disconnect and exit."""
+        self._stop_event.set()
+
+    def run(self):
+        while not self._stop_event.is_set():
+            try:
+                self._consume()
+            except (requests.exceptions.RequestException, ValueError) as e:
+                self.logger.warning("Prediction stream disconnected: %s", e)
+            except Exception:
+                # e.g. a malformed resource; reconnecting starts over with a reset
+                self.logger.exception("Prediction stream failed")
+            finally:
+                self.store.disconnected()
+            if self._stop_event.wait(self.reconnect_delay):
+                break
+            self.logger.info("Reconnecting prediction stream")
+
+    def _consume(self):
+        headers = {'Accept': 'text/event-stream'}
+        if self.api_key:
+            headers[API_KEY_HEADER] = self.api_key
+        with requests.get(self.url, headers=headers, stream=True,
+                          timeout=(STREAM_CONNECT_TIMEOUT, STREAM_READ_TIMEOUT)) as response:
+            response.raise_for_status()
+            self.logger.info("Prediction stream connected")
+            # chunk_size=None hands over each chunk as soon as it arrives
+            # instead of waiting for a fixed-size buffer to fill
+            lines = response.iter_lines(chunk_size=None, decode_unicode=True)
+            for event, data in iter_sse_events(lines):
+                if self._stop_event.is_set():
+                    return
+                self.handle_event(event, data)
+
+    def handle_event(self, event: str, data: str):
+        """Apply a single SSE event to the store."""
+        payload = json.loads(data)
+        if event == 'reset':
+            self.store.reset(payload)
+            self.logger.debug("Stream reset with %d resources", len(payload))
+        elif event in ('add', 'update'):
+            self.store.upsert(payload)
+        elif event == 'remove':
+            self.store.remove(payload)
+        else:
+            self.logger.debug("Ignoring stream event %s", event)
@@ -0,0 +1,338 @@
+"""A fake MBTA V3 API for integration tests.
+
+FakeMBTAServer runs an HTTP server on localhost that answers the endpoints
+InstantMBTA uses from a scenario: a JSON file in tests/scenarios/ listing
+JSON:API resources by type. Requests are filtered the way the real API
+filters them and `include=` pulls related resources into `included`, so
+InfoGather can be pointed at it with `api_url=server.url`.
+
+Scenario files look like:
+
+    {
+        "now": "2025-07-07T10:00:00-04:00",
+        "resources": {
+            "prediction": [{"type": "prediction", "id": "...",
+                            "attributes": {...}, "relationships": {...}}],
+            "stop": [...],
+            ...
+        }
+    }
+
+Successful responses can report a rate budget with set_rate_limit() and
+carry a Last-Modified date (`last_modified`, answering If-Modified-Since with
+304 Not Modified), and
+faults can be injected per path prefix to exercise error handling:
+
+    server.inject('/predictions', status=429, headers={'Retry-After': '1'})
+    server.inject('/alerts', status=503, count=2)
+    server.inject('/schedules', delay=2.0)  # longer than the client timeout
+"""
+
+from dataclasses import dataclass, field
+from datetime import datetime
+import json
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from pathlib import Path
+import threading
+import time
+from typing import Dict, List, Optional, Tuple
+from urllib.parse import parse_qsl, urlencode, urlsplit
+
+SCENARIO_DIR = Path(__file__).parent / 'scenarios'
+
+# Path segment → resource type
+COLLECTIONS = {
+    'predictions': 'prediction',
+    'schedules': 'schedule',
+    'routes': 'route',
+    'stops': 'stop',
+    'alerts': 'alert',
+    'vehicles': 'vehicle',
+    'trips': 'trip',
+    'lines': 'line',
+}
+
+
+def load_scenario(name: str) -> Dict:
+    """Load tests/scenarios/<name>.json."""
+    with open(SCENARIO_DIR / f'{name}.json') as f:
+        return json.load(f)
+
+
+def _related_id(resource: Dict, relationship: str) -> Optional[str]:
+    data = resource.get('relationships', {}).get(relationship, {}).get('data')
+    return data.get('id') if isinstance(data, dict) else None
+
+
+@dataclass
+class Fault:
+    """A canned failure for requests whose path starts with prefix."""
+    prefix: str
+    status: Optional[int] = None  # Respond with this status instead of data
+    delay: float = 0              # Seconds to wait before responding
+    headers: Dict[str, str] = field(default_factory=dict)
+    count: int = 1                # Requests to affect; -1 for every request
+
+
+class FakeMBTAServer:
+    """Serve a scenario on localhost. Use as a context manager or start()/stop()."""
+
+    def __init__(self, scenario='basic'):
+        if isinstance(scenario, str):
+            scenario = load_scenario(scenario)
+        self.scenario = scenario
+        self.resources: Dict[Tuple[str, str], Dict] = {}
+        for items in scenario.get('resources', {}).values():
+            for item in items:
+                self.resources[(item['type'], item['id'])] = item
+        self.faults: List[Fault] = []
+        # (limit, remaining, reset) reported in x-ratelimit-* headers; see set_rate_limit
+        self.rate_limit: Optional[List[float]] = None
+        # Sent as Last-Modified; requests with a matching If-Modified-Since get a 304
+        self.last_modified: Optional[str] = scenario.get('last_modified')
+        # Page size for collections requested without page[limit]; None sends everything
+        self.page_size: Optional[int] = None
+        self.requests: List[Tuple[str, Dict[str, str], Dict[str, str]]] = []
+        self._lock = threading.Lock()
+        self._server = None
+        self._thread = None
+        self._url: Optional[str] = None
+
+    @property
+    def now(self) -> Optional[datetime]:
+        """The scenario's current time, for a FixedClock."""
+        now = self.scenario.get('now')
+        return datetime.fromisoformat(now) if now else None
+
+    @property
+    def url(self) -> str:
+        # Kept after stop() for handlers still finishing a response
+        return self._url
+
+    def start(self) -> 'FakeMBTAServer':
+        handler = type('Handler', (_Handler,), {'fake': self})
+        self._server = ThreadingHTTPServer(('127.0.0.1', 0), handler)
+        self._server.daemon_threads = True
+        # Don't wait for handlers still sleeping in an injected delay
+        self._server.block_on_close = False
+        host, port = self._server.server_address[:2]
+        self._url = f"http://{host}:{port}"
+        self._thread = threading.Thread(target=self._server.serve_forever, daemon=True)
+        self._thread.start()
+        return self
+
+    def stop(self):
+        if self._server is not None:
+            self._server.shutdown()
+            self._server.server_close()
+            self._server = None
+
+    def __enter__(self):
+        return self.start()
+
+    def __exit__(self, *exc):
+        self.stop()
+
+    def set_rate_limit(self, limit: int, remaining: int, reset: float):
+        """Report a rate budget on every response, counting remaining down."""
+        with self._lock:
+            self.rate_limit = [limit, remaining, reset]
+
+    def _rate_limit_headers(self) -> Dict[str, str]:
+        with self._lock:
+            if self.rate_limit is None:
+                return {}
+            limit, remaining, reset = self.rate_limit
+            self.rate_limit[1] = max(0, remaining - 1)
+        return {'x-ratelimit-limit': str(limit),
+                'x-ratelimit-remaining': str(max(0, remaining - 1)),
+                'x-ratelimit-reset': str(int(reset))}
+
+    def inject(self, prefix: str, status: Optional[int] = None, delay: float = 0,
+               headers: Optional[Dict[str, str]] = None, count: int = 1):
+        """Make the next `count` requests under `prefix` fail or stall."""
+        with self._lock:
+            self.faults.append(Fault(prefix, status, delay, headers or {}, count))
+
+    def requests_to(self, path: str) -> List[Dict[str, str]]:
+        """Query parameters of every request made to `path`."""
+        return [params for p, params, _ in self.requests if p == path]
+
+    def _take_fault(self, path: str) -> Optional[Fault]:
+        with self._lock:
+            for fault in self.faults:
+                if path.startswith(fault.prefix) and fault.count != 0:
+                    if fault.count > 0:
+                        fault.count -= 1
+                    return fault
+        return None
+
+    # Request handling
+
+    def handle(self, path: str, params: Dict[str, str]) -> Tuple[int, Dict]:
+        """The status and JSON:API document for a request."""
+        segments = [s for s in path.split('/') if s]
+        if not segments or segments[0] not in COLLECTIONS or len(segments) > 2:
+            return 404, {'errors': [{'status': '404', 'code': 'not_found'}]}
+        resource_type = COLLECTIONS[segments[0]]
+
+        if len(segments) == 2:
+            item = self.resources.get((resource_type, segments[1]))
+            if item is None:
+                return 404, {'errors': [{'status': '404', 'code': 'not_found'}]}
+            return 200, {'data': item, 'included': self._included([item], params),
+                         'jsonapi': {'version': '1.0'}}
+
+        items = [item for (t, _), item in self.resources.items() if t == resource_type]
+        items = [item for item in items if self._matches(item, params)]
+
+        sort = params.get('sort')
+        if sort:
+            key = sort.lstrip('-')
+            items.sort(key=lambda item: item.get('attributes', {}).get(key) or '',
+                       reverse=sort.startswith('-'))
+
+        offset = int(params.get('page[offset]', 0))
+        limit = params.get('page[limit]') or self.page_size
+        document = {'jsonapi': {'version': '1.0'}}
+        if limit:
+            limit = int(limit)
+            if offset + limit < len(items):
+                next_params = dict(params, **{'page[offset]': offset + limit, 'page[limit]': limit})
+                document['links'] = {'next': f"{self.url}{path}?{urlencode(next_params, safe='[],')}"}
+            items = items[offset:offset + limit]
+        else:
+            items = items[offset:]
+
+        document.update({'data': items, 'included': self._included(items, params)})
+        return 200, document
+
+    def _matches(self, item: Dict, params: Dict[str, str]) -> bool:
+        attrs = item.get('attributes', {})
+        for key, value in params.items():
+            if not key.startswith('filter['):
+                continue
+            name = key[len('filter['):-1]
+            wanted = set(value.split(','))
+
+            if name == 'id':
+                ok = item['id'] in wanted
+            elif name == 'stop':
+                ok = self._stop_matches(item, wanted)
+            elif name == 'route':
+                ok = self._route_matches(item, wanted)
+            elif name == 'trip':
+                ok = _related_id(item, 'trip') in wanted
+            elif name in ('direction_id', 'location_type', 'type'):
+                ok = str(attrs.get(name)) in wanted
+            else:
+                # date, datetime, min_time etc.: a scenario is a single
+                # service day as of `now`
+                ok = True
+            if not ok:
+                return False
+        return True
+
+    def _stop_matches(self, item: Dict, wanted: set) -> bool:
+        if item['type'] == 'alert':
+            return any(entity.get('stop') in wanted
+                       for entity in item.get('attributes', {}).get('informed_entity', []))
+        if item['type'] == 'route':
+            return bool(self._stops_served_by(item['id']) & self._with_children(wanted))
+        if item['type'] == 'stop':
+            return item['id'] in wanted
+        stop_id = _related_id(item, 'stop')
+        return stop_id in wanted or self._parent_of(stop_id) in wanted
+
+    def _route_matches(self, item: Dict, wanted: set) -> bool:
+        if item['type'] == 'alert':
+            return any(entity.get('route') in wanted
+                       for entity in item.get('attributes', {}).get('informed_entity', []))
+        if item['type'] == 'stop':
+            served = set()
+            for route_id in wanted:
+                served |= self._stops_served_by(route_id)
+            return item['id'] in served or any(
+                self._parent_of(stop) == item['id'] for stop in served)
+        return _related_id(item, 'route') in wanted
+
+    def _parent_of(self, stop_id: Optional[str]) -> Optional[str]:
+        stop = self.resources.get(('stop', stop_id))
+        return _related_id(stop, 'parent_station') if stop else None
+
+    def _with_children(self, stop_ids: set) -> set:
+        children = {stop_id for (t, stop_id), stop in self.resources.items()
+                    if t == 'stop' and _related_id(stop, 'parent_station') in stop_ids}
+        return stop_ids | children
+
+    def _stops_served_by(self, route_id: str) -> set:
+        """Stops the scenario's schedules and predictions call at on a route."""
+        return {_related_id(item, 'stop')
+                for (t, _), item in self.resources.items()
+                if t in ('schedule', 'prediction') and _related_id(item, 'route') == route_id}
+
+    def _included(self, items: List[Dict], params: Dict[str, str]) -> List[Dict]:
+        include = params.get('include')
+        if not include:
+            return []
+        included = {}
+        for path in include.split(','):
+            current = items
+            for relationship in path.split('.'):
+                related = []
+                for item in current:
+                    data = item.get('relationships', {}).get(relationship, {}).get('data')
+                    for ref in data if isinstance(data, list) else [data]:
+                        if ref and (ref['type'], ref['id']) in self.resources:
+                            resource = self.resources[(ref['type'], ref['id'])]
+                            included[(ref['type'], ref['id'])] = resource
+                            related.append(resource)
+                current = related
+        return list(included.values())
+
+
+class _Handler(BaseHTTPRequestHandler):
+    fake: FakeMBTAServer
+
+    def do_GET(self):
+        parts = urlsplit(self.path)
+        params = dict(parse_qsl(parts.query, keep_blank_values=True))
+        headers = dict(self.headers.items())
+        self.fake.requests.append((parts.path, params, headers))
+
+        fault = self.fake._take_fault(parts.path)
+        if fault is not None:
+            if fault.delay:
+                time.sleep(fault.delay)
+            if fault.status is not None:
+                self._send(fault.status, {'errors': [{'status': str(fault.status)}]},
+                           fault.headers)
+                return
+
+        headers = self.fake._rate_limit_headers()
+        last_modified = self.fake.last_modified
+        if last_modified:
+            if self.headers.get('If-Modified-Since') == last_modified:
+                self._send(304, None, headers)
+                return
+            headers['Last-Modified'] = last_modified
+
+        status, body = self.fake.handle(parts.path, params)
+        self._send(status, body, headers)
+
+    def _send(self, status: int, body: Optional[Dict], headers: Optional[Dict[str, str]] = None):
+        payload = json.dumps(body).encode() if body is not None else b''
+        try:
+            self.send_response(status)
+            self.send_header('Content-Type', 'application/vnd.api+json')
+            self.send_header('Content-Length', str(len(payload)))
+            for name, value in (headers or {}).items():
+                self.send_header(name, value)
+            self.end_headers()
+            self.wfile.write(payload)
+        except (BrokenPipeError, ConnectionResetError):
+            # The client gave up, e.g. after an injected delay
+            pass
+
+    def log_message(self, format, *args):
+        pass
@@ -0,0 +1,188 @@
+"""Tests for service alerts and their display."""
+
+import unittest
+from datetime import datetime, timedelta, timezone
+from unittest.mock import MagicMock, patch
+
+from instantmbta.alerts import Alert, alert_from_resource, select_alerts
+from instantmbta.clock import FixedClock
+from instantmbta.config_parser import Config, DisplayConfig, RouteConfig
+from instantmbta.display_modes import DisplayData, MultiStationMode, SingleStationMode
+from instantmbta.gtfs_realtime import GTFSRealtimeProvider
+from instantmbta.infogather import InfoGather
+
+SHUTTLE = {
+    'id': '123',
+    'type': 'alert',
+    'attributes': {
+        'header': 'Shuttle buses replace Orange Line service between Oak Grove and North Station',
+        'short_header': 'Shuttles Oak Grove - North Station',
+        'effect': 'SHUTTLE',
+        'severity': 7,
+        'active_period': [{'start': '2025-07-05T04:30:00-04:00', 'end': '2025-07-07T02:30:00-04:00'}],
+        'informed_entity': [
+            {'route': 'Orange', 'stop': 'place-ogmnl', 'route_type': 1},
+            {'route': 'Orange', 'stop': '70036', 'route_type': 1},
+        ],
+    },
+}
+
+
+def alert(severity, route='Orange', stop=None, active_period=None, header='Delays'):
+    return Alert(id=str(severity), header=header, effect='DELAY', severity=severity,
+                 active_period=active_period or [], informed_entity=[(route, stop)])
+
+
+class TestAlert(unittest.TestCase):
+    def test_from_resource(self):
+        a = alert_from_resource(SHUTTLE)
+        self.assertEqual(a.effect, 'SHUTTLE')
+        self.assertEqual(a.severity, 7)
+        self.assertEqual(a.short_header, 'Shuttles Oak Grove - North Station')
+        self.assertEqual(a.active_period[0][0].isoformat(), '2025-07-05T04:30:00-04:00')
+        self.assertIn(('Orange', 'place-ogmnl'), a.informed_entity)
+
+    def test_is_active(self):
+        a = alert_from_resource(SHUTTLE)
+        self.assertTrue(a.is_active(datetime(2025, 7, 6, 12, tzinfo=timezone(timedelta(hours=-4)))))
+        self.assertFalse(a.is_active(datetime(2025, 7, 7, 12, tzinfo=timezone(timedelta(hours=-4)))))
+        self.assertTrue(alert(7).is_active())  # No active period means always
+
+    def test_affects(self):
+        a = alert_from_resource(SHUTTLE)
+        self.assertTrue(a.affects(['Orange'], ['place-ogmnl']))
+        self.assertFalse(a.affects(['Orange'], ['place-welln']))
+        self.assertFalse(a.affects(['Red'], ['place-ogmnl']))
+        # Route-wide alert applies to every stop on the route
+        self.assertTrue(alert(7, stop=None).affects(['Orange'], ['place-welln']))
+
+    def test_select_alerts(self):
+        now = datetime.now().astimezone()
+        expired = [(now - timedelta(hours=2), now - timedelta(hours=1))]
+        alerts = [alert(3), alert(9), alert(7), alert(10, active_period=expired)]
+        self.assertEqual([a.severity for a in select_alerts(alerts, 7)], [9, 7])
+
+
+class TestInfoGatherAlerts(unittest.TestCase):
+    def setUp(self):
+        self.ig = InfoGather()
+        self.ig.logger = MagicMock()
+
+    def test_get_alerts_filters_by_stop(self):
+        other_stop = {'id': '456', 'attributes': {
+            'header': 'Elevator closed', 'effect': 'ELEVATOR_CLOSURE', 'severity': 3,
+            'informed_entity': [{'route': 'Orange', 'stop': 'place-welln'}],
+        }}
+        response = MagicMock(status_code=200)
+        response.json.return_value = {'data': [SHUTTLE, other_stop]}
+
+        with patch.object(self.ig, '_make_api_request', return_value=response) as mock_request:
+            alerts = self.ig.get_alerts(['Orange'], ['place-ogmnl'])
+
+        self.assertEqual([a.id for a in alerts], ['123'])
+        request = mock_request.call_args[0][0]
+        self.assertIn('/alerts?filter[datetime]=NOW', request)
+        self.assertIn('filter[route]=Orange', request)
+
+    def test_get_alerts_error(self):
+        with patch.object(self.ig, '_make_api_request', side_effect=Exception("down")):
+            self.assertEqual(self.ig.get_alerts(['Orange'], ['place-ogmnl']), [])
+
+
+class TestGTFSRealtimeAlerts(unittest.TestCase):
+    def test_alerts_feed(self):
+        static = MagicMock(timezone=timezone.utc)
+        static.stop_ids_for.return_value = ['place-ogmnl', '70036']
+        provider = GTFSRealtimeProvider('http://example.test/tu.pb', static,
+                                        alerts_url='http://example.test/alerts.pb')
+        feed = {'entity': [
+            {'id': 'a1', 'alert': {
+                'header_text': {'translation': [{'text': 'Suspension', 'language': 'en'}]},
+                'effect': 'NO_SERVICE',
+                'severity_level': 'SEVERE',
+                'informed_entity': [{'stop_id': '70036'}],
+            }},
+            {'id': 'a2', 'alert': {
+                'header_text': {'translation': [{'text': 'Elsewhere'}]},
+                'informed_entity': [{'route_id': 'Red'}],
+            }},
+        ]}
+        with patch.object(provider, '_fetch_feed', return_value=feed):
+            alerts = provider.get_alerts(['Orange'], ['place-ogmnl'])
+
+        self.assertEqual(len(alerts), 1)
+        self.assertEqual(alerts[0].header, 'Suspension')
+        self.assertEqual(alerts[0].severity, 9)
+
+    def test_no_alerts_url(self):
+        provider = GTFSRealtimeProvider('http://example.test/tu.pb', MagicMock())
+        self.assertEqual(provider.get_alerts(['Orange'], ['place-ogmnl']), [])
+
+
+class TestAlertDisplay(unittest.TestCase):
+    def setUp(self):
+        self.ig = MagicMock()
+        self.ig.get_predictions_filtered.return_value = []
+        self.ig.get_journey_trips.return_value = []
+        self.ig.get_alerts.return_value = [alert(5, header='Minor delays'), alert_from_resource(SHUTTLE)]
+        self.now = datetime(2025, 7, 6, 12, tzinfo=timezone(timedelta(hours=-4)))
+
+    def single_station_config(self, **display):
+        return Config(
+            mode='single-station',
+            station='Oak Grove',
+            station_id='place-ogmnl',
+            routes=[RouteConfig(route_id='Orange', route_name='Orange Line', inbound=1)],
+            display=DisplayConfig(**display),
+        )
+
+    def test_single_station_alert_footer(self):
+        mode = SingleStationMode(self.single_station_config(), clock=FixedClock(self.now))
+        data = mode.gather_data(self.ig)
+        display = mode.format_for_display(data)
+
+        self.ig.get_alerts.assert_called_once_with(['Orange'], ['place-ogmnl'])
+        self.assertEqual(display.alerts, ['Shuttles Oak Grove - North Station'])
+
+    def test_min_severity_and_disabled(self):
+        mode = SingleStationMode(self.single_station_config(alert_min_severity=3))
+        display = mode.format_for_display(mode.gather_data(self.ig))
+        self.assertEqual(display.alerts, ['Minor delays'])  # Shuttle has expired
+
+        mode = SingleStationMode(self.single_station_config(alerts=False))
+        self.ig.get_alerts.reset_mock()
+        display = mode.format_for_display(mode.gather_data(self.ig))
+        self.assertEqual(display.alerts, [])
+        self.ig.get_alerts.assert_not_called()
+
+    def test_multi_station_uses_both_stations(self):
+        config = Config(
+            mode='multi-station',
+            route_id='Green-B,Green-C',
+            route_name='Green Line',
+            from_station='Park Street',
+            from_station_id='place-pktrm',
+            to_station='Kenmore',
+            to_station_id='place-kencl',
+        )
+        mode = MultiStationMode(config)
+        mode.gather_data(self.ig)
+        self.ig.get_alerts.assert_called_once_with(
+            ['Green-B', 'Green-C'], ['place-pktrm', 'place-kencl'])
+
+    def test_alerts_error_does_not_break_display(self):
+        self.ig.get_alerts.side_effect = Exception("boom")
+        mode = SingleStationMode(self.single_station_config())
+        with self.assertLogs('instantmbta.SingleStationMode', level='ERROR') as logs:
+            data = mode.gather_data(self.ig)
+        self.assertIn('boom', logs.output[0])
+        self.assertEqual(data['alerts'], [])
+        self.assertEqual(data['errors'], [])
+
+    def test_display_data_equality_includes_alerts(self):
+        self.assertNotEqual(DisplayData(title='A', date='d', alerts=['x']),
+                            DisplayData(title='A', date='d'))
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,230 @@
+"""Tests for the station and route catalog."""
+
+import tempfile
+import time
+import unittest
+import zipfile
+from pathlib import Path
+from unittest.mock import MagicMock, patch
+
+import yaml
+
+from instantmbta.catalog import (
+    Catalog,
+    CatalogLookupError,
+    CatalogRoute,
+    CatalogStop,
+    load_catalog,
+    normalize,
+)
+from instantmbta.config_parser import Config, ConfigParser, GTFSConfig
+from instantmbta.models import Route, Stop
+
+from tests.test_gtfs_static import FEED
+
+STOPS = [
+    CatalogStop('place-ogmnl', 'Oak Grove', 1),
+    CatalogStop('70036', 'Oak Grove', 0, 'place-ogmnl'),
+    CatalogStop('place-harsq', 'Harvard', 1),
+    CatalogStop('place-wondl', 'Wonderland', 1),
+    CatalogStop('place-aqucl', 'Aquarium', 1),
+    CatalogStop('place-sstat', 'South Station', 1),
+    CatalogStop('place-WML-0035', 'Auburndale', 1),
+    CatalogStop('place-NEC-2203', 'Hyde Park', 1),
+    CatalogStop('place-FB-0118', 'Hyde Park Avenue', 1),
+    # Bus stops on either side of the street share a name
+    CatalogStop('1123', 'Massachusetts Ave @ Albany St', 0),
+    CatalogStop('1124', 'Massachusetts Ave @ Albany St', 0),
+    CatalogStop('2168', 'Harvard', 0),
+]
+
+ROUTES = [
+    CatalogRoute('Orange', '', 'Orange Line', 1),
+    CatalogRoute('Blue', '', 'Blue Line', 1),
+    CatalogRoute('CR-Fitchburg', '', 'Fitchburg Line', 2),
+    CatalogRoute('1', '1', 'Harvard Square - Nubian Station', 3),
+    CatalogRoute('741', 'SL1', 'Logan Airport Terminals - South Station', 3),
+]
+
+
+class TestCatalog(unittest.TestCase):
+    def setUp(self):
+        self.catalog = Catalog(STOPS, ROUTES)
+
+    def test_normalize(self):
+        self.assertEqual(normalize('Mass. Ave @ Albany St'), 'mass avenue albany st')
+        self.assertEqual(normalize('Kendall/MIT'), 'kendall/mit')
+        self.assertEqual(normalize('Harvard Sq'), 'harvard square')
+
+    def test_resolve_stations(self):
+        self.assertEqual(self.catalog.resolve_station('Wonderland'), 'place-wondl')
+        self.assertEqual(self.catalog.resolve_station('aquarium'), 'place-aqucl')
+        self.assertEqual(self.catalog.resolve_station('Auburndale'), 'place-WML-0035')
+        # Optional suffix and abbreviation aliases
+        self.assertEqual(self.catalog.resolve_station('Harvard Sq'), 'place-harsq')
+        self.assertEqual(self.catalog.resolve_station('South Station'), 'place-sstat')
+        # IDs, including platforms and bus stops, pass through
+        self.assertEqual(self.catalog.resolve_station('70036'), '70036')
+        self.assertEqual(self.catalog.resolve_station('1123'), '1123')
+
+    def test_fuzzy_match(self):
+        # A single close match is used as-is
+        self.assertEqual(self.catalog.resolve_station('Wonderlnd'), 'place-wondl')
+        self.assertEqual(self.catalog.resolve_station('Hyde Prk Ave'), 'place-FB-0118')
+
+    def test_unknown_station_suggestions(self):
+        with self.assertRaises(CatalogLookupError) as ctx:
+            self.catalog.resolve_station('Hide Parc')
+        self.assertIn('Did you mean', str(ctx.exception))
+        self.assertEqual(ctx.exception.suggestions[0], 'Hyde Park')
+
+        with self.assertRaises(CatalogLookupError) as ctx:
+            self.catalog.resolve_station('Zzyzx')
+        self.assertEqual(str(ctx.exception), "Unknown station 'Zzyzx'")
+
+    def test_ambiguous_station(self):
+        with self.assertRaises(CatalogLookupError) as ctx:
+            self.catalog.resolve_station('Massachusetts Ave @ Albany St')
+        self.assertIn('ambiguous', str(ctx.exception))
+        self.assertEqual(ctx.exception.suggestions, [
+            'Massachusetts Ave @ Albany St (1123)',
+            'Massachusetts Ave @ Albany St (1124)',
+        ])
+
+    def test_resolve_routes(self):
+        self.assertEqual(self.catalog.resolve_route('Fitchburg Line'), 'CR-Fitchburg')
+        self.assertEqual(self.catalog.resolve_route('fitchburg'), 'CR-Fitchburg')
+        self.assertEqual(self.catalog.resolve_route('Blue Line'), 'Blue')
+        self.assertEqual(self.catalog.resolve_route('SL1'), '741')
+        self.assertEqual(self.catalog.resolve_route('1'), '1')
+        with self.assertRaises(CatalogLookupError) as ctx:
+            self.catalog.resolve_route('Fichbrg')
+        self.assertIn('Fitchburg Line', ctx.exception.suggestions)
+
+    def test_route_type(self):
+        self.assertEqual(self.catalog.route_type('741'), 3)
+        self.assertEqual(self.catalog.route_type('Orange,Blue'), 1)
+        self.assertIsNone(self.catalog.route_type('Orange,1'))
+        self.assertIsNone(self.catalog.route_type('Nope'))
+
+    def test_cache_round_trip(self):
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            path = Path(tmp_dir) / 'catalog.json'
+            self.catalog.save(path)
+            loaded = Catalog.load(path)
+        self.assertEqual(loaded.stops, self.catalog.stops)
+        self.assertEqual(loaded.routes, self.catalog.routes)
+        self.assertEqual(loaded.created, self.catalog.created)
+
+    def test_from_api(self):
+        ig = MagicMock()
+        station = Stop('place-ogmnl', 'Oak Grove', location_type=1)
+        ig.get_all_stops.return_value = [
+            station,
+            Stop('70036', 'Oak Grove', location_type=0, parent_station=station),
+        ]
+        ig.get_all_routes.return_value = [Route('Orange', 'Orange Line', '', type=1)]
+        catalog = Catalog.from_api(ig)
+        self.assertEqual(catalog.stops['70036'].parent_station, 'place-ogmnl')
+        self.assertEqual(catalog.resolve_station('Oak Grove'), 'place-ogmnl')
+        self.assertEqual(catalog.resolve_route('Orange Line'), 'Orange')
+
+
+class TestLoadCatalog(unittest.TestCase):
+    def setUp(self):
+        self.temp_dir = tempfile.TemporaryDirectory()
+        self.temp_path = Path(self.temp_dir.name)
+        self.cache_path = self.temp_path / 'catalog.json'
+        self.config = Config(mode='single-station')
+
+    def tearDown(self):
+        self.temp_dir.cleanup()
+
+    def test_fetches_and_caches(self):
+        with patch('instantmbta.catalog.Catalog.from_api', return_value=Catalog(STOPS, ROUTES)) as mock_api:
+            catalog = load_catalog(self.config, self.cache_path)
+            self.assertTrue(self.cache_path.exists())
+            load_catalog(self.config, self.cache_path)
+        self.assertEqual(mock_api.call_count, 1)
+        self.assertEqual(catalog.resolve_station('Wonderland'), 'place-wondl')
+
+    def test_stale_cache_used_when_offline(self):
+        Catalog(STOPS, ROUTES, created=time.time() - 30 * 24 * 3600).save(self.cache_path)
+        with patch('instantmbta.catalog.Catalog.from_api', side_effect=Exception("offline")):
+            catalog = load_catalog(self.config, self.cache_path)
+        self.assertEqual(catalog.resolve_station('Wonderland'), 'place-wondl')
+
+    def test_offline_without_cache(self):
+        with patch('instantmbta.catalog.Catalog.from_api', side_effect=Exception("offline")):
+            self.assertIsNone(load_catalog(self.config, self.cache_path))
+
+    def test_from_gtfs_feed(self):
+        zip_path = self.temp_path / 'feed.zip'
+        with zipfile.ZipFile(zip_path, 'w') as zf:
+            for name, content in FEED.items():
+                zf.writestr(name, content)
+        self.config.gtfs = GTFSConfig(zip_path=str(zip_path), database=str(self.temp_path / 'gtfs.sqlite'))
+
+        with patch('instantmbta.catalog.Catalog.from_api') as mock_api:
+            catalog = load_catalog(self.config, self.cache_path)
+        mock_api.assert_not_called()
+        self.assertEqual(catalog.resolve_station('Wellington'), 'place-welln')
+        self.assertEqual(catalog.resolve_route('Orange Line'), 'Orange')
+
+
+class TestConfigParserWithCatalog(unittest.TestCase):
+    def setUp(self):
+        self.temp_dir = tempfile.TemporaryDirectory()
+        self.config_path = Path(self.temp_dir.name) / 'config.yaml'
+
+    def tearDown(self):
+        self.temp_dir.cleanup()
+
+    def parse(self, config_dict, **kwargs):
+        with open(self.config_path, 'w') as f:
+            yaml.dump(config_dict, f)
+        return ConfigParser(**kwargs).parse_yaml(self.config_path)
+
+    def test_catalog_resolves_names_outside_builtin_table(self):
+        config = self.parse({
+            'mode': 'multi-station',
+            'route': 'Blue Line',
+            'from': 'Wonderland',
+            'to': 'Aquarium',
+        }, catalog=Catalog(STOPS, ROUTES))
+        self.assertEqual(config.route_id, 'Blue')
+        self.assertEqual(config.from_station_id, 'place-wondl')
+        self.assertEqual(config.to_station_id, 'place-aqucl')
+
+    def test_builtin_names_are_aliases(self):
+        # 'Oak Grove' and 'Orange Line' resolve from the built-in tables
+        config = self.parse({
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'OL': {'inbound': 1}}],
+        }, catalog=Catalog([], []))
+        self.assertEqual(config.station_id, 'place-ogmnl')
+        self.assertEqual(config.routes[0].route_id, 'Orange')
+
+    def test_unknown_name_errors(self):
+        with self.assertRaises(ValueError) as ctx:
+            self.parse({
+                'mode': 'single-station',
+                'station': 'Wonderlund Park',
+                'routes': [{'Blue Line': {'inbound': 1}}],
+            }, catalog=Catalog(STOPS, ROUTES))
+        self.assertIn("Did you mean: Wonderland", str(ctx.exception))
+
+    def test_catalog_loader_receives_config(self):
+        loader = MagicMock(return_value=Catalog(STOPS, ROUTES))
+        config = self.parse({
+            'mode': 'single-station',
+            'station': 'Aquarium',
+            'routes': [{'Blue Line': {'inbound': 1}}],
+        }, catalog_loader=loader)
+        self.assertEqual(config.station_id, 'place-aqucl')
+        self.assertEqual(loader.call_args[0][0].mode, 'single-station')
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,87 @@
+"""Tests for service-day and timezone handling."""
+
+import unittest
+from datetime import date, datetime, timedelta, timezone
+from unittest.mock import MagicMock, patch
+
+from instantmbta.clock import MBTA_TIMEZONE, Clock, FixedClock
+from instantmbta.config_parser import Config, RouteConfig
+from instantmbta.display_modes import SingleStationMode
+from instantmbta.infogather import InfoGather
+
+
+def boston(*args):
+    return datetime(*args, tzinfo=MBTA_TIMEZONE)
+
+
+class TestClock(unittest.TestCase):
+    def test_service_date_ends_at_3am(self):
+        clock = Clock()
+        self.assertEqual(clock.service_date(boston(2025, 7, 12, 0, 40)), date(2025, 7, 11))
+        self.assertEqual(clock.service_date(boston(2025, 7, 12, 2, 59)), date(2025, 7, 11))
+        self.assertEqual(clock.service_date(boston(2025, 7, 12, 3, 0)), date(2025, 7, 12))
+
+    def test_service_time_runs_past_24(self):
+        clock = Clock()
+        self.assertEqual(clock.service_time(boston(2025, 7, 12, 0, 40)), '24:40')
+        self.assertEqual(clock.service_time(boston(2025, 7, 12, 23, 5)), '23:05')
+
+    def test_utc_input_is_converted(self):
+        # 04:30 UTC is 00:30 in Boston, still Friday's service
+        clock = Clock()
+        utc = datetime(2025, 7, 12, 4, 30, tzinfo=timezone.utc)
+        self.assertEqual(clock.service_date(utc), date(2025, 7, 11))
+        self.assertEqual(clock.service_time(utc), '24:30')
+
+    def test_fixed_clock(self):
+        clock = FixedClock(datetime(2025, 3, 9, 1, 30))  # Naive means Boston time
+        self.assertEqual(clock.now().isoformat(), '2025-03-09T01:30:00-05:00')
+        clock.advance(3600)  # Across the spring-forward gap
+        self.assertEqual(clock.now().isoformat(), '2025-03-09T03:30:00-04:00')
+
+
+class TestInfoGatherServiceDay(unittest.TestCase):
+    def setUp(self):
+        self.clock = FixedClock(boston(2025, 7, 12, 0, 30))
+        self.ig = InfoGather(clock=self.clock)
+        self.ig.logger = MagicMock()
+
+    def response(self, data):
+        response = MagicMock(status_code=200)
+        response.json.return_value = {'data': data}
+        return response
+
+    def test_schedule_request_uses_service_day(self):
+        with patch.object(self.ig, '_make_api_request', return_value=self.response([])) as mock_request:
+            self.ig.get_schedule('Orange', 'place-ogmnl', '0')
+        request = mock_request.call_args[0][0]
+        self.assertIn('filter[date]=2025-07-11', request)
+        self.assertIn('filter[min_time]=24:30', request)
+
+    def test_journey_schedule_request_uses_service_day(self):
+        with patch.object(self.ig, '_make_api_request', return_value=self.response([])) as mock_request:
+            self.ig.get_journey_trips('Orange', 'place-ogmnl', 'place-welln')
+        request = mock_request.call_args_list[0][0][0]
+        self.assertIn('filter[date]=2025-07-11', request)
+        self.assertIn('filter[min_time]=24:30', request)
+
+
+class TestDisplayTimezone(unittest.TestCase):
+    def test_times_and_date_shown_in_boston_time(self):
+        config = Config(
+            mode='single-station',
+            station='Oak Grove',
+            station_id='place-ogmnl',
+            routes=[RouteConfig(route_id='Orange', route_name='Orange Line', inbound=1)],
+        )
+        # The clock reads UTC but the display still shows Boston time
+        clock = FixedClock(datetime(2025, 7, 12, 3, 30, tzinfo=timezone.utc))
+        mode = SingleStationMode(config, clock=clock)
+
+        self.assertEqual(mode.format_time(datetime(2025, 7, 12, 3, 45, tzinfo=timezone.utc)), '11:45 PM')
+        display = mode.format_for_display({'station': 'Oak Grove', 'predictions': [], 'errors': []})
+        self.assertEqual(display.date, '07/11/25')
+
+
+if __name__ == '__main__':
+    unittest.main()
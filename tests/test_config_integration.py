@@ -1,33 +1,46 @@
-"""Integration test for config parser with real MBTA API."""
+"""Integration test for config parser with the MBTA API (served by FakeMBTAServer)."""
 
 import unittest
 import tempfile
 import yaml
 from pathlib import Path
 
+from instantmbta.catalog import Catalog
+from instantmbta.clock import FixedClock
 from instantmbta.config_parser import ConfigParser
 from instantmbta.infogather import InfoGather
 
+from tests.fake_mbta import FakeMBTAServer
+
 
 class TestConfigIntegration(unittest.TestCase):
-    """Test configuration with real API calls."""
-    
+    """Test configuration against the fake API's 'basic' scenario."""
+
+    @classmethod
+    def setUpClass(cls):
+        cls.server = FakeMBTAServer('basic').start()
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.server.stop()
+
     def setUp(self):
         self.parser = ConfigParser()
-        self.ig = InfoGather()
+        self.ig = InfoGather(api_url=self.server.url, clock=FixedClock(self.server.now))
         self.temp_dir = tempfile.TemporaryDirectory()
         self.temp_path = Path(self.temp_dir.name)
-    
+        self.server.requests.clear()
+
     def tearDown(self):
         self.temp_dir.cleanup()
-    
+
     def write_config(self, config_dict: dict) -> Path:
         """Helper to write a test config file."""
         config_path = self.temp_path / 'test_config.yaml'
         with open(config_path, 'w') as f:
             yaml.dump(config_dict, f)
         return config_path
-    
+
     def test_oak_grove_multi_route(self):
         """Test Oak Grove configuration with Orange Line."""
         config_dict = {
@@ -37,27 +50,26 @@ class TestConfigIntegration(unittest.TestCase):
                 {'Orange Line': {'inbound': 2}}
             ]
         }
-        
+
         config_path = self.write_config(config_dict)
         config = self.parser.parse_yaml(config_path)
-        
+
         # Verify config parsing
         self.assertEqual(config.station_id, 'place-ogmnl')
         self.assertEqual(config.routes[0].route_id, 'Orange')
-        
-        # Test with real API
-        print("\nTesting Oak Grove Orange Line predictions...")
-        
-        for route in config.routes:
-            if route.has_inbound:
-                preds = self.ig.get_predictions_filtered(
-                    config.station_id, "0", route.route_id, route.inbound
-                )
-                print(f"\nRoute: {route.route_name}, Direction: inbound")
-                print(f"Found {len(preds)} predictions")
-                for i, pred in enumerate(preds):
-                    print(f"  Prediction {i+1}: {pred['departure_time']}")
-    
+
+        route = config.routes[0]
+        preds = self.ig.get_predictions_filtered(
+            config.station_id, "0", route.route_id, route.inbound
+        )
+        self.assertEqual([p.time.isoformat() for p in preds],
+                         ['2025-07-07T10:05:00-04:00', '2025-07-07T10:13:00-04:00'])
+        self.assertTrue(all(p.route_id == 'Orange' for p in preds))
+
+        params = self.server.requests_to('/predictions')[-1]
+        self.assertEqual(params['filter[stop]'], 'place-ogmnl')
+        self.assertEqual(params['filter[route]'], 'Orange')
+
     def test_central_square_bidirectional(self):
         """Test Central Square bidirectional configuration."""
         config_dict = {
@@ -67,34 +79,27 @@ class TestConfigIntegration(unittest.TestCase):
                 {'Red Line': {'inbound': 2, 'outbound': 2}}
             ]
         }
-        
+
         config_path = self.write_config(config_dict)
         config = self.parser.parse_yaml(config_path)
-        
+
         # Verify config parsing
         self.assertEqual(config.station_id, 'place-cntsq')
         self.assertEqual(config.routes[0].route_id, 'Red')
-        
-        print("\nTesting Central Square bidirectional predictions...")
+
         route = config.routes[0]
-        
-        # Get inbound predictions
-        if route.has_inbound:
-            inbound = self.ig.get_predictions_filtered(
-                config.station_id, "0", route.route_id, route.inbound
-            )
-            print(f"\nInbound predictions: {len(inbound)}")
-            for i, pred in enumerate(inbound):
-                print(f"  Inbound {i+1}: {pred['departure_time']}")
-
-        # Get outbound predictions
-        if route.has_outbound:
-            outbound = self.ig.get_predictions_filtered(
-                config.station_id, "1", route.route_id, route.outbound
-            )
-            print(f"\nOutbound predictions: {len(outbound)}")
-            for i, pred in enumerate(outbound):
-                print(f"  Outbound {i+1}: {pred['departure_time']}")
+        inbound = self.ig.get_predictions_filtered(
+            config.station_id, "0", route.route_id, route.inbound
+        )
+        outbound = self.ig.get_predictions_filtered(
+            config.station_id, "1", route.route_id, route.outbound
+        )
+
+        # Southbound trains end at Central in this scenario, so only arrivals
+        self.assertEqual([p.time.isoformat() for p in inbound],
+                         ['2025-07-07T10:05:00-04:00', '2025-07-07T10:11:00-04:00'])
+        self.assertEqual([p.time.isoformat() for p in outbound],
+                         ['2025-07-07T10:04:00-04:00', '2025-07-07T10:10:00-04:00'])
 
     def test_multi_station_mode_schedule(self):
         """Test multi-station mode with schedule data."""
@@ -104,35 +109,46 @@ class TestConfigIntegration(unittest.TestCase):
             'from': 'Central Square',
             'to': 'Harvard Square'
         }
-        
+
         config_path = self.write_config(config_dict)
         config = self.parser.parse_yaml(config_path)
-        
-        print("\nTesting multi-station mode (Central to Harvard)...")
-        
-        # Get schedule for from station
-        from_data = self.ig.get_current_schedule(
-            config.route_id, 
-            config.from_station_id
-        )
-        
-        print(f"\nFrom {config.from_station}:")
-        if from_data[0]:  # Next inbound arrival
-            print(f"  Next Inbound Arrival: {from_data[0]}")
-        if from_data[2]:  # Next inbound departure
-            print(f"  Next Inbound Departure: {from_data[2]}")
-        
-        # Get schedule for to station  
-        to_data = self.ig.get_current_schedule(
-            config.route_id,
-            config.to_station_id
+
+        trips = self.ig.get_journey_trips(
+            config.route_id, config.from_station_id, config.to_station_id, config.trips
         )
-        
-        print(f"\nTo {config.to_station}:")
-        if to_data[0]:  # Next inbound arrival
-            print(f"  Next Inbound Arrival: {to_data[0]}")
+
+        self.assertEqual(
+            [(t.departure_time.isoformat(), t.arrival_time.isoformat(), t.predicted) for t in trips],
+            [('2025-07-07T10:04:00-04:00', '2025-07-07T10:07:00-04:00', True),
+             ('2025-07-07T10:10:00-04:00', '2025-07-07T10:13:00-04:00', True),
+             ('2025-07-07T10:15:00-04:00', '2025-07-07T10:18:00-04:00', False)])
+        # Headsigns come from the trips pulled in with include=trip
+        self.assertEqual({t.destination for t in trips}, {'Alewife'})
+
+        params = self.server.requests_to('/schedules')[-1]
+        self.assertEqual(params['filter[date]'], '2025-07-07')
+        self.assertEqual(params['filter[min_time]'], '10:00')
+
+    def test_names_from_api_catalog(self):
+        """Station names outside the built-in list resolve through the catalog."""
+        parser = ConfigParser(catalog=Catalog.from_api(self.ig))
+        config_path = self.write_config({
+            'mode': 'single-station',
+            'station': 'Malden Centre',
+            'routes': [{'Orange': {'inbound': 1}}],
+        })
+        config = parser.parse_yaml(config_path)
+
+        self.assertEqual(config.station_id, 'place-mlmnl')
+        self.assertEqual(config.routes[0].route_id, 'Orange')
+
+    def test_alerts(self):
+        """Alerts are narrowed to the station being displayed."""
+        alerts = self.ig.get_alerts(['Orange'], ['place-ogmnl'])
+        self.assertEqual([a.id for a in alerts], ['600001'])
+
+        self.assertEqual(self.ig.get_alerts(['Red'], ['place-harsq']), [])
 
 
 if __name__ == '__main__':
-    # Run with verbosity to see print statements
-    unittest.main(verbosity=2)
\ No newline at end of file
+    unittest.main()
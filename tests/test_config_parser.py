@@ -1,10 +1,13 @@
 """Tests for the configuration parser."""
 
+import os
 import unittest
+from unittest.mock import patch
 import tempfile
 import yaml
 from pathlib import Path
-from instantmbta.config_parser import ConfigParser, Config
+from datetime import datetime, time
+from instantmbta.config_parser import ConfigParser, Config, Profile, RouteConfig
 
 
 class TestConfigParser(unittest.TestCase):
@@ -62,6 +65,7 @@ class TestConfigParser(unittest.TestCase):
         self.assertEqual(haverhill_route.route_id, 'CR-Haverhill')
         self.assertEqual(haverhill_route.route_name, 'Haverhill Line')
         self.assertEqual(haverhill_route.inbound, 1)
+        self.assertFalse(config.streaming)
         
         # Verify display settings
         self.assertEqual(config.display.time_format, '12h')
@@ -75,6 +79,7 @@ class TestConfigParser(unittest.TestCase):
             'route': 'Red Line',
             'from': 'Central Square',
             'to': 'Harvard Square',
+            'trips': 4,
             'display': {
                 'show_route': True,
                 'time_format': '24h'
@@ -91,6 +96,7 @@ class TestConfigParser(unittest.TestCase):
         self.assertEqual(config.from_station_id, 'place-cntsq')
         self.assertEqual(config.to_station, 'Harvard Square')
         self.assertEqual(config.to_station_id, 'place-harsq')
+        self.assertEqual(config.trips, 4)
         self.assertTrue(config.display.show_route)
         self.assertEqual(config.display.time_format, '24h')
     
@@ -152,6 +158,224 @@ class TestConfigParser(unittest.TestCase):
             result = self.parser.resolve_route_id(route_name)
             self.assertEqual(result, expected_id)
     
+    def test_commuter_rail_routes(self):
+        """Commuter Rail routes are shown by schedule."""
+        self.assertTrue(RouteConfig('CR-Haverhill', 'Haverhill Line').commuter_rail)
+        self.assertTrue(RouteConfig('CR-Haverhill,CR-Lowell', 'North Side').commuter_rail)
+        self.assertFalse(RouteConfig('Orange', 'Orange Line').commuter_rail)
+        self.assertFalse(RouteConfig('Orange,CR-Haverhill', 'Malden').commuter_rail)
+
+    def test_bus_and_ferry_routes(self):
+        """Bus, Silver Line and ferry routes resolve to IDs and get a route type."""
+        test_cases = [
+            ('1', '1'),
+            ('34E', '34E'),
+            ('Route 111', '111'),
+            ('SL4', '751'),
+            ('Silver Line 1', '741'),
+            ('SL Waterfront', '746'),
+            ('Boat-F1', 'Boat-F1'),
+        ]
+        for route_name, expected_id in test_cases:
+            with self.subTest(route_name=route_name):
+                self.assertEqual(self.parser.resolve_route_id(route_name), expected_id)
+
+        self.assertEqual(RouteConfig('1', 'Route 1').route_type, 3)
+        self.assertEqual(RouteConfig('741,742', 'Silver Line').route_type, 3)
+        self.assertEqual(RouteConfig('Boat-F1', 'Hingham Ferry').route_type, 4)
+        self.assertEqual(RouteConfig('Green-B', 'Green Line B').route_type, 0)
+        self.assertIsNone(RouteConfig('Orange,1', 'Malden').route_type)
+
+    def test_green_line_branches(self):
+        """The Green Line can be limited to some of its branches."""
+        self.assertEqual(self.parser.resolve_route_id('Green Line'), 'Green-B,Green-C,Green-D,Green-E')
+        self.assertEqual(self.parser.resolve_route_id('Green Line D'), 'Green-D')
+        self.assertEqual(self.parser.resolve_route_id('GL-E'), 'Green-E')
+
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Kenmore',
+            'routes': [{'Green Line': {'outbound': 3, 'branches': ['D', 'e']}}],
+        }
+        config = self.parser.parse_yaml(self.write_config('green_test.yaml', config_dict))
+        route, = config.routes
+        self.assertEqual(route.route_id, 'Green-D,Green-E')
+        self.assertEqual(route.branches, ['D', 'E'])
+        self.assertEqual(route.route_type, 0)
+
+        config_dict['routes'] = [{'Green Line': {'outbound': 3, 'branches': ['F']}}]
+        with self.assertRaises(ValueError) as cm:
+            self.parser.parse_yaml(self.write_config('green_unknown_test.yaml', config_dict))
+        self.assertIn('F', str(cm.exception))
+
+        config_dict['routes'] = [{'Orange Line': {'outbound': 3, 'branches': ['D']}}]
+        with self.assertRaises(ValueError) as cm:
+            self.parser.parse_yaml(self.write_config('green_orange_test.yaml', config_dict))
+        self.assertIn('Green Line', str(cm.exception))
+
+    def test_headsign_filters(self):
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'JFK/UMass',
+            'routes': [{'Red Line': {'outbound': 2, 'headsigns': ['Ashmont']}},
+                       {'Orange Line': {'inbound': 1, 'exclude_headsigns': ['Forest Hills']}}],
+        }
+        config = self.parser.parse_yaml(self.write_config('headsign_test.yaml', config_dict))
+        red, orange = config.routes
+        self.assertEqual(red.headsigns, ['Ashmont'])
+        self.assertTrue(red.filters_headsigns)
+        self.assertTrue(red.wants_headsign('Ashmont'))
+        self.assertTrue(red.wants_headsign('ashmont'))
+        self.assertFalse(red.wants_headsign('Braintree'))
+        self.assertFalse(red.wants_headsign(None))
+        self.assertTrue(orange.wants_headsign('Oak Grove'))
+        self.assertFalse(orange.wants_headsign('Forest Hills'))
+        self.assertTrue(orange.wants_headsign(None))
+
+        self.assertFalse(RouteConfig('Orange', 'Orange Line').filters_headsigns)
+
+    def test_walk_minutes(self):
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'walk_minutes': 6,
+            'routes': [{'Orange Line': {'inbound': 1}},
+                       {'Haverhill Line': {'inbound': 1, 'walk_minutes': 8}}],
+        }
+        config = self.parser.parse_yaml(self.write_config('walk_test.yaml', config_dict))
+        orange, haverhill = config.routes
+        self.assertEqual(config.walk_minutes_for(orange), 6)
+        self.assertEqual(config.walk_minutes_for(haverhill), 8)
+
+        del config_dict['walk_minutes']
+        config = self.parser.parse_yaml(self.write_config('walk_default_test.yaml', config_dict))
+        self.assertEqual(config.walk_minutes_for(config.routes[0]), 0)
+
+        config_dict['walk_minutes'] = -1
+        with self.assertRaises(ValueError):
+            self.parser.parse_yaml(self.write_config('walk_negative_test.yaml', config_dict))
+
+    def test_walk_minutes_type(self):
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'walk_minutes': '5',
+            'routes': [{'Orange Line': {'inbound': 1, 'walk_minutes': '7'}}],
+        }
+        config = self.parser.parse_yaml(self.write_config('walk_string_test.yaml', config_dict))
+        self.assertEqual(config.walk_minutes, 5)
+        self.assertEqual(config.routes[0].walk_minutes, 7)
+
+        for bad in ('five', 2.5):
+            config_dict['walk_minutes'] = bad
+            with self.assertRaisesRegex(ValueError, 'whole number of minutes'):
+                self.parser.parse_yaml(self.write_config('walk_bad_test.yaml', config_dict))
+
+        config_dict['walk_minutes'] = 5
+        config_dict['routes'] = [{'Orange Line': {'inbound': 1, 'walk_minutes': 'soon'}}]
+        with self.assertRaisesRegex(ValueError, 'whole number of minutes'):
+            self.parser.parse_yaml(self.write_config('walk_bad_route_test.yaml', config_dict))
+
+    def test_profiles(self):
+        """Profiles each have a window and their own mode, stations and routes."""
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 2}}],
+            'display': {'time_format': 'countdown'},
+            'profiles': {
+                'morning': {
+                    'days': 'weekdays',
+                    'start': '06:30',
+                    'end': 570,  # 09:30 unquoted
+                    'station': 'Oak Grove',
+                    'routes': [{'Orange Line': {'inbound': 3}}],
+                },
+                'evening': {
+                    'days': ['mon', 'Friday'],
+                    'start': '16:30',
+                    'end': '19:00',
+                    'mode': 'multi-station',
+                    'route': 'Red Line',
+                    'from': 'Park Street',
+                    'to': 'Davis',
+                },
+            },
+        }
+        config = self.parser.parse_yaml(self.write_config('profiles_test.yaml', config_dict))
+        self.assertEqual(config.station, 'Oak Grove')
+        profiles = {profile.name: profile for profile in config.profiles}
+        morning, evening = profiles['morning'], profiles['evening']
+        self.assertEqual((morning.name, morning.start, morning.end, morning.days),
+                         ('morning', time(6, 30), time(9, 30), [0, 1, 2, 3, 4]))
+        self.assertEqual(morning.config.routes[0].inbound, 3)
+        self.assertEqual(evening.days, [0, 4])
+        self.assertEqual(evening.config.mode, 'multi-station')
+        self.assertEqual(evening.config.route_id, 'Red')
+        self.assertEqual(evening.config.from_station_id, 'place-pktrm')
+        # Display settings are shared
+        self.assertIs(evening.config.display, config.display)
+
+        monday = datetime(2025, 7, 7, 7, 0)
+        self.assertIs(config.profile_at(monday), morning)
+        self.assertIs(config.profile_at(monday.replace(hour=17)), evening)
+        self.assertIsNone(config.profile_at(monday.replace(hour=12)))
+        self.assertIsNone(config.profile_at(datetime(2025, 7, 8, 17, 0)))  # Tuesday
+        self.assertEqual(config.next_profile_change(monday), monday.replace(hour=9, minute=30))
+        self.assertEqual(config.next_profile_change(monday.replace(hour=20)), datetime(2025, 7, 8, 6, 30))
+
+    def test_default_profile(self):
+        config_dict = {
+            'default_profile': 'home',
+            'profiles': {
+                'home': {'start': '00:00', 'end': '00:01', 'station': 'Oak Grove',
+                         'routes': [{'Orange Line': {'inbound': 1}}]},
+            },
+        }
+        config = self.parser.parse_yaml(self.write_config('default_profile_test.yaml', config_dict))
+        self.assertEqual(config.station_id, 'place-ogmnl')
+        self.assertEqual([p.name for p in config.profiles], ['home'])
+
+        config_dict['default_profile'] = 'away'
+        with self.assertRaises(ValueError):
+            self.parser.parse_yaml(self.write_config('unknown_profile_test.yaml', config_dict))
+
+    def test_profile_errors(self):
+        base = {'station': 'Oak Grove', 'routes': [{'Orange Line': {'inbound': 1}}]}
+        cases = [
+            {'start': '6am', 'end': '09:30'},
+            {'start': '06:30', 'end': '09:30', 'days': ['someday']},
+            {'start': '06:30', 'end': '06:30'},
+            {'start': '06:30', 'end': '09:30', 'routes': []},
+        ]
+        for profile in cases:
+            with self.subTest(profile=profile):
+                config_dict = dict(base, profiles={'broken': dict(base, **profile)})
+                with self.assertRaises(ValueError):
+                    self.parser.parse_yaml(self.write_config('profile_error_test.yaml', config_dict))
+
+    def test_overnight_profile(self):
+        profile = Profile('late', Config(mode='single-station'), start=time(23, 0), end=time(2, 0), days=[4])
+        self.assertTrue(profile.active(datetime(2025, 7, 11, 23, 30)))   # Friday night
+        self.assertTrue(profile.active(datetime(2025, 7, 12, 1, 30)))    # Into Saturday
+        self.assertFalse(profile.active(datetime(2025, 7, 12, 23, 30)))  # Saturday night
+        self.assertFalse(profile.active(datetime(2025, 7, 11, 1, 30)))   # Thursday's
+
+    def test_bus_stop(self):
+        """A bus stop can be given by its numeric ID."""
+        self.assertEqual(self.parser.resolve_station_id('2166'), '2166')
+
+        config_dict = {
+            'mode': 'single-station',
+            'station_id': 2166,
+            'routes': [{1: {'inbound': 2}}, {'SL4': {'outbound': 1}}],
+        }
+        config = self.parser.parse_yaml(self.write_config('bus_test.yaml', config_dict))
+        self.assertEqual(config.station_id, '2166')
+        self.assertEqual(config.station, '2166')
+        self.assertEqual([(r.route_id, r.route_name, r.route_type) for r in config.routes],
+                         [('1', '1', 3), ('751', 'SL4', 3)])
+
     def test_validation_errors(self):
         """Test configuration validation errors."""
         # Single-station mode without station
@@ -239,5 +463,139 @@ class TestConfigParser(unittest.TestCase):
                     config_path.unlink()
 
 
+    def test_time_format(self):
+        """Test countdown time format and rejection of unknown formats."""
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+            'display': {'time_format': 'countdown'}
+        }
+        
+        config = self.parser.parse_yaml(self.write_config('countdown_test.yaml', config_dict))
+        self.assertEqual(config.display.time_format, 'countdown')
+        
+        config_dict['display'] = {'time_format': 'minutes'}
+        with self.assertRaises(ValueError):
+            self.parser.parse_yaml(self.write_config('bad_format_test.yaml', config_dict))
+    
+    def test_alert_settings(self):
+        """Test alert display options and severity validation."""
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+            'display': {'alert_min_severity': 5}
+        }
+        
+        config = self.parser.parse_yaml(self.write_config('alerts_test.yaml', config_dict))
+        self.assertTrue(config.display.alerts)
+        self.assertEqual(config.display.alert_min_severity, 5)
+        
+        config_dict['display'] = {'alerts': False}
+        config = self.parser.parse_yaml(self.write_config('alerts_off_test.yaml', config_dict))
+        self.assertFalse(config.display.alerts)
+        
+        config_dict['display'] = {'alert_min_severity': 11}
+        with self.assertRaises(ValueError):
+            self.parser.parse_yaml(self.write_config('alerts_bad_test.yaml', config_dict))
+
+    def test_vehicle_setting(self):
+        """Vehicle positions and occupancy cost extra requests, so they're opt-in."""
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+        }
+
+        config = self.parser.parse_yaml(self.write_config('vehicles_default_test.yaml', config_dict))
+        self.assertFalse(config.display.vehicles)
+
+        config_dict['display'] = {'vehicles': True}
+        config = self.parser.parse_yaml(self.write_config('vehicles_test.yaml', config_dict))
+        self.assertTrue(config.display.vehicles)
+        self.assertFalse(config.display.occupancy)
+        self.assertFalse(config.display.hide_full)
+
+        config_dict['display'] = {'occupancy': True, 'hide_full': True}
+        config = self.parser.parse_yaml(self.write_config('occupancy_test.yaml', config_dict))
+        self.assertTrue(config.display.occupancy)
+        self.assertTrue(config.display.hide_full)
+
+    def test_destinations_setting(self):
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+        }
+        config = self.parser.parse_yaml(self.write_config('destinations_default_test.yaml', config_dict))
+        self.assertTrue(config.display.destinations)
+
+        config_dict['display'] = {'destinations': False}
+        config = self.parser.parse_yaml(self.write_config('destinations_test.yaml', config_dict))
+        self.assertFalse(config.display.destinations)
+
+    def test_cancelled_setting(self):
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+        }
+
+        config = self.parser.parse_yaml(self.write_config('cancelled_default_test.yaml', config_dict))
+        self.assertTrue(config.display.show_cancelled)
+
+        config_dict['display'] = {'show_cancelled': False}
+        config = self.parser.parse_yaml(self.write_config('cancelled_test.yaml', config_dict))
+        self.assertFalse(config.display.show_cancelled)
+
+    def test_api_connection_settings(self):
+        """Test the MBTA API base URL and timeout."""
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+        }
+
+        config = self.parser.parse_yaml(self.write_config('api_default_test.yaml', config_dict))
+        self.assertIsNone(config.provider.api_url)
+        self.assertEqual(config.provider.timeout, 30)
+        self.assertEqual(config.provider.http_cache, 'instantmbta_http_cache.json')
+
+        config_dict['provider'] = {'api_url': 'http://localhost:8080', 'timeout': 5, 'http_cache': None}
+        config = self.parser.parse_yaml(self.write_config('api_test.yaml', config_dict))
+        self.assertEqual(config.provider.api_url, 'http://localhost:8080')
+        self.assertEqual(config.provider.timeout, 5)
+        self.assertIsNone(config.provider.http_cache)
+
+        config_dict['provider'] = {'api_key': 'config-key'}
+        with patch.dict(os.environ, {'MBTA_API_KEY': ''}):
+            config = self.parser.parse_yaml(self.write_config('api_key_test.yaml', config_dict))
+        self.assertEqual(config.provider.api_key, 'config-key')
+        self.assertNotIn('config-key', repr(config))
+
+        config_dict['provider'] = {'timeout': 0}
+        with self.assertRaises(ValueError):
+            self.parser.parse_yaml(self.write_config('api_bad_test.yaml', config_dict))
+
+    def test_gtfs_config(self):
+        """Test parsing the offline GTFS fallback section."""
+        config_dict = {
+            'mode': 'single-station',
+            'station': 'Oak Grove',
+            'routes': [{'Orange Line': {'inbound': 1}}],
+            'gtfs': {'zip': 'MBTA_GTFS.zip'}
+        }
+        
+        config = self.parser.parse_yaml(self.write_config('gtfs_test.yaml', config_dict))
+        
+        self.assertEqual(config.gtfs.zip_path, 'MBTA_GTFS.zip')
+        self.assertEqual(config.gtfs.database, 'instantmbta_gtfs.sqlite')
+        
+        del config_dict['gtfs']
+        config = self.parser.parse_yaml(self.write_config('no_gtfs_test.yaml', config_dict))
+        self.assertIsNone(config.gtfs)
+
+
 if __name__ == '__main__':
     unittest.main()
\ No newline at end of file
@@ -0,0 +1,86 @@
+"""Tests for API key lookup and log redaction."""
+
+import io
+import logging
+import os
+import tempfile
+import unittest
+from pathlib import Path
+from unittest.mock import patch
+
+from instantmbta.credentials import (
+    API_KEY_ENV,
+    RedactingFilter,
+    redact_logs,
+    resolve_api_key,
+)
+
+
+class TestResolveApiKey(unittest.TestCase):
+    def setUp(self):
+        self.temp_dir = tempfile.TemporaryDirectory()
+        self.key_file = Path(self.temp_dir.name) / 'api_key'
+        self.key_file.write_text('file-key\n')
+        # Keep the developer's own key and key file out of the tests
+        self.env = patch.dict(os.environ, {}, clear=False)
+        self.env.start()
+        os.environ.pop(API_KEY_ENV, None)
+        self.default_file = patch('instantmbta.credentials.API_KEY_FILE',
+                                  str(Path(self.temp_dir.name) / 'missing'))
+        self.default_file.start()
+
+    def tearDown(self):
+        self.default_file.stop()
+        self.env.stop()
+        self.temp_dir.cleanup()
+
+    def test_precedence(self):
+        os.environ[API_KEY_ENV] = 'env-key'
+        self.assertEqual(resolve_api_key('config-key', str(self.key_file)), 'env-key')
+
+        del os.environ[API_KEY_ENV]
+        self.assertEqual(resolve_api_key('config-key', str(self.key_file)), 'config-key')
+        self.assertEqual(resolve_api_key(None, str(self.key_file)), 'file-key')
+
+    def test_default_key_file(self):
+        with patch('instantmbta.credentials.API_KEY_FILE', str(self.key_file)):
+            self.assertEqual(resolve_api_key(), 'file-key')
+
+    def test_anonymous(self):
+        self.assertIsNone(resolve_api_key())
+        os.environ[API_KEY_ENV] = '  '
+        self.assertIsNone(resolve_api_key(''))
+
+    def test_missing_key_file(self):
+        with self.assertRaises(ValueError):
+            resolve_api_key(None, str(Path(self.temp_dir.name) / 'nope'))
+
+
+class TestRedaction(unittest.TestCase):
+    def test_redacts_formatted_message(self):
+        stream = io.StringIO()
+        logger = logging.getLogger('instantmbta.test_redaction')
+        logger.propagate = False
+        handler = logging.StreamHandler(stream)
+        logger.addHandler(handler)
+        try:
+            redact_logs(logger, 'sekrit')
+            redact_logs(logger, 'sekrit')
+            logger.warning("Request to %s failed", 'https://x/?api_key=sekrit')
+        finally:
+            logger.removeHandler(handler)
+
+        self.assertEqual(stream.getvalue(), "Request to https://x/?api_key=*** failed\n")
+        self.assertEqual(sum(isinstance(f, RedactingFilter) for f in handler.filters), 1)
+
+    def test_no_key_no_filter(self):
+        logger = logging.getLogger('instantmbta.test_redaction_none')
+        handler = logging.NullHandler()
+        logger.addHandler(handler)
+        redact_logs(logger, None)
+        self.assertEqual(handler.filters, [])
+        logger.removeHandler(handler)
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -2,18 +2,41 @@
 
 import unittest
 from unittest.mock import Mock, MagicMock, patch
-from datetime import datetime
+from datetime import datetime, time, timedelta
 import json
 
 from instantmbta.display_modes import (
     create_display_mode, 
     SingleStationMode, 
     MultiStationMode,
+    ProfileModes,
     TrainPrediction,
     DisplayData,
-    DisplayLine
+    DisplayLine,
+    FILTER_FETCH_FACTOR
 )
-from instantmbta.config_parser import Config, RouteConfig, DisplayConfig
+from instantmbta.config_parser import Config, Profile, RouteConfig, DisplayConfig
+from instantmbta.clock import FixedClock
+from instantmbta.models import (
+    Carriage, JourneyTrip, Prediction, Route, Schedule, Stop, Trip, Vehicle
+)
+
+
+def _time(value):
+    return datetime.fromisoformat(value) if value else None
+
+
+def prediction(departure=None, arrival=None, route_id='Orange', destination=None, **kwargs):
+    """A Prediction with times given as ISO strings."""
+    return Prediction(id=kwargs.pop('id', 'prediction'), departure_time=_time(departure),
+                      arrival_time=_time(arrival), route=Route(route_id),
+                      trip=Trip('trip', headsign=destination), **kwargs)
+
+
+def journey(trip_id, departure, arrival, from_arrival=None, **kwargs):
+    """A JourneyTrip with times given as ISO strings."""
+    return JourneyTrip(trip_id=trip_id, departure_time=_time(departure), arrival_time=_time(arrival),
+                       from_arrival_time=_time(from_arrival), **kwargs)
 
 
 class TestDisplayModes(unittest.TestCase):
@@ -22,6 +45,10 @@ class TestDisplayModes(unittest.TestCase):
     def setUp(self):
         """Set up test fixtures."""
         self.mock_ig = Mock()
+        # The Haverhill Line goes through get_departures; one sequence of
+        # responses covers every route in config order
+        self.mock_ig.get_departures = self.mock_ig.get_predictions_filtered
+        self.mock_ig.get_alerts.return_value = []
         
     def create_single_station_config(self):
         """Create a test single-station configuration."""
@@ -80,6 +107,30 @@ class TestDisplayModes(unittest.TestCase):
         mode = create_display_mode(config)
         self.assertIsInstance(mode, MultiStationMode)
     
+    def test_profile_modes(self):
+        """The active profile's mode is used during its window, and the default outside it."""
+        config = self.create_single_station_config()
+        evening = Profile('evening', self.create_multi_station_config(), start=time(16, 30), end=time(19, 0),
+                          days=[0, 1, 2, 3, 4])
+        config.profiles = [evening]
+        default_mode = SingleStationMode(config)
+        clock = FixedClock(datetime.fromisoformat('2025-07-07T16:29:00-04:00'))  # Monday
+        profiles = ProfileModes(config, default_mode, clock)
+
+        self.assertEqual(profiles.active(), (None, default_mode))
+
+        clock.set(datetime.fromisoformat('2025-07-07T16:30:00-04:00'))
+        profile, mode = profiles.active()
+        self.assertIs(profile, evening)
+        self.assertIsInstance(mode, MultiStationMode)
+        self.assertIs(mode.clock, clock)
+        # Created once and kept
+        self.assertIs(profiles.active()[1], mode)
+
+        # Not at the weekend
+        clock.set(datetime.fromisoformat('2025-07-12T17:00:00-04:00'))
+        self.assertEqual(profiles.active(), (None, default_mode))
+
     def test_create_display_mode_invalid(self):
         """Test factory raises error for invalid mode."""
         config = Config(mode='invalid')
@@ -93,50 +144,18 @@ class TestDisplayModes(unittest.TestCase):
         
         # Mock predictions for Orange Line inbound
         orange_inbound_data = [
-            {
-                'attributes': {
-                    'departure_time': '2025-07-05T10:15:00-04:00',
-                    'departure_uncertainty': 120
-                },
-                'relationships': {
-                    'route': {'data': {'id': 'Orange'}}
-                }
-            },
-            {
-                'attributes': {
-                    'departure_time': '2025-07-05T10:23:00-04:00',
-                    'departure_uncertainty': 180
-                },
-                'relationships': {
-                    'route': {'data': {'id': 'Orange'}}
-                }
-            }
+            prediction('2025-07-05T10:15:00-04:00', departure_uncertainty=120),
+            prediction('2025-07-05T10:23:00-04:00', departure_uncertainty=180),
         ]
         
         # Mock predictions for Orange Line outbound
         orange_outbound_data = [
-            {
-                'attributes': {
-                    'departure_time': '2025-07-05T10:18:00-04:00',
-                    'departure_uncertainty': 60
-                },
-                'relationships': {
-                    'route': {'data': {'id': 'Orange'}}
-                }
-            }
+            prediction('2025-07-05T10:18:00-04:00', departure_uncertainty=60),
         ]
         
         # Mock predictions for Haverhill Line
         haverhill_data = [
-            {
-                'attributes': {
-                    'departure_time': '2025-07-05T10:30:00-04:00',
-                    'departure_uncertainty': 300
-                },
-                'relationships': {
-                    'route': {'data': {'id': 'CR-Haverhill'}}
-                }
-            }
+            prediction('2025-07-05T10:30:00-04:00', route_id='CR-Haverhill', departure_uncertainty=300),
         ]
         
         self.mock_ig.get_predictions_filtered.side_effect = [
@@ -222,23 +241,11 @@ class TestDisplayModes(unittest.TestCase):
         config = self.create_multi_station_config()
         mode = MultiStationMode(config)
         
-        # Mock schedule responses
-        self.mock_ig.get_current_schedule.side_effect = [
-            # From station (Central Square)
-            (
-                '2025-07-05T10:15:00-04:00',  # inbound arrival
-                '2025-07-05T10:20:00-04:00',  # outbound arrival
-                '2025-07-05T10:16:00-04:00',  # inbound departure
-                '2025-07-05T10:21:00-04:00'   # outbound departure
-            ),
-            # To station (Harvard Square)
-            (
-                '2025-07-05T10:18:00-04:00',  # inbound arrival
-                '2025-07-05T10:25:00-04:00',  # outbound arrival
-                '2025-07-05T10:19:00-04:00',  # inbound departure
-                '2025-07-05T10:26:00-04:00'   # outbound departure
-            )
+        trips = [
+            journey('trip-1', '2025-07-05T10:15:00-04:00', '2025-07-05T10:18:00-04:00', direction_id=1),
+            journey('trip-2', '2025-07-05T10:22:00-04:00', '2025-07-05T10:25:00-04:00', direction_id=1),
         ]
+        self.mock_ig.get_journey_trips.return_value = trips
         
         # Gather data
         data = mode.gather_data(self.mock_ig)
@@ -247,14 +254,13 @@ class TestDisplayModes(unittest.TestCase):
         self.assertEqual(data['route'], 'Red Line')
         self.assertEqual(data['from_station'], 'Central Square')
         self.assertEqual(data['to_station'], 'Harvard Square')
-        self.assertIsNotNone(data['from_schedule'])
-        self.assertIsNotNone(data['to_schedule'])
+        self.assertEqual(data['trips'], trips)
         self.assertEqual(len(data['errors']), 0)
         
-        # Verify API calls
-        self.assertEqual(self.mock_ig.get_current_schedule.call_count, 2)
-        self.mock_ig.get_current_schedule.assert_any_call('Red', 'place-cntsq')
-        self.mock_ig.get_current_schedule.assert_any_call('Red', 'place-harsq')
+        # Verify a single journey lookup for both stations
+        self.mock_ig.get_journey_trips.assert_called_once_with(
+            'Red', 'place-cntsq', 'place-harsq', 3
+        )
     
     def test_multi_station_format_display(self):
         """Test MultiStationMode display formatting."""
@@ -266,13 +272,10 @@ class TestDisplayModes(unittest.TestCase):
             'route': 'Red Line',
             'from_station': 'Central Square',
             'to_station': 'Harvard Square',
-            'from_schedule': {
-                'inbound_departure': '2025-07-05T10:16:00-04:00'
-            },
-            'to_schedule': {
-                'inbound_departure': '2025-07-05T10:19:00-04:00',
-                'outbound_departure': '2025-07-05T10:26:00-04:00'
-            },
+            'trips': [
+                journey('trip-1', '2025-07-05T10:15:00-04:00', '2025-07-05T10:18:00-04:00'),
+                journey('trip-2', '2025-07-05T10:22:00-04:00', '2025-07-05T10:25:00-04:00'),
+            ],
             'errors': []
         }
         
@@ -282,15 +285,16 @@ class TestDisplayModes(unittest.TestCase):
         # Verify basic structure
         self.assertEqual(display_data.title, 'Red Line')
         
-        # Find the station headers
         headers = [l for l in display_data.lines if l.is_header]
-        self.assertEqual(len(headers), 2)
-        self.assertEqual(headers[0].text, 'Central Square')
-        self.assertEqual(headers[1].text, 'Harvard Square')
-        
-        # Check times are formatted correctly
-        time_lines = [l for l in display_data.lines if 'Next' in l.text]
-        self.assertEqual(len(time_lines), 3)  # 1 at Central, 2 at Harvard
+        self.assertEqual(len(headers), 1)
+        self.assertEqual(headers[0].text, 'Central Square → Harvard Square')
+        
+        # Each trip pairs a departure with the same train's arrival
+        trip_lines = [l.text for l in display_data.lines if l.is_route]
+        self.assertEqual(trip_lines, [
+            'depart 10:15 AM → arrive 10:18 AM',
+            'depart 10:22 AM → arrive 10:25 AM',
+        ])
     
     def test_error_handling(self):
         """Test error handling in data gathering."""
@@ -334,11 +338,7 @@ class TestDisplayModes(unittest.TestCase):
         
         # Mock mixed results - Orange succeeds, Haverhill fails
         orange_predictions = [
-            {
-                'departure_time': '2025-07-06T10:15:00-04:00',
-                'route_id': 'Orange',
-                'destination': 'Forest Hills'
-            }
+            prediction('2025-07-06T10:15:00-04:00', destination='Forest Hills'),
         ]
         
         self.mock_ig.get_predictions_filtered.side_effect = [
@@ -354,20 +354,33 @@ class TestDisplayModes(unittest.TestCase):
         self.assertEqual(len(data['errors']), 1)
         self.assertIn('Haverhill Line', data['errors'][0])
 
-    def test_multi_station_missing_schedule_data(self):
-        """Test multi-station mode with missing schedule data."""
+    def test_multi_station_no_trips(self):
+        """Test multi-station mode when no trip serves both stations."""
         config = self.create_multi_station_config()
         mode = MultiStationMode(config)
         
-        # Mock partial schedule data
-        self.mock_ig.get_current_schedule.side_effect = [
-            (None, None, None, None),  # From station - no data
-            ('2025-07-06T10:15:00-04:00', None, '2025-07-06T10:16:00-04:00', None)  # To station - partial
-        ]
+        self.mock_ig.get_journey_trips.return_value = []
         
         data = mode.gather_data(self.mock_ig)
         display_data = mode.format_for_display(data)
         
+        lines_text = [line.text for line in display_data.lines]
+        self.assertIn('No upcoming trips', lines_text)
+
+    def test_multi_station_missing_arrival_time(self):
+        """Test multi-station mode with a trip missing its arrival time."""
+        config = self.create_multi_station_config()
+        mode = MultiStationMode(config)
+        
+        data = {
+            'route': 'Red Line',
+            'from_station': 'Central Square',
+            'to_station': 'Harvard Square',
+            'trips': [journey('trip-1', '2025-07-06T10:16:00-04:00', None)],
+            'errors': []
+        }
+        display_data = mode.format_for_display(data)
+        
         # Should handle missing data gracefully
         lines_text = [line.text for line in display_data.lines]
         self.assertIn('---', ' '.join(lines_text))  # Missing times shown as ---
@@ -418,23 +431,11 @@ class TestDisplayModes(unittest.TestCase):
 
         # Two inbound, one outbound
         inbound_predictions = [
-            {
-                'departure_time': '2025-07-06T10:15:00-04:00',
-                'route_id': 'Orange',
-                'departure_uncertainty': 60,   # 1 minute
-            },
-            {
-                'departure_time': '2025-07-06T10:20:00-04:00',
-                'route_id': 'Orange',
-                'departure_uncertainty': 300,  # 5 minutes
-            },
+            prediction('2025-07-06T10:15:00-04:00', departure_uncertainty=60),   # 1 minute
+            prediction('2025-07-06T10:20:00-04:00', departure_uncertainty=300),  # 5 minutes
         ]
         outbound_predictions = [
-            {
-                'departure_time': '2025-07-06T10:25:00-04:00',
-                'route_id': 'Orange',
-                'departure_uncertainty': None,  # No uncertainty
-            }
+            prediction('2025-07-06T10:25:00-04:00', departure_uncertainty=None),  # No uncertainty
         ]
 
         # gather_data will call get_predictions_filtered twice (dir 0 then dir 1)
@@ -449,5 +450,467 @@ class TestDisplayModes(unittest.TestCase):
         self.assertEqual(data['predictions'][1].uncertainty_minutes, 5)
         self.assertIsNone(data['predictions'][2].uncertainty_minutes)
 
+    def test_scheduled_fallback_marked(self):
+        """Departures from the static schedule are marked 'sched'."""
+        config = self.create_single_station_config()
+        mode = SingleStationMode(config)
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:15:00-04:00', scheduled=True)],
+            [prediction('2025-07-06T10:18:00-04:00')],
+            [],
+        ]
+
+        data = mode.gather_data(self.mock_ig)
+        self.assertTrue(data['predictions'][0].scheduled)
+        self.assertFalse(data['predictions'][1].scheduled)
+
+        display_data = mode.format_for_display(data)
+        lines = [l.text for l in display_data.lines]
+        self.assertIn('OL In: 10:15 AM sched', lines)
+        self.assertIn('OL Out: 10:18 AM', lines)
+
+    def test_vehicle_positions(self):
+        """The next train on each line shows where it is."""
+        config = self.create_single_station_config()
+        config.display.vehicles = True
+        mode = SingleStationMode(config)
+
+        def locate(predictions, count_stops):
+            self.assertTrue(count_stops)
+            first, second, outbound, commuter_rail = predictions
+            first.vehicle = Vehicle('v1', current_status='STOPPED_AT', stop=Stop('70032', 'Wellington'))
+            first.stops_away = 3
+            second.vehicle = Vehicle('v2', current_status='IN_TRANSIT_TO', stop=Stop('70034'))
+            second.stops_away = 1
+            outbound.vehicle = Vehicle('v3', current_status='INCOMING_AT')
+            outbound.stops_away = 0
+            return predictions
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:15:00-04:00'), prediction('2025-07-06T10:23:00-04:00')],
+            [prediction('2025-07-06T10:02:00-04:00')],
+            [prediction('2025-07-06T10:40:00-04:00')],
+        ]
+        self.mock_ig.locate_vehicles.side_effect = locate
+
+        data = mode.gather_data(self.mock_ig)
+        self.mock_ig.locate_vehicles.assert_called_once()
+        self.assertEqual(data['predictions'][1].vehicle_stop, 'Wellington')
+        self.assertEqual(data['predictions'][1].stops_away, 3)
+
+        lines = [l.text for l in mode.format_for_display(data).lines]
+        self.assertIn('OL In: 10:15 AM (Stopped at Wellington), 10:23 AM', lines)
+        self.assertIn('OL Out: 10:02 AM (Approaching)', lines)
+        self.assertIn('CR In: 10:40 AM', lines)
+
+    def test_vehicle_position_text(self):
+        config = self.create_single_station_config()
+        mode = SingleStationMode(config)
+        now = datetime.fromisoformat('2025-07-06T10:00:00-04:00')
+
+        def tp(status=None, stop=None, stops_away=None):
+            return TrainPrediction(now, 'Orange Line', 'inbound', vehicle_status=status,
+                                   vehicle_stop=stop, stops_away=stops_away)
+
+        self.assertEqual(mode.format_vehicle(tp('IN_TRANSIT_TO', 'Malden Center', 2)), '2 stops away')
+        self.assertEqual(mode.format_vehicle(tp('INCOMING_AT', 'Malden Center', 1)), '1 stop away')
+        self.assertEqual(mode.format_vehicle(tp('STOPPED_AT', 'Malden Center', 1)), 'Stopped at Malden Center')
+        self.assertEqual(mode.format_vehicle(tp('STOPPED_AT', 'Oak Grove', 0)), 'Boarding')
+        self.assertEqual(mode.format_vehicle(tp('IN_TRANSIT_TO', 'Oak Grove', 0)), 'Approaching')
+        self.assertIsNone(mode.format_vehicle(tp()))
+
+        # Countdown already says ARR or BRD
+        config.display.time_format = 'countdown'
+        self.assertIsNone(mode.format_vehicle(tp('STOPPED_AT', 'Oak Grove', 0)))
+
+    def test_vehicles_off_by_default(self):
+        mode = SingleStationMode(self.create_single_station_config())
+        self.mock_ig.get_predictions_filtered.return_value = [prediction('2025-07-06T10:15:00-04:00')]
+        mode.gather_data(self.mock_ig)
+        self.mock_ig.locate_vehicles.assert_not_called()
+
+    def test_occupancy(self):
+        """The next train's crowding goes on its line; full trains can be skipped."""
+        config = self.create_single_station_config()
+        config.display.occupancy = True
+        config.display.hide_full = True
+        mode = SingleStationMode(config)
+
+        def locate(predictions, count_stops):
+            full, standing, outbound, _ = predictions
+            full.vehicle = Vehicle('v1', carriages=[Carriage('1', 'FULL'), Carriage('2', 'FULL')])
+            standing.vehicle = Vehicle('v2', carriages=[Carriage('1', 'FULL'),
+                                                        Carriage('2', 'STANDING_ROOM_ONLY')])
+            outbound.vehicle = Vehicle('v3', occupancy_status='MANY_SEATS_AVAILABLE')
+            return predictions
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:15:00-04:00'), prediction('2025-07-06T10:23:00-04:00')],
+            [prediction('2025-07-06T10:18:00-04:00')],
+            [prediction('2025-07-06T10:40:00-04:00')],
+        ]
+        self.mock_ig.locate_vehicles.side_effect = locate
+
+        data = mode.gather_data(self.mock_ig)
+        self.assertFalse(self.mock_ig.locate_vehicles.call_args.kwargs['count_stops'])
+        self.assertEqual([p.occupancy for p in data['predictions']], [1, 2, None])
+
+        lines = mode.format_for_display(data).lines
+        self.assertEqual([(l.text, l.occupancy) for l in lines], [
+            ('OL Out: 10:18 AM', 1),
+            ('OL In: 10:23 AM', 2),
+            ('CR In: 10:40 AM', None),
+        ])
+
+    def test_hide_full_shows_next_train(self):
+        """A skipped full train is made up by the one after it."""
+        config = Config(mode='single-station', station='Oak Grove', station_id='place-ogmnl',
+                        routes=[RouteConfig('Orange', 'Orange Line', inbound=2, outbound=1)])
+        config.display.hide_full = True
+        mode = SingleStationMode(config)
+
+        def locate(predictions, count_stops):
+            for p in predictions:
+                if p.id.startswith('full'):
+                    p.vehicle = Vehicle(p.id, occupancy_status='FULL')
+            return predictions
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:15:00-04:00', id='full-in'),
+             prediction('2025-07-06T10:23:00-04:00', id='in-2'),
+             prediction('2025-07-06T10:31:00-04:00', id='in-3')],
+            [prediction('2025-07-06T10:18:00-04:00', id='full-out'),
+             prediction('2025-07-06T10:26:00-04:00', id='out-2')],
+        ]
+        self.mock_ig.locate_vehicles.side_effect = locate
+
+        data = mode.gather_data(self.mock_ig)
+        self.assertEqual(self.mock_ig.get_predictions_filtered.call_args_list[0][0][3],
+                         2 * FILTER_FETCH_FACTOR)
+        lines = [l.text for l in mode.format_for_display(data).lines]
+        self.assertEqual(lines, ['OL In: 10:23 AM, 10:31 AM', 'OL Out: 10:26 AM'])
+
+    def test_schedule_relationships(self):
+        """Cancelled and skipped trains stay on the display, marked."""
+        config = self.create_single_station_config()
+        config.display.vehicles = True
+        mode = SingleStationMode(config, clock=FixedClock(datetime.fromisoformat('2025-07-06T10:00:00-04:00')))
+
+        def locate(predictions, count_stops):
+            for p in predictions:
+                p.stops_away = 2
+            return predictions
+
+        self.mock_ig.locate_vehicles.side_effect = locate
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:15:00-04:00', schedule_relationship='CANCELLED'),
+             prediction('2025-07-06T10:23:00-04:00')],
+            [prediction('2025-07-06T10:18:00-04:00', schedule_relationship='ADDED'),
+             prediction('2025-07-06T10:26:00-04:00', schedule_relationship='NO_DATA')],
+            [prediction('2025-07-06T10:40:00-04:00', schedule_relationship='SKIPPED')],
+        ]
+
+        data = mode.gather_data(self.mock_ig)
+        self.assertTrue(data['predictions'][0].cancelled)
+        self.assertTrue(data['predictions'][3].scheduled)
+
+        lines = [l.text for l in mode.format_for_display(data).lines]
+        # The location goes with the first train that's actually coming
+        self.assertIn('OL In: 10:15 AM CANCELLED, 10:23 AM (2 stops away)', lines)
+        self.assertIn('OL Out: 10:18 AM added (2 stops away), 10:26 AM sched', lines)
+        self.assertIn('CR In: 10:40 AM SKIPPED', lines)
+
+        # Clock time in countdown mode too
+        config.display.time_format = 'countdown'
+        lines = [l.text for l in mode.format_for_display(data).lines]
+        self.assertIn('OL In: 10:15 AM CANCELLED, 20+ min (2 stops away)', lines)
+
+    def test_hide_cancelled(self):
+        """Hidden cancelled trains don't count against the trains shown."""
+        config = self.create_single_station_config()
+        config.display.show_cancelled = False
+        mode = SingleStationMode(config)
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:15:00-04:00', schedule_relationship='CANCELLED'),
+             prediction('2025-07-06T10:23:00-04:00'),
+             prediction('2025-07-06T10:31:00-04:00'),
+             prediction('2025-07-06T10:39:00-04:00')],
+            [prediction('2025-07-06T10:18:00-04:00', schedule_relationship='SKIPPED')],
+            [prediction('2025-07-06T10:40:00-04:00', schedule_relationship='ADDED')],
+        ]
+
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['OL In: 10:23 AM, 10:31 AM', 'CR In: 10:40 AM added'])
+        self.assertEqual(self.mock_ig.get_predictions_filtered.call_args_list[0][0][3],
+                         2 * FILTER_FETCH_FACTOR)
+
+    def test_commuter_rail_delays(self):
+        """Commuter Rail shows the scheduled time and how late, with train and track."""
+        config = self.create_single_station_config()
+        config.routes[1].outbound = 0
+        mode = SingleStationMode(config, clock=FixedClock(datetime.fromisoformat('2025-07-06T10:00:00-04:00')))
+
+        def departure(scheduled, predicted=None, **kwargs):
+            schedule = Schedule('schedule', departure_time=_time(scheduled))
+            return prediction(predicted, route_id='CR-Haverhill', schedule=schedule, **kwargs)
+
+        late = departure('2025-07-06T09:35:00-04:00', '2025-07-06T10:05:00-04:00', status='Delayed',
+                         stop=Stop('BNT-0000-03', platform_code='3'))
+        late.trip.name = '207'
+        self.mock_ig.get_predictions_filtered.side_effect = [[], []]
+        self.mock_ig.get_departures = Mock(return_value=[
+            late,
+            departure('2025-07-06T10:20:00-04:00', '2025-07-06T10:20:00-04:00', status='All aboard'),
+            departure('2025-07-06T10:50:00-04:00', '2025-07-06T10:50:30-04:00', status='On time'),
+            departure('2025-07-06T11:20:00-04:00'),
+        ])
+
+        data = mode.gather_data(self.mock_ig)
+        self.mock_ig.get_departures.assert_called_once_with('place-ogmnl', '0', 'CR-Haverhill', 1)
+        self.assertEqual(data['predictions'][0].delay, 30)
+
+        lines = [l.text for l in mode.format_for_display(data).lines]
+        self.assertEqual(lines, ['CR In: 9:35 AM (+30) #207 Trk 3, 10:20 AM All aboard, '
+                                 '10:50 AM, 11:20 AM'])
+
+        # Counting down to the predicted departure
+        config.display.time_format = 'countdown'
+        lines = [l.text for l in mode.format_for_display(data).lines]
+        self.assertEqual(lines, ['CR In: 5 min (+30) #207 Trk 3, 20 min All aboard, 20+ min, 20+ min'])
+
+    def test_bus_headsigns(self):
+        """Buses are labelled by headsign, one line per destination."""
+        config = Config(mode='single-station', station='Massachusetts Ave @ Albany St', station_id='1123',
+                        routes=[RouteConfig('1', '1', inbound=3), RouteConfig('751', 'SL4', outbound=1)])
+        mode = SingleStationMode(config)
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:05:00-04:00', route_id='1', destination='Harvard'),
+             prediction('2025-07-06T10:12:00-04:00', route_id='1', destination='Harvard via Central'),
+             prediction('2025-07-06T10:20:00-04:00', route_id='1', destination='Harvard')],
+            [prediction('2025-07-06T10:08:00-04:00', route_id='751', destination='South Station')],
+        ]
+
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['1 Harvard: 10:05 AM, 10:20 AM',
+                                 'SL4 South Station: 10:08 AM',
+                                 '1 Harvard via Central: 10:12 AM'])
+
+    def test_ferry_route_label(self):
+        """Ferries are labelled by route, not all as 'Ferry'."""
+        config = Config(mode='single-station', station='Long Wharf', station_id='Boat-Long',
+                        routes=[RouteConfig('Boat-F1', 'Hingham/Hull Ferry', outbound=1),
+                                RouteConfig('Boat-F4', 'Charlestown Ferry', outbound=1)])
+        mode = SingleStationMode(config)
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:30:00-04:00', route_id='Boat-F1', destination='Hingham')],
+            [prediction('2025-07-06T10:15:00-04:00', route_id='Boat-F4', destination='Charlestown')],
+        ]
+
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['F4 Charlestown: 10:15 AM', 'F1 Hingham: 10:30 AM'])
+
+    def test_bus_schedule_fallback(self):
+        """A bus route with no predictions shows its scheduled departures."""
+        config = Config(mode='single-station', station='Massachusetts Ave @ Albany St', station_id='1123',
+                        routes=[RouteConfig('1', 'Route 1', inbound=2)])
+        mode = SingleStationMode(config)
+
+        scheduled = prediction(route_id='1', destination='Harvard',
+                               schedule=Schedule('schedule', departure_time=_time('2025-07-06T10:30:00-04:00')))
+        self.mock_ig.get_predictions_filtered = Mock(return_value=[])
+        self.mock_ig.get_departures = Mock(return_value=[scheduled])
+
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.mock_ig.get_departures.assert_called_once_with('1123', '0', '1', 2)
+        self.assertEqual(lines, ['1 Harvard: 10:30 AM sched'])
+
+    def test_green_line_branches(self):
+        """Trunk stations merge the branches in time order, each with its letter and headsign."""
+        config = Config(mode='single-station', station='Kenmore', station_id='place-kencl',
+                        routes=[RouteConfig('Green-B,Green-C,Green-D,Green-E', 'Green Line', outbound=3,
+                                            branches=['C', 'D'])])
+        mode = SingleStationMode(config)
+
+        self.mock_ig.get_predictions_filtered.side_effect = [[
+            prediction('2025-07-06T10:02:00-04:00', route_id='Green-D', destination='Riverside'),
+            prediction('2025-07-06T10:04:00-04:00', route_id='Green-C', destination='Cleveland Circle'),
+            prediction('2025-07-06T10:09:00-04:00', route_id='Green-D', destination='Riverside'),
+        ]]
+
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.mock_ig.get_predictions_filtered.assert_called_once_with('place-kencl', '1', 'Green-C,Green-D', 3)
+        self.assertEqual(lines, ['GL Out: D Riverside 10:02 AM, C Cleveland Circle 10:04 AM, '
+                                 'D Riverside 10:09 AM'])
+
+        # Just the letters without destinations
+        config.display.destinations = False
+        self.mock_ig.get_predictions_filtered.side_effect = [[
+            prediction('2025-07-06T10:02:00-04:00', route_id='Green-D', destination='Riverside'),
+            prediction('2025-07-06T10:04:00-04:00', route_id='Green-C', destination='Cleveland Circle'),
+        ]]
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['GL Out: D 10:02 AM, C 10:04 AM'])
+
+    def test_headsign_filters(self):
+        """Only trains to the wanted headsigns are shown, each with its destination."""
+        config = Config(mode='single-station', station='JFK/UMass', station_id='place-jfk',
+                        routes=[RouteConfig('Red', 'Red Line', outbound=2, headsigns=['Ashmont'])])
+        mode = SingleStationMode(config)
+
+        self.mock_ig.get_predictions_filtered.side_effect = [[
+            prediction('2025-07-06T10:02:00-04:00', route_id='Red', destination='Braintree'),
+            prediction('2025-07-06T10:05:00-04:00', route_id='Red', destination='Ashmont'),
+            prediction('2025-07-06T10:09:00-04:00', route_id='Red', destination='Braintree'),
+            prediction('2025-07-06T10:14:00-04:00', route_id='Red', destination='Ashmont'),
+            prediction('2025-07-06T10:23:00-04:00', route_id='Red', destination='Ashmont'),
+        ]]
+
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        # More are asked for, since some will be filtered out
+        self.mock_ig.get_predictions_filtered.assert_called_once_with('place-jfk', '1', 'Red', 8)
+        self.assertEqual(lines, ['RL Out: 10:05 AM Ashmont, 10:14 AM Ashmont'])
+
+    def test_destinations(self):
+        """Every train shows where it's going, unless turned off."""
+        config = Config(mode='single-station', station='JFK/UMass', station_id='place-jfk',
+                        routes=[RouteConfig('Red', 'Red Line', outbound=2)])
+        mode = SingleStationMode(config)
+        responses = [[
+            prediction('2025-07-06T10:02:00-04:00', route_id='Red', destination='Braintree'),
+            prediction('2025-07-06T10:05:00-04:00', route_id='Red', destination='Ashmont'),
+        ]]
+
+        self.mock_ig.get_predictions_filtered.side_effect = responses
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['RL Out: 10:02 AM Braintree, 10:05 AM Ashmont'])
+
+        config.display.destinations = False
+        self.mock_ig.get_predictions_filtered.side_effect = responses
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['RL Out: 10:02 AM, 10:05 AM'])
+
+    def test_walk_time(self):
+        """Trains that can't be caught are hidden, with when to leave for the next one."""
+        config = self.create_single_station_config()
+        config.walk_minutes = 6
+        config.routes[1].inbound = 0
+        now = datetime.fromisoformat('2025-07-06T10:00:00-04:00')
+        mode = SingleStationMode(config, clock=FixedClock(now))
+
+        responses = [
+            [prediction('2025-07-06T10:04:00-04:00'),
+             prediction('2025-07-06T10:10:30-04:00'),
+             prediction('2025-07-06T10:19:00-04:00'),
+             prediction('2025-07-06T10:27:00-04:00')],
+            [prediction('2025-07-06T10:12:00-04:00', schedule_relationship='CANCELLED'),
+             prediction('2025-07-06T10:20:00-04:00')],
+        ]
+        self.mock_ig.get_predictions_filtered.side_effect = responses
+
+        display = mode.format_for_display(mode.gather_data(self.mock_ig))
+        self.assertEqual([l.text for l in display.lines],
+                         ['Leave in 4 min: OL In 10:10 AM',
+                          'OL In: 10:10 AM, 10:19 AM',
+                          'OL Out: 10:12 AM CANCELLED'])
+        self.assertFalse(display.lines[0].urgent)
+        self.mock_ig.get_predictions_filtered.assert_any_call('place-ogmnl', '0', 'Orange', 8)
+
+        mode.clock = FixedClock(now + timedelta(minutes=4, seconds=10))
+        self.mock_ig.get_predictions_filtered.side_effect = responses
+        leave = mode.format_for_display(mode.gather_data(self.mock_ig)).lines[0]
+        self.assertEqual(leave.text, 'LEAVE NOW: OL In 10:10 AM')
+        self.assertTrue(leave.urgent)
+
+    def test_no_walk_time(self):
+        mode = SingleStationMode(self.create_single_station_config())
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [prediction('2025-07-06T10:04:00-04:00')], [], [],
+        ]
+        lines = [l.text for l in mode.format_for_display(mode.gather_data(self.mock_ig)).lines]
+        self.assertEqual(lines, ['OL In: 10:04 AM'])
+
+    def test_route_type_abbreviations(self):
+        mode = SingleStationMode(self.create_single_station_config())
+        cases = [
+            (('Route 1', 3), '1'),
+            (('Silver Line 4', 3), 'SL4'),
+            (('SL Waterfront', 3), 'SLW'),
+            (('Hingham/Hull Ferry', 4, 'Boat-F1'), 'F1'),
+            (('Charlestown Ferry', 4, 'Boat-F4'), 'F4'),
+            (('East Boston Ferry', 4, 'Boat-EastBoston'), 'Ferry'),
+            (('Lowell Line', 2), 'CR'),
+            (('Orange Line', 1), 'OL'),
+        ]
+        for args, expected in cases:
+            with self.subTest(args=args):
+                self.assertEqual(mode.abbreviate_route(*args), expected)
+
+    def test_countdown_rules(self):
+        """Countdown follows the MBTA sign rules."""
+        config = self.create_single_station_config()
+        config.display.time_format = 'countdown'
+        now = datetime.fromisoformat('2025-07-06T10:00:00-04:00')
+        mode = SingleStationMode(config, clock=FixedClock(now))
+
+        def at(seconds):
+            return datetime.fromisoformat('2025-07-06T10:00:00-04:00') + timedelta(seconds=seconds)
+
+        cases = [
+            ((at(20), at(60), None), 'ARR'),
+            ((at(45), at(80), None), '1 min'),
+            ((at(89), None, None), '1 min'),
+            ((at(90), None, None), '2 min'),
+            ((at(20 * 60 + 29), None, None), '20 min'),
+            ((at(20 * 60 + 30), None, None), '20+ min'),
+            ((None, at(300), None), '5 min'),               # First stop: departure only
+            ((at(-10), at(40), None), 'BRD'),               # Arrived, not yet departed
+            ((at(240), at(300), 'Boarding'), 'BRD'),
+        ]
+        for args, expected in cases:
+            self.assertEqual(mode.format_countdown(*args), expected, args)
+
+    def test_countdown_single_station(self):
+        """Countdown display uses arrival, departure and status."""
+        config = self.create_single_station_config()
+        config.display.time_format = 'countdown'
+        mode = SingleStationMode(config, clock=FixedClock(datetime.fromisoformat('2025-07-06T10:00:00-04:00')))
+
+        self.mock_ig.get_predictions_filtered.side_effect = [
+            [
+                prediction('2025-07-06T10:01:00-04:00', '2025-07-06T10:00:20-04:00'),
+                prediction('2025-07-06T10:08:00-04:00', '2025-07-06T10:07:00-04:00'),
+            ],
+            [prediction('2025-07-06T10:30:00-04:00', None, status=None)],
+            [prediction('2025-07-06T10:04:00-04:00', '2025-07-06T10:03:00-04:00', status='Boarding')],
+        ]
+
+        display_data = mode.format_for_display(mode.gather_data(self.mock_ig))
+        lines = [l.text for l in display_data.lines]
+        self.assertIn('OL In: ARR, 7 min', lines)
+        self.assertIn('OL Out: 20+ min', lines)
+        self.assertIn('CR In: BRD', lines)
+
+    def test_countdown_multi_station(self):
+        """Multi-station mode counts down to departure and shows the arrival time."""
+        config = self.create_multi_station_config()
+        config.display.time_format = 'countdown'
+        mode = MultiStationMode(config, clock=FixedClock(datetime.fromisoformat('2025-07-05T10:10:00-04:00')))
+
+        data = {
+            'route': 'Red Line',
+            'from_station': 'Central Square',
+            'to_station': 'Harvard Square',
+            'trips': [journey('trip-1', '2025-07-05T10:15:00-04:00', '2025-07-05T10:18:00-04:00',
+                              from_arrival='2025-07-05T10:14:00-04:00')],
+            'errors': []
+        }
+
+        trip_lines = [l.text for l in mode.format_for_display(data).lines if l.is_route]
+        self.assertEqual(trip_lines, ['depart 4 min → arrive 10:18 AM'])
+
 if __name__ == '__main__':
     unittest.main()
\ No newline at end of file
@@ -0,0 +1,200 @@
+"""Tests for the provider abstraction and GTFS-Realtime provider."""
+
+import tempfile
+import unittest
+import zipfile
+from datetime import datetime, timedelta
+from pathlib import Path
+from unittest.mock import MagicMock, patch
+
+from instantmbta.config_parser import Config, GTFSConfig, ProviderConfig
+from instantmbta.gtfs_realtime import GTFSRealtimeProvider, decode_feed
+from instantmbta.gtfs_static import GTFSStaticStore, service_day_origin
+from instantmbta.infogather import InfoGather
+from instantmbta.provider import TransitProvider, create_provider
+
+from tests.test_gtfs_static import FEED
+
+try:
+    from google.transit import gtfs_realtime_pb2
+except ImportError:
+    gtfs_realtime_pb2 = None
+
+
+def posix(dt):
+    return str(int(dt.timestamp()))
+
+
+class TestGTFSRealtimeProvider(unittest.TestCase):
+    def setUp(self):
+        self.temp_dir = tempfile.TemporaryDirectory()
+        temp_path = Path(self.temp_dir.name)
+        zip_path = temp_path / 'feed.zip'
+        with zipfile.ZipFile(zip_path, 'w') as zf:
+            for name, content in FEED.items():
+                zf.writestr(name, content)
+            zf.writestr('agency.txt', "agency_id,agency_name,agency_timezone\n1,MBTA,America/New_York\n")
+        self.static = GTFSStaticStore.open(temp_path / 'feed.sqlite', zip_path)
+        self.provider = GTFSRealtimeProvider('http://example.test/TripUpdates.pb', self.static)
+        self.provider.logger = MagicMock()
+        self.now = datetime.now(self.static.timezone)
+
+    def tearDown(self):
+        self.static.close()
+        self.temp_dir.cleanup()
+
+    def feed(self, *trip_updates):
+        return {'entity': [{'id': str(i), 'trip_update': tu} for i, tu in enumerate(trip_updates)]}
+
+    def test_is_a_transit_provider(self):
+        self.assertIsInstance(self.provider, TransitProvider)
+        self.assertIsInstance(InfoGather(), TransitProvider)
+
+    def test_predictions_use_static_headsign_and_direction(self):
+        feed = self.feed(
+            {
+                'trip': {'trip_id': 'wk-2', 'route_id': 'Orange'},
+                'stop_time_update': [
+                    {'stop_sequence': 1, 'stop_id': '70036',
+                     'departure': {'time': posix(self.now + timedelta(minutes=8))}},
+                ],
+            },
+            {
+                'trip': {'trip_id': 'wk-1', 'route_id': 'Orange'},
+                'stop_time_update': [
+                    {'stop_sequence': 1, 'stop_id': '70036',
+                     'departure': {'time': posix(self.now + timedelta(minutes=3)), 'uncertainty': 60}},
+                    {'stop_sequence': 2, 'stop_id': '70032',
+                     'arrival': {'time': posix(self.now + timedelta(minutes=8))}},
+                ],
+            },
+            {
+                # Northbound trip is filtered out by direction
+                'trip': {'trip_id': 'wk-north', 'route_id': 'Orange'},
+                'stop_time_update': [
+                    {'stop_sequence': 20, 'stop_id': '70036',
+                     'departure': {'time': posix(self.now + timedelta(minutes=1))}},
+                ],
+            },
+        )
+        with patch.object(self.provider, '_fetch_feed', return_value=feed):
+            predictions = self.provider.get_predictions_filtered('place-ogmnl', '0', 'Orange', 3)
+
+        self.assertEqual([p.trip_id for p in predictions], ['wk-1', 'wk-2'])
+        self.assertEqual(predictions[0].destination, 'Forest Hills')
+        self.assertEqual(predictions[0].direction_id, 0)
+        self.assertEqual(predictions[0].departure_uncertainty, 60)
+
+    def test_skipped_cancelled_and_departed(self):
+        feed = self.feed(
+            {
+                'trip': {'trip_id': 'wk-1', 'schedule_relationship': 'CANCELED'},
+                'stop_time_update': [
+                    {'stop_id': '70036', 'departure': {'time': posix(self.now + timedelta(minutes=3))}},
+                ],
+            },
+            {
+                'trip': {'trip_id': 'wk-2'},
+                'stop_time_update': [
+                    {'stop_id': '70036', 'schedule_relationship': 'SKIPPED'},
+                ],
+            },
+            {
+                'trip': {'trip_id': 'wk-late'},
+                'stop_time_update': [
+                    {'stop_id': '70036', 'departure': {'time': posix(self.now - timedelta(minutes=1))}},
+                ],
+            },
+        )
+        with patch.object(self.provider, '_fetch_feed', return_value=feed):
+            self.assertEqual(self.provider.get_predictions_filtered('place-ogmnl', '0'), [])
+
+    def test_delay_only_update_uses_static_schedule(self):
+        service_date = datetime(2025, 7, 7).date()
+        feed = self.feed({
+            'trip': {'trip_id': 'wk-1', 'start_date': '20250707'},
+            'stop_time_update': [{'stop_sequence': 1, 'departure': {'delay': 120}}],
+        })
+        info = self.provider._trip_info(feed['entity'][0]['trip_update'])
+        calls = self.provider._stop_calls(feed['entity'][0]['trip_update'], info)
+
+        self.assertEqual(calls[0]['stop_id'], '70036')
+        expected = service_day_origin(service_date, self.static.timezone) + timedelta(hours=10, minutes=17)
+        self.assertEqual(calls[0]['departure'].isoformat(), expected.isoformat())
+
+    def test_journey_trips(self):
+        feed = self.feed({
+            'trip': {'trip_id': 'wk-1', 'route_id': 'Orange'},
+            'stop_time_update': [
+                {'stop_sequence': 1, 'stop_id': '70036',
+                 'departure': {'time': posix(self.now + timedelta(minutes=3))}},
+                {'stop_sequence': 2, 'stop_id': '70032',
+                 'arrival': {'time': posix(self.now + timedelta(minutes=8))}},
+            ],
+        })
+        with patch.object(self.provider, '_fetch_feed', return_value=feed):
+            trips = self.provider.get_journey_trips('Orange', 'place-ogmnl', 'place-welln')
+            reverse = self.provider.get_journey_trips('Orange', 'place-welln', 'place-ogmnl')
+
+        self.assertEqual(len(trips), 1)
+        self.assertEqual(trips[0].trip_id, 'wk-1')
+        self.assertEqual(reverse, [])
+
+    def test_routes_at_stop(self):
+        routes = self.provider.get_routes_at_stop('place-ogmnl')
+        self.assertEqual([r.id for r in routes], ['Orange'])
+        self.assertEqual(routes[0].name, 'Orange Line')
+
+    def test_feed_errors_return_empty(self):
+        with patch.object(self.provider, '_fetch_feed', side_effect=Exception("timeout")):
+            self.assertEqual(self.provider.get_predictions_filtered('place-ogmnl', '0'), [])
+
+    def test_feed_is_cached_between_calls(self):
+        with patch('instantmbta.gtfs_realtime.requests.get') as mock_get, \
+                patch('instantmbta.gtfs_realtime.decode_feed', return_value={'entity': []}):
+            mock_get.return_value = MagicMock(content=b'')
+            self.provider.get_predictions_filtered('place-ogmnl', '0')
+            self.provider.get_predictions_filtered('place-ogmnl', '1')
+        self.assertEqual(mock_get.call_count, 1)
+
+
+@unittest.skipIf(gtfs_realtime_pb2 is None, "gtfs-realtime-bindings not installed")
+class TestDecodeFeed(unittest.TestCase):
+    def test_decode_protobuf(self):
+        message = gtfs_realtime_pb2.FeedMessage()
+        message.header.gtfs_realtime_version = '2.0'
+        entity = message.entity.add(id='1')
+        entity.trip_update.trip.trip_id = 'wk-1'
+        stu = entity.trip_update.stop_time_update.add(stop_id='70036', stop_sequence=1)
+        stu.departure.time = 1751897700
+
+        feed = decode_feed(message.SerializeToString())
+        trip_update = feed['entity'][0]['trip_update']
+        self.assertEqual(trip_update['trip']['trip_id'], 'wk-1')
+        self.assertEqual(trip_update['stop_time_update'][0]['departure']['time'], '1751897700')
+
+
+class TestCreateProvider(unittest.TestCase):
+    def test_default_is_mbta(self):
+        config = Config(mode='single-station')
+        self.assertIsInstance(create_provider(config), InfoGather)
+
+    def test_gtfs_rt_requires_static_feed(self):
+        config = Config(
+            mode='single-station',
+            provider=ProviderConfig(type='gtfs-rt', trip_updates_url='http://example.test/tu.pb'),
+            gtfs=GTFSConfig(zip_path='feed.zip'),
+        )
+        with self.assertRaises(ValueError):
+            create_provider(config)
+        provider = create_provider(config, gtfs_store=MagicMock())
+        self.assertIsInstance(provider, GTFSRealtimeProvider)
+
+    def test_unknown_provider(self):
+        config = Config(mode='single-station', provider=ProviderConfig(type='nope'))
+        with self.assertRaises(ValueError):
+            create_provider(config)
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,174 @@
+"""Tests for the GTFS static importer and offline schedule fallback."""
+
+import tempfile
+import unittest
+import zipfile
+from datetime import date, datetime
+from pathlib import Path
+from unittest.mock import MagicMock, patch
+
+from instantmbta.clock import FixedClock
+from instantmbta.gtfs_static import (
+    AGENCY_TIMEZONE,
+    GTFSStaticStore,
+    parse_gtfs_time,
+    service_day_origin,
+)
+from instantmbta.infogather import InfoGather
+from instantmbta.models import Prediction
+
+FEED = {
+    'stops.txt': (
+        "stop_id,stop_name,parent_station,location_type,platform_code\n"
+        "place-ogmnl,Oak Grove,,1,\n"
+        "70036,Oak Grove,place-ogmnl,0,\n"
+        "place-welln,Wellington,,1,\n"
+        "70032,Wellington,place-welln,0,\n"
+    ),
+    'routes.txt': (
+        "route_id,route_short_name,route_long_name,route_type\n"
+        "Orange,,Orange Line,1\n"
+    ),
+    'trips.txt': (
+        "route_id,service_id,trip_id,trip_headsign,trip_short_name,direction_id\n"
+        "Orange,weekday,wk-1,Forest Hills,,0\n"
+        "Orange,weekday,wk-2,Forest Hills,,0\n"
+        "Orange,weekday,wk-late,Forest Hills,,0\n"
+        "Orange,weekday,wk-north,Oak Grove,,1\n"
+        "Orange,weekend,we-1,Forest Hills,,0\n"
+    ),
+    'stop_times.txt': (
+        "trip_id,arrival_time,departure_time,stop_id,stop_sequence\n"
+        "wk-1,10:15:00,10:15:00,70036,1\n"
+        "wk-1,10:20:00,10:20:00,70032,2\n"
+        "wk-2,10:23:00,10:23:00,70036,1\n"
+        "wk-late,25:10:00,25:10:00,70036,1\n"
+        "wk-north,10:17:00,10:17:00,70036,20\n"
+        "we-1,10:30:00,10:30:00,70036,1\n"
+    ),
+    'calendar.txt': (
+        "service_id,monday,tuesday,wednesday,thursday,friday,saturday,sunday,start_date,end_date\n"
+        "weekday,1,1,1,1,1,0,0,20250101,20251231\n"
+        "weekend,0,0,0,0,0,1,1,20250101,20251231\n"
+    ),
+    'calendar_dates.txt': (
+        "service_id,date,exception_type\n"
+        "weekday,20250704,2\n"
+        "weekend,20250704,1\n"
+    ),
+}
+
+
+def local(*args):
+    return datetime(*args, tzinfo=AGENCY_TIMEZONE)
+
+
+class TestGTFSStaticStore(unittest.TestCase):
+    def setUp(self):
+        self.temp_dir = tempfile.TemporaryDirectory()
+        self.temp_path = Path(self.temp_dir.name)
+        self.zip_path = self.temp_path / 'MBTA_GTFS.zip'
+        with zipfile.ZipFile(self.zip_path, 'w') as zf:
+            for name, content in FEED.items():
+                zf.writestr(name, content)
+        self.store = GTFSStaticStore.open(self.temp_path / 'gtfs.sqlite', self.zip_path)
+
+    def tearDown(self):
+        self.store.close()
+        self.temp_dir.cleanup()
+
+    def test_parse_gtfs_time(self):
+        self.assertEqual(parse_gtfs_time('10:15:00'), 36900)
+        self.assertEqual(parse_gtfs_time('25:10:00'), 90600)
+        self.assertIsNone(parse_gtfs_time(''))
+
+    def test_service_day_origin_dst(self):
+        # On the spring-forward day noon minus 12h is 11pm the night before
+        origin = service_day_origin(date(2025, 3, 9))
+        self.assertEqual(origin.isoformat(), '2025-03-08T23:00:00-05:00')
+        self.assertEqual(service_day_origin(date(2025, 7, 7)).isoformat(), '2025-07-07T00:00:00-04:00')
+
+    def test_import(self):
+        self.assertFalse(self.store.is_empty())
+        self.assertEqual(self.store.stop_name('place-ogmnl'), 'Oak Grove')
+        self.assertEqual(self.store.stop_ids_for('place-ogmnl'), ['place-ogmnl', '70036'])
+
+    def test_active_services(self):
+        self.assertEqual(self.store.active_services(date(2025, 7, 7)), {'weekday'})  # Monday
+        self.assertEqual(self.store.active_services(date(2025, 7, 5)), {'weekend'})  # Saturday
+        # July 4th (Friday) runs the weekend schedule
+        self.assertEqual(self.store.active_services(date(2025, 7, 4)), {'weekend'})
+
+    def test_scheduled_departures(self):
+        departures = self.store.scheduled_departures(
+            'place-ogmnl', '0', 'Orange', 2, now=local(2025, 7, 7, 10, 0))
+
+        self.assertEqual([d.trip_id for d in departures], ['wk-1', 'wk-2'])
+        self.assertEqual(departures[0].departure_time.isoformat(), '2025-07-07T10:15:00-04:00')
+        self.assertEqual(departures[0].destination, 'Forest Hills')
+        self.assertTrue(departures[0].scheduled)
+
+    def test_scheduled_departures_direction_and_route(self):
+        departures = self.store.scheduled_departures(
+            'place-ogmnl', '1', None, 3, now=local(2025, 7, 7, 10, 0))
+        self.assertEqual([d.trip_id for d in departures], ['wk-north'])
+
+        departures = self.store.scheduled_departures(
+            'place-ogmnl', '0', 'Red', 3, now=local(2025, 7, 7, 10, 0))
+        self.assertEqual(departures, [])
+
+    def test_post_midnight_trip_from_previous_service_day(self):
+        # 25:10 on Monday's service is 1:10am Tuesday
+        departures = self.store.scheduled_departures(
+            'place-ogmnl', '0', 'Orange', 3, now=local(2025, 7, 8, 0, 30))
+        self.assertEqual(departures[0].trip_id, 'wk-late')
+        self.assertEqual(departures[0].departure_time.isoformat(), '2025-07-08T01:10:00-04:00')
+
+    def test_reopen_skips_import_when_current(self):
+        self.store.close()
+        with patch.object(GTFSStaticStore, 'import_zip') as mock_import:
+            self.store = GTFSStaticStore.open(self.temp_path / 'gtfs.sqlite', self.zip_path)
+        mock_import.assert_not_called()
+
+
+class TestInfoGatherScheduleFallback(unittest.TestCase):
+    def setUp(self):
+        self.gtfs_store = MagicMock()
+        self.gtfs_store.scheduled_departures.return_value = [
+            Prediction('schedule-1', departure_time=local(2025, 7, 7, 10, 15), scheduled=True)
+        ]
+        self.clock = FixedClock(local(2025, 7, 7, 10, 0))
+        self.ig = InfoGather(gtfs_store=self.gtfs_store, clock=self.clock)
+        self.ig.logger = MagicMock()
+
+    def test_fallback_on_request_failure(self):
+        with patch.object(self.ig, '_make_api_request', side_effect=Exception("Circuit breaker is OPEN")):
+            predictions = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 2)
+
+        self.assertEqual(len(predictions), 1)
+        self.assertTrue(predictions[0].scheduled)
+        self.gtfs_store.scheduled_departures.assert_called_once_with(
+            'place-ogmnl', '0', 'Orange', 2, now=self.clock.now())
+
+    def test_fallback_on_error_status(self):
+        with patch.object(self.ig, '_make_api_request', return_value=MagicMock(status_code=503)):
+            predictions = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 2)
+        self.assertTrue(predictions[0].scheduled)
+
+    def test_no_fallback_when_api_succeeds(self):
+        response = MagicMock(status_code=200)
+        response.json.return_value = {'data': []}
+        with patch.object(self.ig, '_make_api_request', return_value=response):
+            predictions = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 2)
+        self.assertEqual(predictions, [])
+        self.gtfs_store.scheduled_departures.assert_not_called()
+
+    def test_no_store_returns_empty(self):
+        ig = InfoGather()
+        ig.logger = MagicMock()
+        with patch.object(ig, '_make_api_request', side_effect=Exception("down")):
+            self.assertEqual(ig.get_predictions_filtered('place-ogmnl', '0'), [])
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,94 @@
+"""Tests for API response caching."""
+
+import tempfile
+import unittest
+from datetime import datetime
+from pathlib import Path
+
+from instantmbta.clock import FixedClock
+from instantmbta.http_cache import MAX_MEMORY_ENTRIES, ResponseCache, build_response
+
+LAST_MODIFIED = 'Mon, 07 Jul 2025 13:59:00 GMT'
+PREDICTIONS = 'https://api-v3.mbta.com/predictions?filter[stop]=place-ogmnl'
+ROUTES = 'https://api-v3.mbta.com/routes?filter[stop]=place-ogmnl'
+
+
+class TestResponseCache(unittest.TestCase):
+    def setUp(self):
+        self.clock = FixedClock(datetime(2025, 7, 7, 10, 0))
+        self.temp_dir = tempfile.TemporaryDirectory()
+        self.path = Path(self.temp_dir.name) / 'http_cache.json'
+
+    def tearDown(self):
+        self.temp_dir.cleanup()
+
+    def test_not_modified_uses_cached_body(self):
+        cache = ResponseCache(clock=self.clock)
+        self.assertEqual(cache.conditional_headers(PREDICTIONS), {})
+
+        cache.update(PREDICTIONS, build_response(PREDICTIONS, 200, {'Last-Modified': LAST_MODIFIED}, '{"data": [1]}'))
+        self.assertEqual(cache.conditional_headers(PREDICTIONS), {'If-Modified-Since': LAST_MODIFIED})
+
+        not_modified = build_response(PREDICTIONS, 304, {'x-ratelimit-remaining': '990'}, '')
+        response = cache.update(PREDICTIONS, not_modified)
+        self.assertEqual(response.status_code, 200)
+        self.assertEqual(response.json(), {'data': [1]})
+        self.assertEqual(response.headers['x-ratelimit-remaining'], '990')
+
+    def test_without_last_modified_nothing_is_kept(self):
+        cache = ResponseCache(clock=self.clock)
+        cache.update(PREDICTIONS, build_response(PREDICTIONS, 200, {}, '{}'))
+        self.assertEqual(cache.conditional_headers(PREDICTIONS), {})
+        # Realtime data never comes from the disk cache
+        self.assertIsNone(ResponseCache(self.path, clock=self.clock).fresh(PREDICTIONS))
+
+    def test_errors_pass_through(self):
+        cache = ResponseCache(clock=self.clock)
+        error = build_response(PREDICTIONS, 503, {'Last-Modified': LAST_MODIFIED}, '')
+        self.assertIs(cache.update(PREDICTIONS, error), error)
+        self.assertEqual(cache.conditional_headers(PREDICTIONS), {})
+
+    def test_disk_cache_survives_restart(self):
+        cache = ResponseCache(self.path, clock=self.clock)
+        self.assertIsNone(cache.fresh(ROUTES))
+        cache.update(ROUTES, build_response(ROUTES, 200, {}, '{"data": ["Orange"]}'))
+
+        restarted = ResponseCache(self.path, clock=self.clock)
+        self.assertEqual(restarted.fresh(ROUTES).json(), {'data': ['Orange']})
+
+        self.clock.advance(25 * 60 * 60)
+        self.assertIsNone(restarted.fresh(ROUTES))
+
+    def test_unchanged_response_not_rewritten(self):
+        cache = ResponseCache(self.path, clock=self.clock)
+        cache.update(ROUTES, build_response(ROUTES, 200, {'Last-Modified': LAST_MODIFIED}, '{"data": ["Orange"]}'))
+        self.path.write_text('{"version": 1, "entries": {}}')  # Would be visible if rewritten
+
+        self.clock.advance(25 * 60 * 60)
+        cache.update(ROUTES, build_response(ROUTES, 304, {}, ''))
+        cache.update(ROUTES, build_response(ROUTES, 200, {'Last-Modified': LAST_MODIFIED}, '{"data": ["Orange"]}'))
+        self.assertEqual(self.path.read_text(), '{"version": 1, "entries": {}}')
+
+        cache.update(ROUTES, build_response(ROUTES, 200, {}, '{"data": ["Red"]}'))
+        self.assertEqual(ResponseCache(self.path, clock=self.clock).fresh(ROUTES).json(), {'data': ['Red']})
+
+    def test_memory_only_without_path(self):
+        cache = ResponseCache(clock=self.clock)
+        cache.update(ROUTES, build_response(ROUTES, 200, {}, '{}'))
+        self.assertIsNone(cache.fresh(ROUTES))
+
+    def test_memory_is_bounded(self):
+        cache = ResponseCache(clock=self.clock)
+        for i in range(MAX_MEMORY_ENTRIES + 1):
+            url = f'{PREDICTIONS}&filter[min_time]={i}'
+            cache.update(url, build_response(url, 200, {'Last-Modified': LAST_MODIFIED}, '{}'))
+        self.assertEqual(cache.conditional_headers(f'{PREDICTIONS}&filter[min_time]=0'), {})
+        self.assertNotEqual(cache.conditional_headers(f'{PREDICTIONS}&filter[min_time]=1'), {})
+
+    def test_unreadable_disk_cache(self):
+        self.path.write_text('not json')
+        self.assertIsNone(ResponseCache(self.path, clock=self.clock).fresh(ROUTES))
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -1,10 +1,19 @@
 import unittest
+import copy
 from unittest.mock import patch, MagicMock
 import requests
 from instantmbta.infogather import InfoGather, CircuitBreaker
 import time
 from datetime import datetime, timedelta
 
+import tempfile
+from pathlib import Path
+
+from instantmbta.clock import FixedClock
+from instantmbta.config_parser import Config
+from instantmbta.display_modes import MultiStationMode
+from tests.fake_mbta import FakeMBTAServer
+
 class TestInfoGather(unittest.TestCase):
     def setUp(self):
         """Set up test fixtures before each test method."""
@@ -14,7 +23,7 @@ class TestInfoGather(unittest.TestCase):
 
     def test_verify_connection_success(self):
         """Test successful connection verification."""
-        with patch('requests.get') as mock_get:
+        with patch.object(self.ig.session, 'get') as mock_get:
             mock_response = MagicMock()
             mock_response.raise_for_status.return_value = None
             mock_get.return_value = mock_response
@@ -27,7 +36,7 @@ class TestInfoGather(unittest.TestCase):
 
     def test_verify_connection_failure(self):
         """Test failed connection verification."""
-        with patch('requests.get') as mock_get:
+        with patch.object(self.ig.session, 'get') as mock_get:
             mock_get.side_effect = requests.exceptions.RequestException("Connection error")
 
             result = self.ig.verify_connection()
@@ -38,28 +47,27 @@ class TestInfoGather(unittest.TestCase):
 
     def test_make_api_request_with_retries(self):
         """Test API request with retry logic."""
-        with patch('requests.get') as mock_get:
+        with patch.object(self.ig.session, 'get') as mock_get, patch('time.sleep') as mock_sleep:
             # Simulate two failures followed by success
             mock_get.side_effect = [
                 requests.exceptions.RequestException("First failure"),
                 requests.exceptions.RequestException("Second failure"),
-                MagicMock()
+                MagicMock(status_code=200, headers={})
             ]
 
-            # Mock verify_connection to simulate network issues
             with patch.object(self.ig, 'verify_connection') as mock_verify:
-                # First two attempts fail, then always succeed
-                mock_verify.side_effect = [False, False, True, True, True, True]
-                
                 # Should succeed on third attempt
                 result = self.ig._make_api_request("test_url")
                 
-                # Verify the number of attempts
-                self.assertEqual(mock_get.call_count, 3)
-                self.assertGreaterEqual(mock_verify.call_count, 3)
-                
-                # Verify the result
-                self.assertIsNotNone(result)
+                # No extra connectivity check per request
+                mock_verify.assert_not_called()
+            
+            # Verify the number of attempts and the exponential backoff
+            self.assertEqual(mock_get.call_count, 3)
+            self.assertEqual([c.args[0] for c in mock_sleep.call_args_list], [5, 10])
+            
+            # Verify the result
+            self.assertIsNotNone(result)
 
     def test_circuit_breaker(self):
         """Test circuit breaker functionality."""
@@ -110,6 +118,7 @@ class TestInfoGather(unittest.TestCase):
             mock_response.json.return_value = {
                 'data': [
                     {
+                        'type': 'prediction',
                         'id': f'prediction-{i}',
                         'attributes': {
                             'departure_time': f'2025-07-06T10:{15+i}:00-04:00',
@@ -118,8 +127,8 @@ class TestInfoGather(unittest.TestCase):
                             'departure_uncertainty': 120
                         },
                         'relationships': {
-                            'route': {'data': {'id': 'Orange'}},
-                            'trip': {'data': {'id': f'trip-{i}'}}
+                            'route': {'data': {'type': 'route', 'id': 'Orange'}},
+                            'trip': {'data': {'type': 'trip', 'id': f'trip-{i}'}}
                         }
                     } for i in range(10)  # 10 predictions
                 ]
@@ -131,8 +140,8 @@ class TestInfoGather(unittest.TestCase):
             
             # Should return only 3
             self.assertEqual(len(predictions), 3)
-            self.assertEqual(predictions[0]['id'], 'prediction-0')
-            self.assertEqual(predictions[2]['id'], 'prediction-2')
+            self.assertEqual(predictions[0].id, 'prediction-0')
+            self.assertEqual(predictions[2].id, 'prediction-2')
 
     def test_get_predictions_filtered_missing_times(self):
         """Test handling predictions with missing departure/arrival times."""
@@ -142,36 +151,40 @@ class TestInfoGather(unittest.TestCase):
             mock_response.json.return_value = {
                 'data': [
                     {
+                        'type': 'prediction',
                         'id': 'pred-1',
                         'attributes': {
                             'departure_time': '2025-07-06T10:15:00-04:00',
                             'arrival_time': None
                         },
-                        'relationships': {'route': {'data': {'id': 'Orange'}}}
+                        'relationships': {'route': {'data': {'type': 'route', 'id': 'Orange'}}}
                     },
                     {
+                        'type': 'prediction',
                         'id': 'pred-2',
                         'attributes': {
                             'departure_time': None,
                             'arrival_time': '2025-07-06T10:20:00-04:00'
                         },
-                        'relationships': {'route': {'data': {'id': 'Orange'}}}
+                        'relationships': {'route': {'data': {'type': 'route', 'id': 'Orange'}}}
                     },
                     {
+                        'type': 'prediction',
                         'id': 'pred-3',
                         'attributes': {
                             'departure_time': None,
                             'arrival_time': None  # No times at all
                         },
-                        'relationships': {'route': {'data': {'id': 'Orange'}}}
+                        'relationships': {'route': {'data': {'type': 'route', 'id': 'Orange'}}}
                     },
                     {
+                        'type': 'prediction',
                         'id': 'pred-4',
                         'attributes': {
                             'departure_time': '2025-07-06T10:25:00-04:00',
                             'arrival_time': '2025-07-06T10:24:00-04:00'
                         },
-                        'relationships': {'route': {'data': {'id': 'Orange'}}}
+                        'relationships': {'route': {'data': {'type': 'route', 'id': 'Orange'}}}
                     }
                 ]
             }
@@ -181,9 +194,12 @@ class TestInfoGather(unittest.TestCase):
             
             # Should have 3 predictions (skipping the one with no times)
             self.assertEqual(len(predictions), 3)
-            self.assertEqual(predictions[0]['departure_time'], '2025-07-06T10:15:00-04:00')
-            self.assertEqual(predictions[1]['departure_time'], '2025-07-06T10:20:00-04:00')
-            self.assertEqual(predictions[2]['departure_time'], '2025-07-06T10:25:00-04:00')
+            self.assertEqual([p.time.isoformat() for p in predictions], [
+                '2025-07-06T10:15:00-04:00',
+                '2025-07-06T10:20:00-04:00',
+                '2025-07-06T10:25:00-04:00',
+            ])
+            self.assertIsNone(predictions[1].departure_time)
 
     def test_get_predictions_filtered_with_destinations(self):
         """Test extraction of destination information from included trips."""
@@ -193,19 +209,21 @@ class TestInfoGather(unittest.TestCase):
             mock_response.json.return_value = {
                 'data': [
                     {
+                        'type': 'prediction',
                         'id': 'pred-1',
                         'attributes': {'departure_time': '2025-07-06T10:15:00-04:00'},
                         'relationships': {
-                            'route': {'data': {'id': 'Orange'}},
-                            'trip': {'data': {'id': 'trip-forest-hills'}}
+                            'route': {'data': {'type': 'route', 'id': 'Orange'}},
+                            'trip': {'data': {'type': 'trip', 'id': 'trip-forest-hills'}}
                         }
                     },
                     {
+                        'type': 'prediction',
                         'id': 'pred-2',
                         'attributes': {'departure_time': '2025-07-06T10:20:00-04:00'},
                         'relationships': {
-                            'route': {'data': {'id': 'Orange'}},
-                            'trip': {'data': {'id': 'trip-oak-grove'}}
+                            'route': {'data': {'type': 'route', 'id': 'Orange'}},
+                            'trip': {'data': {'type': 'trip', 'id': 'trip-oak-grove'}}
                         }
                     }
                 ],
@@ -232,8 +250,8 @@ class TestInfoGather(unittest.TestCase):
             predictions = self.ig.get_predictions_filtered('place-ogmnl', '0')
             
             self.assertEqual(len(predictions), 2)
-            self.assertEqual(predictions[0]['destination'], 'Forest Hills')
-            self.assertEqual(predictions[1]['destination'], 'Oak Grove')
+            self.assertEqual(predictions[0].destination, 'Forest Hills')
+            self.assertEqual(predictions[1].destination, 'Oak Grove')
 
     def test_get_routes_at_stop_various_types(self):
         """Test route retrieval with various route types."""
@@ -243,6 +261,7 @@ class TestInfoGather(unittest.TestCase):
             mock_response.json.return_value = {
                 'data': [
                     {
+                        'type': 'route',
                         'id': 'Orange',
                         'attributes': {
                             'long_name': 'Orange Line',
@@ -253,6 +272,7 @@ class TestInfoGather(unittest.TestCase):
                         }
                     },
                     {
+                        'type': 'route',
                         'id': '39',
                         'attributes': {
                             'long_name': 'Forest Hills - Back Bay Station',
@@ -263,6 +283,7 @@ class TestInfoGather(unittest.TestCase):
                         }
                     },
                     {
+                        'type': 'route',
                         'id': 'CR-Providence',
                         'attributes': {
                             'long_name': 'Providence/Stoughton Line',
@@ -281,17 +302,18 @@ class TestInfoGather(unittest.TestCase):
             self.assertEqual(len(routes), 3)
             
             # Check route types
-            orange = next(r for r in routes if r['id'] == 'Orange')
-            self.assertEqual(orange['type'], 1)
-            self.assertEqual(orange['short_name'], 'OL')
+            orange = next(r for r in routes if r.id == 'Orange')
+            self.assertEqual(orange.type, 1)
+            self.assertEqual(orange.short_name, 'OL')
+            self.assertEqual(orange.direction_destinations, ['Forest Hills', 'Oak Grove'])
             
-            bus = next(r for r in routes if r['id'] == '39')
-            self.assertEqual(bus['type'], 3)
-            self.assertEqual(bus['name'], 'Forest Hills - Back Bay Station')
+            bus = next(r for r in routes if r.id == '39')
+            self.assertEqual(bus.type, 3)
+            self.assertEqual(bus.name, 'Forest Hills - Back Bay Station')
             
-            cr = next(r for r in routes if r['id'] == 'CR-Providence')
-            self.assertEqual(cr['type'], 2)
-            self.assertIsNone(cr['short_name'])
+            cr = next(r for r in routes if r.id == 'CR-Providence')
+            self.assertEqual(cr.type, 2)
+            self.assertIsNone(cr.short_name)
 
     def test_api_request_url_construction(self):
         """Test that API URLs are constructed correctly."""
@@ -316,57 +338,358 @@ class TestInfoGather(unittest.TestCase):
             self.assertNotIn('filter[route]', call_url)
             self.assertIn('page[limit]=4', call_url)  # 2 * 2
 
-    def test_get_current_schedule_edge_cases(self):
-        """Test edge cases in get_current_schedule method."""
-        with patch.object(self.ig, '_make_api_request') as mock_request:
-            # Mock response with edge cases
-            mock_response = MagicMock()
-            mock_response.status_code = 200
-            
-            # Test 1: All times in the past
-            current_time = datetime.now().astimezone()
-            past_time = current_time - timedelta(hours=1)
-            
-            mock_response.json.return_value = {
-                'data': [
-                    {
-                        'attributes': {
-                            'departure_time': past_time.isoformat(),
-                            'arrival_time': past_time.isoformat(),
-                            'direction_id': 0
-                        }
-                    }
-                ]
+    def _stop_time(self, trip_id, stop_id, sequence, time, direction_id=1, resource_type='schedule'):
+        """Build a schedule/prediction resource for get_journey_trips tests."""
+        return {
+            'type': resource_type,
+            'id': f'{resource_type}-{trip_id}-{sequence}',
+            'attributes': {
+                'arrival_time': time.isoformat(),
+                'departure_time': time.isoformat(),
+                'stop_sequence': sequence,
+                'direction_id': direction_id
+            },
+            'relationships': {
+                'trip': {'data': {'type': 'trip', 'id': trip_id}},
+                'stop': {'data': {'type': 'stop', 'id': stop_id}}
             }
-            mock_request.return_value = mock_response
-            
-            result = self.ig.get_current_schedule('Orange', 'place-ogmnl')
-            # Should return None for all values if no future times
-            self.assertEqual(result, (None, None, None, None))
-            
-            # Test 2: Mixed past and future times
-            future_time = current_time + timedelta(minutes=10)
-            mock_response.json.return_value = {
-                'data': [
-                    {
-                        'attributes': {
-                            'departure_time': past_time.isoformat(),
-                            'direction_id': 0
-                        }
-                    },
-                    {
-                        'attributes': {
-                            'departure_time': future_time.isoformat(),
-                            'direction_id': 0
-                        }
-                    }
-                ]
-            }
-            
-            result = self.ig.get_current_schedule('Orange', 'place-ogmnl')
-            # Should only return future times
-            self.assertIsNotNone(result[2])  # inbound departure
-            self.assertIn(future_time.strftime('%H:%M'), result[2])
+        }
+
+    def test_get_journey_trips_matches_same_trip(self):
+        """Departure and arrival must come from the same trip, in stop order."""
+        now = datetime.now().astimezone()
+        included = [
+            {'type': 'stop', 'id': '70069',
+             'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-cntsq'}}}},
+            {'type': 'stop', 'id': '70067',
+             'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-harsq'}}}},
+            {'type': 'stop', 'id': '70070',
+             'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-cntsq'}}}},
+            {'type': 'stop', 'id': '70068',
+             'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-harsq'}}}},
+        ]
+        schedules = MagicMock(status_code=200)
+        schedules.json.return_value = {
+            'data': [
+                # Northbound trip: Central then Harvard
+                self._stop_time('north-1', '70069', 10, now + timedelta(minutes=5)),
+                self._stop_time('north-1', '70067', 11, now + timedelta(minutes=8)),
+                # Southbound trip reaches Harvard before Central
+                self._stop_time('south-1', '70068', 5, now + timedelta(minutes=2), 0),
+                self._stop_time('south-1', '70070', 6, now + timedelta(minutes=4), 0),
+                # Second northbound trip only known at Central so far
+                self._stop_time('north-2', '70069', 10, now + timedelta(minutes=12)),
+            ],
+            'included': included
+        }
+        predictions = MagicMock(status_code=200)
+        predictions.json.return_value = {
+            'data': [
+                # Running late: prediction replaces the scheduled arrival
+                self._stop_time('north-1', '70067', 11, now + timedelta(minutes=10),
+                                resource_type='prediction'),
+            ],
+            'included': included
+        }
+
+        with patch.object(self.ig, '_make_api_request') as mock_request:
+            mock_request.side_effect = [schedules, predictions]
+            trips = self.ig.get_journey_trips('Red', 'place-cntsq', 'place-harsq', 3)
+
+        self.assertEqual(len(trips), 1)
+        self.assertEqual(trips[0].trip_id, 'north-1')
+        self.assertEqual(trips[0].departure_time, now + timedelta(minutes=5))
+        self.assertEqual(trips[0].arrival_time, now + timedelta(minutes=10))
+        self.assertTrue(trips[0].predicted)
+
+        urls = [call[0][0] for call in mock_request.call_args_list]
+        self.assertIn('filter[stop]=place-cntsq,place-harsq', urls[0])
+        self.assertIn('/schedules?', urls[0])
+        self.assertIn('/predictions?', urls[1])
+
+    def test_get_journey_trips_sorted_and_limited(self):
+        """Trips are returned in departure order, trimmed to count."""
+        now = datetime.now().astimezone()
+        schedules = MagicMock(status_code=200)
+        schedules.json.return_value = {
+            'data': [
+                self._stop_time(f'trip-{i}', stop_id, seq, now + timedelta(minutes=offset + 10 * (3 - i)))
+                for i in range(3)
+                for stop_id, seq, offset in (('place-cntsq', 1, 0), ('place-harsq', 2, 3))
+            ]
+        }
+        predictions = MagicMock(status_code=200)
+        predictions.json.return_value = {'data': []}
+
+        with patch.object(self.ig, '_make_api_request') as mock_request:
+            mock_request.side_effect = [schedules, predictions]
+            trips = self.ig.get_journey_trips('Red', 'place-cntsq', 'place-harsq', 2)
+
+        self.assertEqual([t.trip_id for t in trips], ['trip-2', 'trip-1'])
+        self.assertFalse(trips[0].predicted)
+
+    def test_get_journey_trips_skips_cancelled(self):
+        """A trip cancelled at either stop is not offered as a journey."""
+        now = datetime.now().astimezone()
+        schedules = MagicMock(status_code=200)
+        schedules.json.return_value = {
+            'data': [
+                self._stop_time('trip-1', 'place-cntsq', 1, now + timedelta(minutes=5)),
+                self._stop_time('trip-1', 'place-harsq', 2, now + timedelta(minutes=8)),
+            ]
+        }
+        cancelled = self._stop_time('trip-1', 'place-harsq', 2, now + timedelta(minutes=8),
+                                    resource_type='prediction')
+        cancelled['attributes'].update({
+            'arrival_time': None,
+            'departure_time': None,
+            'schedule_relationship': 'CANCELLED'
+        })
+        predictions = MagicMock(status_code=200)
+        predictions.json.return_value = {'data': [cancelled]}
+
+        with patch.object(self.ig, '_make_api_request') as mock_request:
+            mock_request.side_effect = [schedules, predictions]
+            trips = self.ig.get_journey_trips('Red', 'place-cntsq', 'place-harsq')
+
+        self.assertEqual(trips, [])
+
+
+class TestInfoGatherFaults(unittest.TestCase):
+    """Error handling against the fake API with injected failures."""
+
+    @classmethod
+    def setUpClass(cls):
+        cls.server = FakeMBTAServer('basic').start()
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.server.stop()
+
+    def setUp(self):
+        self.server.faults.clear()
+        self.server.requests.clear()
+        self.server.rate_limit = None
+        self.server.last_modified = None
+        self.server.page_size = None
+        self.ig = InfoGather(api_url=self.server.url, timeout=0.5,
+                             clock=FixedClock(self.server.now))
+        self.ig.base_retry_delay = 0
+        self.ig.logger = MagicMock()
+
+    def test_configurable_api_url(self):
+        preds = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 1)
+        self.assertEqual([p.trip_id for p in preds], ['OL-S-1'])
+        self.assertEqual(len(self.server.requests_to('/predictions')), 1)
+
+    def test_api_key_header(self):
+        ig = InfoGather(api_url=self.server.url, api_key='sekrit', clock=FixedClock(self.server.now))
+        ig.get_alerts(['Orange'], ['place-ogmnl'])
+
+        path, params, headers = self.server.requests[-1]
+        self.assertEqual(path, '/alerts')
+        self.assertEqual(headers.get('x-api-key'), 'sekrit')
+        self.assertNotIn('api_key', params)
+
+        self.ig.get_alerts(['Orange'], ['place-ogmnl'])
+        self.assertNotIn('x-api-key', self.server.requests[-1][2])
+
+    def test_not_modified(self):
+        self.server.last_modified = 'Mon, 07 Jul 2025 13:59:00 GMT'
+        first = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 1)
+        second = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 1)
+
+        self.assertEqual(second, first)
+        headers = [h for p, _, h in self.server.requests if p == '/predictions']
+        self.assertNotIn('If-Modified-Since', headers[0])
+        self.assertEqual(headers[1]['If-Modified-Since'], self.server.last_modified)
+
+    def test_routes_cached_across_restarts(self):
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            cache_path = Path(tmp_dir) / 'http_cache.json'
+            for _ in range(2):
+                ig = InfoGather(api_url=self.server.url, clock=FixedClock(self.server.now),
+                                cache_path=cache_path)
+                routes = ig.get_routes_at_stop('place-ogmnl')
+                self.assertEqual([r.id for r in routes], ['Orange'])
+
+        self.assertEqual(len(self.server.requests_to('/routes')), 1)
+
+    def test_follows_pagination(self):
+        self.server.page_size = 2
+        stops = self.ig.get_all_stops()
+
+        self.server.page_size = None
+        self.assertEqual(stops, self.ig.get_all_stops())
+        self.assertGreater(len(stops), 2)
+        self.assertEqual(len(self.server.requests_to('/stops')), (len(stops) + 1) // 2 + 1)
+
+    def test_included_resources_resolved(self):
+        trips = self.ig.get_journey_trips('Red', 'place-cntsq', 'place-harsq', 1)
+        self.assertEqual(trips[0].destination, 'Alewife')
+
+        schedules = self.ig.get_schedule('Red', 'place-cntsq', '1')
+        self.assertEqual(schedules[0].stop.parent_station.id, 'place-cntsq')
+        # Not included in the request, so only the id is known
+        self.assertEqual(schedules[0].trip.id, 'RL-N-1')
+        self.assertIsNone(schedules[0].trip.headsign)
+
+    def test_locate_vehicles(self):
+        predictions = self.ig.get_predictions_filtered('place-ogmnl', '1', 'Orange', 2)
+        predictions += self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 1)
+        self.ig.locate_vehicles(predictions)
+
+        in_transit, not_started, boarding = predictions
+        self.assertEqual(in_transit.vehicle.current_status, 'IN_TRANSIT_TO')
+        self.assertEqual(in_transit.vehicle.stop.name, 'Malden Center')
+        # Sequences 19 → 20, one stop
+        self.assertEqual(in_transit.stops_away, 1)
+        self.assertIsNone(not_started.vehicle)
+        self.assertIsNone(not_started.stops_away)
+        self.assertEqual(boarding.vehicle.current_status, 'STOPPED_AT')
+        self.assertEqual(boarding.stops_away, 0)
+
+        # A trip's stops are only fetched once
+        self.ig.locate_vehicles(predictions)
+        self.assertEqual(len(self.server.requests_to('/schedules')), 1)
+        self.assertEqual(len(self.server.requests_to('/vehicles')), 2)
+
+    def test_vehicle_occupancy(self):
+        predictions = self.ig.get_predictions_filtered('place-ogmnl', '1', 'Orange', 1)
+        predictions += self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 1)
+        predictions += self.ig.get_predictions_filtered('place-harsq', '1', 'Red', 1)
+        full, some_seats, few_seats = self.ig.locate_vehicles(predictions, count_stops=False)
+
+        self.assertEqual([c.occupancy_status for c in some_seats.vehicle.carriages],
+                         ['MANY_SEATS_AVAILABLE', 'FEW_SEATS_AVAILABLE', 'STANDING_ROOM_ONLY'])
+        # The emptiest car counts
+        self.assertEqual(some_seats.vehicle.crowding, 1)
+        self.assertFalse(some_seats.vehicle.full)
+        self.assertEqual(full.vehicle.crowding, 3)
+        self.assertTrue(full.vehicle.full)
+        self.assertEqual(few_seats.vehicle.crowding, 2)
+        self.assertIsNone(full.stops_away)
+        self.assertEqual(self.server.requests_to('/schedules'), [])
+
+    def test_locate_vehicles_counts_stops_not_sequences(self):
+        predictions = self.ig.get_predictions_filtered('place-ogmnl', '1', 'Orange', 1)
+        # Back at North Station, sequence 15; Malden Center (19) is next
+        key = ('vehicle', 'O-54861D')
+        vehicle = copy.deepcopy(self.server.resources[key])
+        vehicle['attributes'].update(current_status='STOPPED_AT', current_stop_sequence=15)
+        vehicle['relationships']['stop']['data']['id'] = '70027'
+        self.addCleanup(self.server.resources.__setitem__, key, self.server.resources[key])
+        self.server.resources[key] = vehicle
+
+        prediction, = self.ig.locate_vehicles(predictions)
+        self.assertEqual(prediction.stops_away, 2)
+        self.assertEqual(prediction.vehicle.stop.name, 'North Station')
+
+    def test_locate_vehicles_unavailable(self):
+        predictions = self.ig.get_predictions_filtered('place-ogmnl', '1', 'Orange', 1)
+        self.server.inject('/vehicles', status=503)
+
+        prediction, = self.ig.locate_vehicles(predictions)
+        self.assertIsNone(prediction.stops_away)
+        # Only the id from the prediction's relationship
+        self.assertIsNone(prediction.vehicle.current_status)
+
+    def test_cancelled_trip_at_scheduled_time(self):
+        preds = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 4)
+
+        self.assertEqual([p.trip_id for p in preds], ['OL-S-1', 'OL-S-2', 'OL-S-3', 'OL-S-4'])
+        cancelled = preds[-1]
+        self.assertTrue(cancelled.cancelled)
+        self.assertIsNone(cancelled.departure_time)
+        self.assertEqual(cancelled.time.isoformat(), '2025-07-07T10:28:00-04:00')
+        self.assertFalse(preds[0].cancelled)
+
+        # Gone once its time has passed, even if the API still lists it
+        self.ig.clock = FixedClock(self.server.now + timedelta(minutes=29))
+        preds = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 4)
+        self.assertNotIn('OL-S-4', [p.trip_id for p in preds])
+
+    def test_departures_merge_schedule_and_prediction(self):
+        late, boarding, not_predicted = self.ig.get_departures('place-north', '0', 'CR-Haverhill', 3)
+
+        # Scheduled 9:35, still to leave at 10:05
+        self.assertEqual(late.trip_id, 'CR-H-207')
+        self.assertEqual(late.schedule.time.isoformat(), '2025-07-07T09:35:00-04:00')
+        self.assertEqual(late.time.isoformat(), '2025-07-07T10:05:00-04:00')
+        self.assertEqual(late.delay, 30)
+        self.assertEqual((late.train, late.track), ('207', '3'))
+
+        self.assertEqual(boarding.delay, 0)
+        self.assertEqual(boarding.status, 'Now boarding')
+        self.assertEqual(boarding.track, '5')
+
+        # Only the schedule so far: its time, no delay and no track yet
+        self.assertEqual(not_predicted.time.isoformat(), '2025-07-07T10:50:00-04:00')
+        self.assertIsNone(not_predicted.delay)
+        self.assertIsNone(not_predicted.track)
+        self.assertEqual(not_predicted.train, '213')
+        self.assertFalse(not_predicted.scheduled)
+
+        request, = self.server.requests_to('/schedules')
+        self.assertEqual(request['filter[min_time]'], '09:00')
+
+    def test_departures_fall_back(self):
+        self.server.inject('/schedules', status=503)
+        self.assertEqual(self.ig.get_departures('place-north', '0', 'CR-Haverhill'), [])
+
+    def test_server_error(self):
+        self.server.inject('/predictions', status=503)
+        self.assertEqual(self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange'), [])
+
+    def test_rate_limited(self):
+        self.server.inject('/alerts', status=429, headers={'Retry-After': '30'})
+        alerts = self.ig.get_alerts(['Orange'], ['place-ogmnl'])
+
+        # Retried once Retry-After had passed on the (simulated) clock
+        self.assertEqual([a.id for a in alerts], ['600001'])
+        self.assertEqual(len(self.server.requests_to('/alerts')), 2)
+        self.assertEqual(self.ig.clock.now(), self.server.now + timedelta(seconds=30))
+
+    def test_rate_limited_throughout(self):
+        self.server.inject('/alerts', status=429, headers={'Retry-After': '30'}, count=-1)
+        self.assertEqual(self.ig.get_alerts(['Orange'], ['place-ogmnl']), [])
+        self.assertEqual(len(self.server.requests_to('/alerts')), self.ig.max_retries)
+
+    def test_waits_for_budget_reset(self):
+        reset = self.server.now.timestamp() + 45
+        self.server.set_rate_limit(1000, 1, reset)
+        self.ig.get_alerts(['Orange'], ['place-ogmnl'])
+
+        budget = self.ig.rate_budget()
+        self.assertEqual((budget.limit, budget.remaining, budget.reset_in), (1000, 0, 45))
+
+        # The next request holds off until the window resets instead of drawing a 429
+        self.ig.get_alerts(['Orange'], ['place-ogmnl'])
+        self.assertEqual(self.ig.clock.now().timestamp(), reset)
+
+    def test_timeout_is_retried(self):
+        self.server.inject('/predictions', delay=1.5)
+        preds = self.ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 1)
+
+        self.assertEqual([p.trip_id for p in preds], ['OL-S-1'])
+        self.assertEqual(len(self.server.requests_to('/predictions')), 2)
+
+    def test_unreachable_api_is_an_error(self):
+        """An API outage shows as an error, not as there being no trips."""
+        self.server.inject('/', status=500, count=-1)
+        self.ig.max_retries = 2
+        with self.assertRaises(requests.HTTPError):
+            self.ig.get_journey_trips('Red', 'place-cntsq', 'place-harsq')
+
+        config = Config(mode='multi-station', route_id='Red', route_name='Red Line',
+                        from_station='Central', from_station_id='place-cntsq',
+                        to_station='Harvard', to_station_id='place-harsq')
+        mode = MultiStationMode(config, clock=self.ig.clock)
+        with self.assertLogs('instantmbta.MultiStationMode', level='ERROR'):
+            lines = [l.text for l in mode.format_for_display(mode.gather_data(self.ig)).lines]
+        self.assertTrue(lines[-1].startswith('Error: 500'))
+        self.assertNotIn('No upcoming trips', lines)
+
 
 if __name__ == '__main__':
     unittest.main() 
\ No newline at end of file
@@ -0,0 +1,138 @@
+"""Tests for parsing JSON:API documents into models."""
+
+import unittest
+from datetime import datetime, timedelta, timezone
+
+from instantmbta.alerts import Alert
+from instantmbta.jsonapi import ResourceIndex, next_link, parse_document
+from instantmbta.models import Prediction, Route, Stop, Trip
+
+EDT = timezone(timedelta(hours=-4))
+
+DOCUMENT = {
+    'data': [
+        {
+            'type': 'prediction', 'id': 'prediction-1',
+            'attributes': {
+                'arrival_time': None,
+                'departure_time': '2025-07-07T10:05:00-04:00',
+                'direction_id': 0,
+                'departure_uncertainty': 60,
+                'stop_sequence': 1,
+                'status': None,
+            },
+            'relationships': {
+                'route': {'data': {'type': 'route', 'id': 'Orange'}},
+                'stop': {'data': {'type': 'stop', 'id': '70036'}},
+                'trip': {'data': {'type': 'trip', 'id': 'OL-S-1'}},
+                'vehicle': {'data': None},
+            },
+        },
+        {
+            'type': 'prediction', 'id': 'prediction-2',
+            'attributes': {'arrival_time': '2025-07-07T10:12:00-04:00', 'direction_id': 0},
+            'relationships': {
+                'route': {'data': {'type': 'route', 'id': 'Orange'}},
+                'trip': {'data': {'type': 'trip', 'id': 'OL-S-2'}},
+            },
+        },
+    ],
+    'included': [
+        {'type': 'trip', 'id': 'OL-S-1',
+         'attributes': {'headsign': 'Forest Hills', 'direction_id': 0},
+         'relationships': {'route': {'data': {'type': 'route', 'id': 'Orange'}}}},
+        {'type': 'stop', 'id': '70036',
+         'attributes': {'name': 'Oak Grove', 'location_type': 0, 'platform_code': None},
+         'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-ogmnl'}}}},
+        {'type': 'stop', 'id': 'place-ogmnl',
+         'attributes': {'name': 'Oak Grove', 'location_type': 1},
+         'relationships': {'parent_station': {'data': None}}},
+    ],
+}
+
+
+class TestResourceIndex(unittest.TestCase):
+    def test_predictions(self):
+        first, second = parse_document(DOCUMENT)
+
+        self.assertIsInstance(first, Prediction)
+        self.assertEqual(first.departure_time, datetime(2025, 7, 7, 10, 5, tzinfo=EDT))
+        self.assertIsNone(first.arrival_time)
+        self.assertEqual(first.time, first.departure_time)
+        self.assertEqual(first.departure_uncertainty, 60)
+        self.assertIsNone(first.vehicle)
+        self.assertFalse(first.scheduled)
+
+        self.assertEqual(second.time, datetime(2025, 7, 7, 10, 12, tzinfo=EDT))
+
+    def test_relationships_resolved(self):
+        first, second = parse_document(DOCUMENT)
+
+        self.assertEqual(first.destination, 'Forest Hills')
+        self.assertEqual(first.stop.name, 'Oak Grove')
+        self.assertEqual(first.stop.parent_station, Stop('place-ogmnl', 'Oak Grove', 1))
+        self.assertEqual(first.stop.station_id, 'place-ogmnl')
+        # One model per resource, however many times it's referred to
+        self.assertIs(first.route, second.route)
+        self.assertIs(first.trip.route, first.route)
+
+    def test_missing_related_resource_has_id_only(self):
+        _, second = parse_document(DOCUMENT)
+
+        self.assertEqual(second.route, Route('Orange'))
+        self.assertEqual(second.trip, Trip('OL-S-2'))
+        self.assertEqual(second.trip_id, 'OL-S-2')
+        self.assertIsNone(second.destination)
+        self.assertIsNone(second.stop)
+
+    def test_single_resource(self):
+        route = {'type': 'route', 'id': 'Red',
+                 'attributes': {'long_name': 'Red Line', 'type': 1,
+                                'direction_destinations': ['Ashmont/Braintree', 'Alewife']}}
+        self.assertEqual(parse_document({'data': route}),
+                         [Route('Red', 'Red Line', type=1,
+                                direction_destinations=['Ashmont/Braintree', 'Alewife'])])
+        self.assertEqual(parse_document({'data': None}), [])
+
+    def test_alerts(self):
+        alert = {'type': 'alert', 'id': '600001',
+                 'attributes': {'header': 'Shuttle buses', 'effect': 'SHUTTLE', 'severity': 7,
+                                'informed_entity': [{'route': 'Orange'}]}}
+        parsed, = parse_document({'data': [alert]})
+        self.assertIsInstance(parsed, Alert)
+        self.assertTrue(parsed.affects(['Orange'], []))
+
+    def test_cancelled_prediction_uses_schedule(self):
+        document = {
+            'data': [{
+                'type': 'prediction', 'id': 'prediction-1',
+                'attributes': {'arrival_time': None, 'departure_time': None,
+                               'schedule_relationship': 'CANCELLED'},
+                'relationships': {'schedule': {'data': {'type': 'schedule', 'id': 'schedule-1'}}},
+            }],
+            'included': [{
+                'type': 'schedule', 'id': 'schedule-1',
+                'attributes': {'departure_time': '2025-07-07T10:28:00-04:00'},
+                'relationships': {'prediction': {'data': {'type': 'prediction', 'id': 'prediction-1'}}},
+            }],
+        }
+        prediction, = parse_document(document)
+
+        self.assertTrue(prediction.cancelled)
+        self.assertEqual(prediction.time, datetime(2025, 7, 7, 10, 28, tzinfo=EDT))
+        # The schedule's link back to the prediction doesn't recurse
+        self.assertIsNone(prediction.schedule.prediction)
+
+    def test_unknown_types_skipped(self):
+        index = ResourceIndex({'data': [{'type': 'facility', 'id': 'f1'}]})
+        self.assertEqual(index.models(), [])
+        self.assertEqual(index.get('facility', 'f1'), {'type': 'facility', 'id': 'f1'})
+
+    def test_next_link(self):
+        self.assertIsNone(next_link(DOCUMENT))
+        url = 'https://api-v3.mbta.com/stops?page[offset]=2&page[limit]=2'
+        self.assertEqual(next_link({'data': [], 'links': {'next': url}}), url)
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,165 @@
+"""Golden-image tests for the e-ink panel layout.
+
+Each case renders a DisplayData through InkyTrain onto a SimulatedInky and
+compares the captured palette image with tests/golden/<case>.png.
+
+To create or refresh the goldens after an intended layout change:
+
+    UPDATE_GOLDENS=1 python -m unittest tests.test_layout_golden
+
+then look over the changed PNGs before committing them. A case without a
+golden fails, so new cases need their golden committed with them.
+"""
+
+import os
+import tempfile
+import unittest
+from pathlib import Path
+
+from instantmbta.display_modes import DisplayData, DisplayLine
+from instantmbta.simulated_inky import SimulatedInky
+
+try:
+    from PIL import Image
+    import font_hanken_grotesk
+except ImportError:
+    Image = None
+
+GOLDEN_DIR = Path(__file__).parent / 'golden'
+UPDATE_GOLDENS = os.environ.get('UPDATE_GOLDENS') == '1'
+
+# Fraction of pixels allowed to differ, to absorb font rasterizer differences
+# between Pillow/FreeType versions
+TOLERANCE = 0.005
+
+
+def route(text):
+    return DisplayLine(text=text, is_route=True)
+
+
+GOLDEN_CASES = {
+    'single_station': DisplayData(
+        title='Oak Grove',
+        date='07/06/25',
+        lines=[
+            route('OL In: 10:15 AM, 10:23 AM'),
+            route('OL Out: 10:18 AM, 10:26 AM'),
+        ],
+    ),
+    'countdown': DisplayData(
+        title='Malden Center',
+        date='07/06/25',
+        lines=[
+            route('OL In: BRD, 4 min'),
+            route('OL Out: ARR, 20+ min'),
+        ],
+    ),
+    # Long titles run into the date
+    'long_title': DisplayData(
+        title='Massachusetts Avenue',
+        date='07/06/25',
+        lines=[route('OL In: 10:15 AM, 10:23 AM')],
+    ),
+    # Lines wider than the panel are clipped at the edge
+    'overflowing_line': DisplayData(
+        title='Park Street',
+        date='07/06/25',
+        lines=[route('GL-E West: 10:15 AM, 10:23 AM, 10:31 AM, 10:39 AM')],
+    ),
+    # More lines than MAX_LINES, in the small font
+    'max_lines': DisplayData(
+        title='North Station',
+        date='07/06/25',
+        lines=[DisplayLine(text=f'Line {i}: 10:{i:02d} AM') for i in range(10)],
+    ),
+    'occupancy': DisplayData(
+        title='Oak Grove',
+        date='07/06/25',
+        lines=[
+            DisplayLine(text='OL In: 10:15 AM, 10:23 AM', is_route=True, occupancy=1),
+            DisplayLine(text='OL Out: 10:18 AM', is_route=True, occupancy=3),
+        ],
+    ),
+    'leave_now': DisplayData(
+        title='Oak Grove',
+        date='07/06/25',
+        lines=[
+            DisplayLine(text='LEAVE NOW: OL In 10:15 AM', urgent=True),
+            route('OL In: 10:15 AM, 10:23 AM'),
+        ],
+    ),
+    'alert_footer': DisplayData(
+        title='Oak Grove',
+        date='07/06/25',
+        lines=[
+            route('OL In: 10:15 AM, 10:23 AM'),
+            route('OL Out: 10:18 AM, 10:26 AM'),
+            route('CR In: 10:30 AM'),
+        ],
+        alerts=['Shuttles Oak Grove - North Station', 'Elevator closure'],
+    ),
+    # As MultiStationMode formats a journey
+    'multi_station': DisplayData(
+        title='Orange Line',
+        date='07/06/25',
+        lines=[
+            DisplayLine(text='Oak Grove → North Station', is_header=True),
+            route('depart 10:15 AM → arrive 10:32 AM'),
+            route('depart 10:23 AM → arrive 10:40 AM'),
+            route('depart 10:31 AM → arrive 10:48 AM'),
+        ],
+    ),
+}
+
+
+def render(display_data):
+    """Render through InkyTrain and return the image the panel was given."""
+    from instantmbta.inkytrain import InkyTrain
+
+    display = SimulatedInky()
+    InkyTrain(display).draw_from_display_data(display_data)
+    return display.shown
+
+
+def differing_fraction(actual, expected) -> float:
+    """Fraction of pixels whose palette index differs."""
+    if actual.size != expected.size:
+        return 1.0
+    diff = sum(a != e for a, e in zip(actual.getdata(), expected.getdata()))
+    return diff / (actual.width * actual.height)
+
+
+@unittest.skipIf(Image is None, "Pillow and fonts not installed")
+class TestLayoutGolden(unittest.TestCase):
+    def test_golden_images(self):
+        for name, display_data in GOLDEN_CASES.items():
+            with self.subTest(case=name):
+                self.check_golden(name, display_data)
+
+    def check_golden(self, name, display_data):
+        actual = render(display_data)
+        golden_path = GOLDEN_DIR / f'{name}.png'
+
+        if UPDATE_GOLDENS:
+            GOLDEN_DIR.mkdir(exist_ok=True)
+            actual.save(golden_path)
+            return
+        if not golden_path.exists():
+            self.fail(f"No golden for {name}; run with UPDATE_GOLDENS=1 to create it")
+
+        with Image.open(golden_path) as expected:
+            expected = expected.convert('P') if expected.mode != 'P' else expected.copy()
+
+        fraction = differing_fraction(actual, expected)
+        if fraction > TOLERANCE:
+            # Keep the rendering around so the difference can be inspected
+            out_dir = Path(tempfile.gettempdir()) / 'instantmbta-golden'
+            out_dir.mkdir(exist_ok=True)
+            actual_path = out_dir / f'{name}.png'
+            actual.save(actual_path)
+            self.fail(f"{name}: {fraction:.2%} of pixels differ from {golden_path} "
+                      f"(tolerance {TOLERANCE:.2%}); rendering saved to {actual_path}")
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -1,5 +1,6 @@
 """Tests for the main module."""
 
+import json
 import unittest
 from unittest.mock import Mock, patch
 from pathlib import Path
@@ -8,8 +9,12 @@ import yaml
 import logging
 import requests
 from instantmbta.__main__ import run_display_loop, run_once, main
-from instantmbta.config_parser import Config, DisplayConfig
+from datetime import datetime, time
+from instantmbta.clock import FixedClock
+from instantmbta.config_parser import Config, DisplayConfig, Profile
 from instantmbta.display_modes import DisplayData, DisplayLine
+from instantmbta.ratelimit import RateBudget
+from instantmbta.renderers import TerminalRenderer
 
 
 class TestMainModule(unittest.TestCase):
@@ -25,6 +30,8 @@ class TestMainModule(unittest.TestCase):
         )
         self.display_mode = Mock()
         self.ig = Mock()
+        self.ig.get_alerts.return_value = []
+        self.ig.rate_budget.return_value = None
         self.it = Mock()
         self.logger = Mock()
     
@@ -97,6 +104,73 @@ class TestMainModule(unittest.TestCase):
         # Should update display both times (first time and when data changes)
         self.assertEqual(self.it.draw_from_display_data.call_count, 2)
     
+    def test_run_display_loop_slows_down_for_rate_budget(self):
+        """The refresh interval stretches when the API budget runs low."""
+        self.display_mode.format_for_display.return_value = DisplayData(
+            title="Oak Grove", date="07/06/25", lines=[DisplayLine("OL In: 10:15 AM")]
+        )
+        self.ig.rate_budget.side_effect = [
+            RateBudget(limit=1000, remaining=995, reset_in=60, retry_after=0, requests=5),
+            RateBudget(limit=100, remaining=25, reset_in=240, retry_after=0, requests=10),
+        ]
+        
+        with patch('time.sleep') as mock_sleep:
+            mock_sleep.side_effect = [None, KeyboardInterrupt()]
+            with self.assertRaises(KeyboardInterrupt):
+                run_display_loop(self.config, self.display_mode, self.ig, self.it, self.logger)
+        
+        sleep_calls = [call[0][0] for call in mock_sleep.call_args_list]
+        # 15 usable requests at 5 per poll: 3 polls spread over 240s
+        self.assertEqual(sleep_calls, [60, 80])
+    
+    def test_run_display_loop_writes_status(self):
+        """Each update logs the API budget and writes it to the status file."""
+        self.display_mode.format_for_display.return_value = DisplayData(title="Oak Grove", date="07/07/25")
+        budget = RateBudget(limit=1000, remaining=995, reset_in=60, retry_after=0, requests=5)
+        self.ig.rate_budget.return_value = budget
+        clock = FixedClock(datetime.fromisoformat('2025-07-07T10:00:00-04:00'))
+        clock.sleep = Mock(side_effect=KeyboardInterrupt())
+
+        with tempfile.TemporaryDirectory() as tmp:
+            status_file = Path(tmp) / 'status.json'
+            with self.assertRaises(KeyboardInterrupt):
+                run_display_loop(self.config, self.display_mode, self.ig, self.it, self.logger, clock,
+                                 status_file=status_file)
+            status = json.loads(status_file.read_text())
+
+        self.assertEqual(status['updated'], '2025-07-07T10:00:00-04:00')
+        self.assertEqual(status['poll_interval_seconds'], 60)
+        self.assertEqual(status['api_rate_remaining'], 995)
+        self.logger.info.assert_any_call("API budget: %s", budget)
+
+    def test_run_display_loop_switches_profiles(self):
+        """The loop wakes when a profile starts and switches to its display mode."""
+        morning_mode = Mock()
+        for mode in (self.display_mode, morning_mode):
+            mode.format_for_display.return_value = DisplayData(title="Oak Grove", date="07/07/25")
+        morning = Profile('morning', self.config, start=time(6, 30), end=time(9, 30))
+        self.config.profiles = [morning]
+        profiles = Mock()
+        profiles.active.side_effect = [(None, self.display_mode), (morning, morning_mode)]
+
+        clock = FixedClock(datetime.fromisoformat('2025-07-07T06:29:30-04:00'))
+        sleeps = []
+
+        def sleep(seconds):
+            sleeps.append(seconds)
+            if len(sleeps) == 2:
+                raise KeyboardInterrupt()
+            clock.advance(seconds)
+
+        clock.sleep = sleep
+        with self.assertRaises(KeyboardInterrupt):
+            run_display_loop(self.config, self.display_mode, self.ig, self.it, self.logger, clock, profiles)
+
+        self.assertEqual(sleeps, [30, 60])
+        self.display_mode.gather_data.assert_called_once_with(self.ig)
+        morning_mode.gather_data.assert_called_once_with(self.ig)
+        self.logger.info.assert_any_call("Switching to profile %s", "morning")
+
     def test_run_display_loop_network_error_recovery(self):
         """Test network error handling with exponential backoff."""
         # Simulate network error then success
@@ -142,7 +216,8 @@ class TestMainModule(unittest.TestCase):
             test_args = ['instantmbta', '--config', str(config_path), '--once']
             
             with patch('sys.argv', test_args):
-                with patch('instantmbta.__main__.InfoGather'):
+                with patch('instantmbta.__main__.create_provider'), \
+                        patch('instantmbta.__main__.load_catalog', return_value=None):
                     with patch('instantmbta.__main__.create_display_mode'):
                         with patch('instantmbta.__main__.run_once') as mock_run:
                             # Mock platform check to avoid display import
@@ -151,6 +226,30 @@ class TestMainModule(unittest.TestCase):
             
             mock_run.assert_called_once()
     
+    def test_main_renderer_option(self):
+        """--renderer picks the backend regardless of platform."""
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            config_path = Path(tmp_dir) / 'test.yaml'
+            with open(config_path, 'w') as f:
+                yaml.dump({
+                    'mode': 'single-station',
+                    'station': 'Oak Grove',
+                    'routes': [{'Orange Line': {'inbound': 2}}]
+                }, f)
+            
+            test_args = ['instantmbta', '--config', str(config_path), '--once', '--renderer', 'terminal']
+            
+            with patch('sys.argv', test_args):
+                with patch('instantmbta.__main__.create_provider'), \
+                        patch('instantmbta.__main__.load_catalog', return_value=None):
+                    with patch('instantmbta.__main__.create_display_mode'):
+                        with patch('instantmbta.__main__.run_once') as mock_run:
+                            with patch('platform.machine', return_value='x86_64'):
+                                main()
+            
+            renderer = mock_run.call_args[0][3]
+            self.assertIsInstance(renderer, TerminalRenderer)
+    
     def test_main_error_handling(self):
         """Test main function error handling."""
         test_args = ['instantmbta']  # No config
@@ -266,7 +365,8 @@ class TestMainModule(unittest.TestCase):
             test_args = ['instantmbta', '--config', str(config_path)]
             
             with patch('sys.argv', test_args):
-                with patch('instantmbta.__main__.InfoGather'):
+                with patch('instantmbta.__main__.create_provider'), \
+                        patch('instantmbta.__main__.load_catalog', return_value=None):
                     with patch('instantmbta.__main__.create_display_mode'):
                         with patch('instantmbta.__main__.run_display_loop') as mock_loop:
                             mock_loop.side_effect = KeyboardInterrupt()
@@ -0,0 +1,104 @@
+"""Tests for API rate limit tracking."""
+
+import unittest
+from datetime import datetime
+from unittest.mock import MagicMock
+
+from instantmbta.clock import FixedClock
+from instantmbta.ratelimit import DEFAULT_RETRY_AFTER, RateBudget, RateLimiter
+
+
+def response(status=200, **headers):
+    return MagicMock(status_code=status, headers=headers)
+
+
+class TestRateLimiter(unittest.TestCase):
+    def setUp(self):
+        self.clock = FixedClock(datetime(2025, 7, 7, 10, 0))
+        self.now = self.clock.now().timestamp()
+        self.limiter = RateLimiter(self.clock)
+
+    def test_tracks_headers(self):
+        self.limiter.update(response(**{
+            'x-ratelimit-limit': '1000',
+            'x-ratelimit-remaining': '997',
+            'x-ratelimit-reset': str(int(self.now) + 40),
+        }))
+        budget = self.limiter.budget()
+        self.assertEqual((budget.limit, budget.remaining, budget.reset_in), (1000, 997, 40))
+        self.assertEqual(budget.requests, 1)
+        self.assertEqual(str(budget), "997/1000 requests left, resets in 40s")
+        self.assertEqual(self.limiter.delay(), 0)
+
+    def test_unknown_budget(self):
+        self.limiter.update(response())
+        self.assertIsNone(self.limiter.budget().limit)
+        self.assertEqual(self.limiter.delay(), 0)
+
+    def test_exhausted_window(self):
+        self.limiter.update(response(**{
+            'x-ratelimit-limit': '20',
+            'x-ratelimit-remaining': '0',
+            'x-ratelimit-reset': str(int(self.now) + 25),
+        }))
+        self.assertEqual(self.limiter.delay(), 25)
+
+        self.limiter.wait()
+        self.assertEqual(self.clock.now().timestamp(), self.now + 25)
+        self.assertEqual(self.limiter.delay(), 0)
+
+    def test_retry_after_seconds(self):
+        self.limiter.update(response(429, **{'Retry-After': '12'}))
+        self.assertEqual(self.limiter.delay(), 12)
+        self.assertEqual(self.limiter.budget().retry_after, 12)
+
+    def test_retry_after_date(self):
+        self.limiter.update(response(429, **{'Retry-After': 'Mon, 07 Jul 2025 14:01:30 GMT'}))
+        self.assertEqual(self.limiter.delay(), 90)
+
+    def test_retry_after_missing(self):
+        self.limiter.update(response(429))
+        self.assertEqual(self.limiter.delay(), DEFAULT_RETRY_AFTER)
+
+        limiter = RateLimiter(self.clock)
+        limiter.update(response(429, **{'x-ratelimit-reset': str(int(self.now) + 8)}))
+        self.assertEqual(limiter.delay(), 8)
+
+
+class TestPollInterval(unittest.TestCase):
+    def test_plenty_of_budget(self):
+        budget = RateBudget(limit=1000, remaining=990, reset_in=50, retry_after=0, requests=10)
+        self.assertEqual(budget.poll_interval(60, 5), 60)
+
+    def test_spreads_remaining_budget(self):
+        # 20 usable requests at 5 per poll: 4 polls to spread over 120s
+        budget = RateBudget(limit=100, remaining=30, reset_in=120, retry_after=0, requests=70)
+        self.assertEqual(budget.poll_interval(10, 5), 30)
+
+    def test_waits_for_reset_when_out_of_budget(self):
+        budget = RateBudget(limit=100, remaining=12, reset_in=200, retry_after=0, requests=88)
+        self.assertEqual(budget.poll_interval(60, 5), 200)
+
+    def test_honors_retry_after(self):
+        budget = RateBudget(limit=None, remaining=None, reset_in=0, retry_after=90, requests=3)
+        self.assertEqual(budget.poll_interval(60, 3), 90)
+
+    def test_unknown_budget(self):
+        budget = RateBudget(limit=None, remaining=None, reset_in=0, retry_after=0, requests=0)
+        self.assertEqual(budget.poll_interval(60, 0), 60)
+
+
+class TestBudgetMetrics(unittest.TestCase):
+    def test_metrics(self):
+        budget = RateBudget(limit=1000, remaining=990, reset_in=49.6, retry_after=0, requests=10)
+        self.assertEqual(budget.metrics(), {
+            'api_requests': 10,
+            'api_rate_limit': 1000,
+            'api_rate_remaining': 990,
+            'api_rate_reset_seconds': 50,
+            'api_retry_after_seconds': 0,
+        })
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,153 @@
+"""Tests for recording and replaying API traffic."""
+
+import json
+import tempfile
+import unittest
+from pathlib import Path
+from unittest.mock import MagicMock, patch
+
+import requests
+import yaml
+
+from instantmbta.__main__ import main
+from instantmbta.clock import FixedClock
+from instantmbta.infogather import InfoGather
+from instantmbta.recording import (
+    RecordingTransport,
+    ReplayFinished,
+    ReplayTransport,
+    redact_url,
+)
+from instantmbta.renderers import Renderer
+
+from tests.fake_mbta import FakeMBTAServer
+
+
+class FrameRecorder(Renderer):
+    def __init__(self):
+        self.frames = []
+
+    def draw_from_display_data(self, display_data):
+        self.frames.append(display_data)
+
+
+class TestRecording(unittest.TestCase):
+    def setUp(self):
+        self.server = FakeMBTAServer('basic').start()
+        self.temp_dir = tempfile.TemporaryDirectory()
+        self.record_dir = Path(self.temp_dir.name) / 'recording'
+
+    def tearDown(self):
+        self.server.stop()
+        self.temp_dir.cleanup()
+
+    def record_predictions(self):
+        clock = FixedClock(self.server.now)
+        ig = InfoGather(api_url=self.server.url, clock=clock,
+                        transport=RecordingTransport(self.record_dir, clock))
+        return ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 2)
+
+    def test_redact_url(self):
+        self.assertEqual(
+            redact_url('https://api-v3.mbta.com/predictions?filter[stop]=place-ogmnl&api_key=secret'),
+            'https://api-v3.mbta.com/predictions?filter[stop]=place-ogmnl')
+
+    def test_records_responses(self):
+        self.record_predictions()
+
+        paths = sorted(self.record_dir.glob('*.json'))
+        self.assertEqual([p.name for p in paths], ['00001.json'])
+        with open(paths[0]) as f:
+            record = json.load(f)
+        self.assertEqual(record['time'], '2025-07-07T10:00:00-04:00')
+        self.assertEqual(record['status'], 200)
+        self.assertIn('/predictions?filter[stop]=place-ogmnl', record['url'])
+        self.assertNotIn('api_key', record['url'])
+        self.assertEqual(len(json.loads(record['body'])['data']), 4)
+
+    def test_records_errors(self):
+        clock = FixedClock(self.server.now)
+        failing = MagicMock(side_effect=requests.exceptions.Timeout("read timed out"))
+        transport = RecordingTransport(self.record_dir, clock, get=failing)
+        with self.assertRaises(requests.exceptions.Timeout):
+            transport.get('http://example/predictions')
+
+        replay = ReplayTransport(self.record_dir)
+        with self.assertRaises(requests.exceptions.Timeout):
+            replay.get('http://example/predictions')
+
+    def test_replay_without_network(self):
+        recorded = self.record_predictions()
+        self.server.stop()
+
+        replay = ReplayTransport(self.record_dir)
+        ig = InfoGather(api_url='https://api-v3.mbta.com', clock=replay.clock, transport=replay)
+
+        self.assertEqual(replay.clock.now().isoformat(), '2025-07-07T10:00:00-04:00')
+        self.assertEqual(ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 2), recorded)
+        self.assertTrue(replay.exhausted)
+        # Running out of recordings ends the replay instead of looking like an outage
+        with self.assertRaises(ReplayFinished):
+            ig.get_predictions_filtered('place-ogmnl', '0', 'Orange', 2)
+
+    def test_replay_follows_recorded_clock(self):
+        clock = FixedClock(self.server.now)
+        transport = RecordingTransport(self.record_dir, clock)
+        url = self.server.url + '/alerts?filter[route]=Orange'
+        transport.get(url)
+        clock.advance(60)
+        transport.get(url)
+
+        replay = ReplayTransport(self.record_dir)
+        replay.get(url)
+        self.assertEqual(replay.clock.now().minute, 0)
+        replay.get(url)
+        self.assertEqual(replay.clock.now().minute, 1)
+
+    def test_unrecorded_request(self):
+        self.record_predictions()
+        replay = ReplayTransport(self.record_dir)
+        with self.assertRaises(requests.exceptions.ConnectionError):
+            replay.get('https://api-v3.mbta.com/vehicles?filter[route]=Red')
+
+    def test_replay_requires_recordings(self):
+        with self.assertRaises(ValueError):
+            ReplayTransport(self.temp_dir.name)
+
+    def test_main_record_then_replay(self):
+        """A run recorded with --once replays through the display loop."""
+        config_path = Path(self.temp_dir.name) / 'config.yaml'
+        with open(config_path, 'w') as f:
+            yaml.dump({
+                'mode': 'single-station',
+                'station': 'Oak Grove',
+                'routes': [{'Orange Line': {'inbound': 2}}],
+                'display': {'time_format': '24h'},
+                'provider': {'api_url': self.server.url},
+            }, f)
+
+        recorded = FrameRecorder()
+        args = ['instantmbta', '--config', str(config_path), '--once', '--record', str(self.record_dir)]
+        with patch('sys.argv', args), \
+                patch('instantmbta.__main__.load_catalog', return_value=None), \
+                patch('instantmbta.__main__.Clock', lambda tz: FixedClock(self.server.now, tz)), \
+                patch('instantmbta.__main__.create_renderer', return_value=recorded), \
+                patch('instantmbta.__main__.setup_logging', return_value=MagicMock()), \
+                patch('builtins.print'):
+            main()
+        self.server.stop()
+
+        replayed = FrameRecorder()
+        args = ['instantmbta', '--config', str(config_path), '--replay', str(self.record_dir)]
+        with patch('sys.argv', args), \
+                patch('instantmbta.__main__.create_renderer', return_value=replayed), \
+                patch('instantmbta.__main__.setup_logging', return_value=MagicMock()):
+            main()
+
+        self.assertEqual(len(recorded.frames), 1)
+        self.assertEqual(replayed.frames, recorded.frames)
+        self.assertIn('10:05', recorded.frames[0].lines[0].text)
+
+
+if __name__ == '__main__':
+    unittest.main()
@@ -0,0 +1,161 @@
+"""Tests for the display renderers."""
+
+import io
+import sys
+import tempfile
+import unittest
+from pathlib import Path
+from unittest.mock import MagicMock, patch
+
+from instantmbta import layout
+from instantmbta.config_parser import Config, DisplayConfig
+from instantmbta.display_modes import DisplayData, DisplayLine
+from instantmbta.renderers import (
+    PNGRenderer,
+    Renderer,
+    TerminalRenderer,
+    create_renderer,
+    default_renderer,
+)
+from instantmbta.simulated_inky import SimulatedInky
+
+try:
+    from PIL import Image
+    import font_hanken_grotesk
+except ImportError:
+    Image = None
+
+
+def sample_display_data(alerts=None):
+    return DisplayData(
+        title='Oak Grove',
+        date='07/06/25',
+        lines=[
+            DisplayLine(text='OL In: 10:15 AM, 10:23 AM', is_route=True),
+            DisplayLine(text='CR In: 10:30 AM', is_route=True),
+        ],
+        alerts=alerts or [],
+    )
+
+
+class TestTerminalRenderer(unittest.TestCase):
+    def test_plain_text_box(self):
+        out = io.StringIO()
+        renderer = TerminalRenderer(stream=out, width=36)
+        # Without rich the display is drawn as a plain text box
+        with patch.dict(sys.modules, {'rich.console': None}):
+            renderer.draw_from_display_data(sample_display_data(['Shuttles Oak Grove - North Station']))
+
+        lines = out.getvalue().splitlines()
+        self.assertEqual(lines[0], '+' + '-' * 34 + '+')
+        self.assertEqual(lines[1], '| Oak Grove               07/06/25 |')
+        self.assertIn('| OL In: 10:15 AM, 10:23 AM        |', lines)
+        self.assertIn('| ! Shuttles Oak Grove - North Sta |', lines)
+        self.assertTrue(all(len(line) == 36 for line in lines))
+
+    def test_plain_occupancy(self):
+        out = io.StringIO()
+        display_data = sample_display_data()
+        display_data.lines[0].occupancy = 2
+        with patch.dict(sys.modules, {'rich.console': None}):
+            TerminalRenderer(stream=out, width=36).draw_from_display_data(display_data)
+        self.assertIn('| OL In: 10:15 AM, 10:23 AM    ▮▮▯ |', out.getvalue().splitlines())
+
+
+class TestOccupancyBars(unittest.TestCase):
+    def test_bars_filled_to_level(self):
+        draw = MagicMock()
+        layout.draw_occupancy(draw, right=240, baseline=50, level=2)
+
+        boxes = [c.args[0] for c in draw.rectangle.call_args_list]
+        fills = [c.kwargs.get('fill') for c in draw.rectangle.call_args_list]
+        self.assertEqual(fills, [layout.BLACK, layout.BLACK, None])
+        # Rising left to right, ending at the right edge
+        self.assertEqual([box[1] for box in boxes], [44, 40, 36])
+        self.assertEqual(boxes[-1][2], 239)
+
+    def test_full_in_red(self):
+        draw = MagicMock()
+        layout.draw_occupancy(draw, right=240, baseline=50, level=3)
+        self.assertEqual({c.kwargs.get('fill') for c in draw.rectangle.call_args_list}, {layout.RED})
+
+
+@unittest.skipIf(Image is None, "Pillow and fonts not installed")
+class TestPNGRenderer(unittest.TestCase):
+    def test_writes_panel_image(self):
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            path = Path(tmp_dir) / 'display.png'
+            PNGRenderer(path).draw_from_display_data(sample_display_data(['Suspension']))
+
+            with Image.open(path) as img:
+                self.assertEqual(img.size, (layout.PANEL_WIDTH, layout.PANEL_HEIGHT))
+                img = img.convert('RGB')
+                # Date and alert footer are drawn in the panel's red
+                self.assertIn((200, 0, 0), {color for _, color in img.getcolors(maxcolors=4096)})
+            self.assertFalse((Path(tmp_dir) / 'display.png.tmp').exists())
+
+
+class TestInkyTrain(unittest.TestCase):
+    def test_draws_on_simulated_display(self):
+        from instantmbta.inkytrain import InkyTrain
+
+        display = SimulatedInky(width=212, height=104)
+        image = MagicMock()
+        with patch('instantmbta.inkytrain.render_display_data', return_value=image) as render:
+            InkyTrain(display).draw_from_display_data(sample_display_data())
+
+        render.assert_called_once_with(sample_display_data(), 212, 104)
+        self.assertIs(display.image, image)
+        self.assertIs(display.shown, image.copy.return_value)
+        self.assertEqual(display.border, SimulatedInky.BLACK)
+        self.assertEqual(display.show_count, 1)
+        self.assertTrue(display.h_flip and display.v_flip)
+
+
+class TestCreateRenderer(unittest.TestCase):
+    def setUp(self):
+        self.config = Config(mode='single-station',
+                             display=DisplayConfig(image_path='out.png', image_width=212, image_height=104))
+
+    def test_png_and_terminal(self):
+        renderer = create_renderer('png', self.config)
+        self.assertIsInstance(renderer, PNGRenderer)
+        self.assertEqual((renderer.width, renderer.height), (212, 104))
+        self.assertEqual(renderer.path, Path('out.png'))
+
+        self.assertIsInstance(create_renderer('terminal', self.config), TerminalRenderer)
+        self.assertIsNone(create_renderer('none', self.config))
+
+    def test_inky(self):
+        fake_module = MagicMock()
+        with patch.dict(sys.modules, {'instantmbta.inkytrain': fake_module}):
+            renderer = create_renderer('inky', self.config)
+        self.assertIs(renderer, fake_module.InkyTrain.return_value)
+
+    def test_default_depends_on_platform(self):
+        with patch('platform.machine', return_value='aarch64'):
+            self.assertEqual(default_renderer(), 'inky')
+        with patch('platform.machine', return_value='x86_64'):
+            self.assertEqual(default_renderer(), 'none')
+            self.assertIsNone(create_renderer(None, self.config))
+
+    def test_unknown(self):
+        with self.assertRaises(ValueError):
+            create_renderer('hologram', self.config)
+
+    def test_renderer_interface(self):
+        class Recorder(Renderer):
+            def __init__(self):
+                self.drawn = []
+
+            def draw_from_display_data(self, display_data):
+                self.drawn.append(display_data)
+
+        recorder = Recorder()
+        recorder.draw_from_display_data(sample_display_data())
+        recorder.close()
+        self.assertEqual(len(recorder.drawn), 1)
+
+
+if __name__ == '__main__':
+    unittest.main()
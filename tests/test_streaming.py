@@ -0,0 +1,235 @@
+"""Tests for the server-sent events prediction stream."""
+
+import json
+import threading
+import time
+import unittest
+from datetime import datetime, timedelta
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+from instantmbta.config_parser import Config, DisplayConfig, RouteConfig
+from instantmbta.display_modes import SingleStationMode
+from instantmbta.streaming import (
+    PredictionStore,
+    PredictionStream,
+    iter_sse_events,
+)
+
+
+def make_prediction(pred_id, minutes, direction_id=0, route_id='Orange',
+                    trip_id=None, stop_id='70036'):
+    """Build a prediction resource departing `minutes` from now."""
+    departure = datetime.now().astimezone() + timedelta(minutes=minutes)
+    return {
+        'type': 'prediction',
+        'id': pred_id,
+        'attributes': {
+            'departure_time': departure.isoformat(),
+            'arrival_time': departure.isoformat(),
+            'direction_id': direction_id,
+            'departure_uncertainty': 60,
+        },
+        'relationships': {
+            'route': {'data': {'type': 'route', 'id': route_id}},
+            'trip': {'data': {'type': 'trip', 'id': trip_id or f'trip-{pred_id}'}},
+            'stop': {'data': {'type': 'stop', 'id': stop_id}},
+        },
+    }
+
+
+class SSEStandIn(BaseHTTPRequestHandler):
+    """
+    Serves the events in `server.events` with chunked encoding, like the
+    MBTA API, then holds the connection open.
+    """
+
+    protocol_version = 'HTTP/1.1'
+
+    def write_chunk(self, text):
+        body = text.encode()
+        self.wfile.write(f"{len(body):x}\r\n".encode() + body + b"\r\n")
+        self.wfile.flush()
+
+    def do_GET(self):
+        self.server.requests.append((self.path, self.headers.get('Accept')))
+        self.send_response(200)
+        self.send_header('Content-Type', 'text/event-stream')
+        self.send_header('Transfer-Encoding', 'chunked')
+        self.end_headers()
+        for event, payload in self.server.events:
+            self.write_chunk(f"event: {event}\ndata: {json.dumps(payload)}\n\n")
+        self.write_chunk(": keep-alive\n\n")
+        self.server.done.wait(5)
+        self.wfile.write(b"0\r\n\r\n")
+
+    def log_message(self, *args):
+        pass
+
+
+class TestSSEParsing(unittest.TestCase):
+    def test_iter_sse_events(self):
+        lines = [
+            ': keep-alive',
+            'event: reset',
+            'data: [1,',
+            'data: 2]',
+            '',
+            'event: remove',
+            'data: {"id": "x"}',
+            '',
+        ]
+        events = list(iter_sse_events(lines))
+        self.assertEqual(events, [('reset', '[1,\n2]'), ('remove', '{"id": "x"}')])
+
+    def test_iter_sse_events_default_name_and_trailing_event(self):
+        events = list(iter_sse_events(['data: hello']))
+        self.assertEqual(events, [('message', 'hello')])
+
+
+class TestPredictionStore(unittest.TestCase):
+    def setUp(self):
+        self.store = PredictionStore()
+        self.stream = PredictionStream('http://unused', self.store)
+
+    def test_reset_add_update_remove(self):
+        self.assertFalse(self.store.ready)
+        self.stream.handle_event('reset', json.dumps([
+            make_prediction('p1', 5),
+            make_prediction('p2', 10),
+        ]))
+        self.assertTrue(self.store.ready)
+        self.assertEqual(len(self.store), 2)
+
+        self.stream.handle_event('add', json.dumps(make_prediction('p3', 2)))
+        self.stream.handle_event('update', json.dumps(make_prediction('p2', 1)))
+        self.stream.handle_event('remove', json.dumps({'type': 'prediction', 'id': 'p1'}))
+
+        preds = self.store.get_predictions_filtered('place-ogmnl', '0', 'Orange', 5)
+        self.assertEqual([p.id for p in preds], ['p2', 'p3'])
+
+    def test_filters_direction_route_stop_and_past(self):
+        self.store.reset([
+            make_prediction('in', 5, direction_id=0),
+            make_prediction('out', 5, direction_id=1),
+            make_prediction('cr', 6, route_id='CR-Haverhill'),
+            make_prediction('gone', -2),
+            make_prediction('elsewhere', 4, stop_id='70001'),
+            {'type': 'stop', 'id': '70036',
+             'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-ogmnl'}}}},
+            {'type': 'stop', 'id': '70001',
+             'relationships': {'parent_station': {'data': {'type': 'stop', 'id': 'place-forhl'}}}},
+            {'type': 'trip', 'id': 'trip-in', 'attributes': {'headsign': 'Forest Hills'}},
+        ])
+
+        preds = self.store.get_predictions_filtered('place-ogmnl', '0', 'Orange', 5)
+        self.assertEqual([p.id for p in preds], ['in'])
+        self.assertEqual(preds[0].destination, 'Forest Hills')
+
+        preds = self.store.get_predictions_filtered('place-ogmnl', '0', 'Orange,CR-Haverhill', 5)
+        self.assertEqual([p.id for p in preds], ['in', 'cr'])
+
+    def test_count_limit(self):
+        self.store.reset([make_prediction(f'p{i}', i + 1) for i in range(5)])
+        preds = self.store.get_predictions_filtered('place-ogmnl', '0', count=2)
+        self.assertEqual([p.id for p in preds], ['p0', 'p1'])
+
+
+class TestPredictionStreamServer(unittest.TestCase):
+    """Run the stream against a local SSE stand-in server."""
+
+    def setUp(self):
+        self.server = ThreadingHTTPServer(('127.0.0.1', 0), SSEStandIn)
+        self.server.requests = []
+        self.server.done = threading.Event()
+        self.server.events = [
+            ('reset', [make_prediction('p1', 5), make_prediction('p2', 9)]),
+            ('add', make_prediction('p3', 3)),
+            ('remove', {'type': 'prediction', 'id': 'p2'}),
+        ]
+        threading.Thread(target=self.server.serve_forever, daemon=True).start()
+        self.url = f"http://127.0.0.1:{self.server.server_address[1]}/predictions"
+
+    def tearDown(self):
+        self.server.done.set()
+        self.server.shutdown()
+        self.server.server_close()
+
+    def wait_for(self, condition, timeout=5):
+        deadline = time.time() + timeout
+        while time.time() < deadline:
+            if condition():
+                return True
+            time.sleep(0.02)
+        return False
+
+    def test_stream_keeps_store_current(self):
+        store = PredictionStore()
+        stream = PredictionStream(self.url, store, reconnect_delay=0.1)
+        stream.start()
+        try:
+            self.assertTrue(self.wait_for(
+                lambda: [p.id for p in store.get_predictions_filtered('s', '0')] == ['p3', 'p1']
+            ))
+        finally:
+            stream.stop()
+
+        path, accept = self.server.requests[0]
+        self.assertEqual(accept, 'text/event-stream')
+
+    def test_stream_end_stops_store_being_read(self):
+        """Once the connection closes the store is stale until the next reset."""
+        store = PredictionStore()
+        stream = PredictionStream(self.url, store, reconnect_delay=5)
+        stream.start()
+        try:
+            self.assertTrue(self.wait_for(lambda: store.ready))
+            self.server.done.set()
+            self.assertTrue(self.wait_for(lambda: not store.ready))
+        finally:
+            stream.stop()
+
+    def test_unexpected_error_reconnects(self):
+        """A malformed resource doesn't end the thread; it reconnects and resets."""
+        self.server.events.insert(1, ('add', {'type': 'prediction'}))
+        store = PredictionStore()
+        stream = PredictionStream(self.url, store, reconnect_delay=0.1)
+        with self.assertLogs('instantmbta.streaming', level='ERROR'):
+            stream.start()
+            try:
+                self.assertTrue(self.wait_for(lambda: len(self.server.requests) >= 2))
+                self.assertTrue(stream.is_alive())
+            finally:
+                stream.stop()
+
+    def test_single_station_mode_reads_from_store(self):
+        store = PredictionStore()
+        stream = PredictionStream(self.url, store, reconnect_delay=0.1)
+        stream.start()
+        try:
+            self.assertTrue(self.wait_for(lambda: len(store) == 2))
+        finally:
+            stream.stop()
+
+        config = Config(
+            mode='single-station',
+            station='Oak Grove',
+            station_id='place-ogmnl',
+            routes=[RouteConfig(route_id='Orange', route_name='Orange Line', inbound=3)],
+            display=DisplayConfig(),
+        )
+        mode = SingleStationMode(config, prediction_store=store)
+
+        class NoPolling:
+            def get_predictions_filtered(self, *args, **kwargs):
+                raise AssertionError("should read from the stream")
+
+            def get_alerts(self, *args, **kwargs):
+                return []
+
+        data = mode.gather_data(NoPolling())
+        self.assertEqual(len(data['predictions']), 2)
+        self.assertEqual(data['errors'], [])
+
+
+if __name__ == '__main__':
+    unittest.main()